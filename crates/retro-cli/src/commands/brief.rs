@@ -1,16 +1,17 @@
 use anyhow::Result;
-use retro_core::config::{Config, retro_dir};
+use retro_core::auto::{self, AutoContext, AutoKind};
+use retro_core::config::retro_dir;
 use retro_core::store::{queue, state::RunnerState};
-use retro_core::{briefing, health, observer};
+use retro_core::{briefing, health, observer, version};
 
 /// SessionStart hook entry: catch-up scan + briefing to stdout.
-/// Same never-fail contract as observe.
-pub fn run() -> Result<()> {
+/// Same never-fail contract as observe. `full_scan` ignores the cached
+/// listings of unchanged session directories.
+pub fn run(full_scan: bool) -> Result<()> {
     let dir = retro_dir();
-    if !dir.join("knowledge").exists() {
+    let Some(AutoContext { config }) = auto::begin(&dir, AutoKind::Brief) else {
         return Ok(());
-    }
-    let config = Config::load(&dir.join("config.toml")).unwrap_or_default();
+    };
     let mut state = RunnerState::load(&dir).unwrap_or_default();
 
     // Catch-up: enqueue sessions modified since the watermark (crashed
@@ -29,15 +30,24 @@ pub fn run() -> Result<()> {
     } else {
         None
     };
-    let modified = observer::find_modified_sessions(&config.claude_dir(), since, &[]);
+    // Subagent transcripts (<session>/subagents/agent-*.jsonl) are parts of
+    // their parent session, not sessions — the scan never returns them.
+    let scan_span = retro_core::profile::span("scan");
+    let mut cache = observer::ScanCache::load(&dir);
+    let (modified, scanned) =
+        observer::find_modified_sessions_cached(&config.claude_dir(), since, &mut cache, full_scan);
+    let _ = cache.save(&dir);
+    drop(scan_span);
+    log::debug!(
+        target: retro_core::logging::CLI,
+        "catch-up scan: {} dir(s), {} listed, {} file(s) checked",
+        scanned.dirs,
+        scanned.dirs_listed,
+        scanned.files
+    );
     let mut enqueued = 0usize;
     let mut max_seen = state.last_observed_unix;
     for m in &modified {
-        // Subagent transcripts (<session>/subagents/agent-*.jsonl) are parts
-        // of their parent session, not sessions — never enqueue them.
-        if m.path.components().any(|c| c.as_os_str() == "subagents") {
-            continue;
-        }
         let Some(stem) = m.path.file_stem().and_then(|s| s.to_str()) else {
             continue;
         };
@@ -46,8 +56,13 @@ pub fn run() -> Result<()> {
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
-        if state.processed.get(stem).is_some_and(|&p| mtime_unix <= p) {
-            continue; // already processed at this mtime; margin overlap only
+        // Already processed at this mtime (margin overlap), or touched
+        // without a content change (backup/sync tools bumping mtimes).
+        if state.is_processed_unchanged(stem, mtime_unix, || {
+            retro_core::ingest::content_fingerprint(&m.path).ok()
+        }) {
+            max_seen = max_seen.max(mtime_unix);
+            continue;
         }
         let entry = queue::QueueEntry {
             session_id: stem.to_string(),
@@ -64,31 +79,26 @@ pub fn run() -> Result<()> {
     state.last_observed_unix = max_seen;
 
     // Briefing: drained notifications + current health warnings.
-    let notifications = state.drain_notifications();
-    let warnings = health::Health::load(&dir)
+    let mut notifications = state.drain_notifications();
+    let mut warnings = health::Health::load(&dir)
         .map(|h| h.warnings())
         .unwrap_or_default();
+    warnings.extend(version::store_skew(&dir));
+    // Cached by the last check; the hook itself never goes to the network.
+    if config.updates.check_github {
+        notifications.extend(version::cached_notice(&state));
+    }
     let text = briefing::build_v3_briefing(&notifications, &warnings);
     if !text.is_empty() {
         print!("{text}");
     }
     let _ = state.save(&dir);
-    let _ = health::record(
-        &dir,
-        "brief",
-        true,
-        &format!("caught up {enqueued} session(s)"),
-    );
-
+    let mut detail = format!("caught up {enqueued} session(s)");
     if enqueued > 0 {
-        if let Ok(exe) = std::env::current_exe() {
-            let _ = std::process::Command::new(exe)
-                .args(["run", "--background"])
-                .stdin(std::process::Stdio::null())
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .spawn();
+        if let Some(note) = super::spawn_background_run(&dir, None) {
+            detail.push_str(&format!(" ({note})"));
         }
     }
+    let _ = health::record(&dir, "brief", true, &detail);
     Ok(())
 }
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use retro_core::config::retro_dir;
+
+/// Seeding writes a whole store; only ever into an explicitly chosen
+/// RETRO_HOME, never the default ~/.retro.
+fn isolated_dir() -> Result<std::path::PathBuf> {
+    anyhow::ensure!(
+        std::env::var_os("RETRO_HOME").is_some(),
+        "set RETRO_HOME to a scratch directory first — `retro dev` never touches ~/.retro"
+    );
+    Ok(retro_dir())
+}
+
+pub fn seed(scenario: &str) -> Result<()> {
+    let dir = isolated_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    retro_core::seed::seed(&dir, scenario)?;
+    println!("Seeded `{scenario}` into {}", dir.display());
+    Ok(())
+}
+
+pub fn dump() -> Result<()> {
+    let dir = isolated_dir()?;
+    let value = retro_core::seed::dump(&dir)?;
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(())
+}
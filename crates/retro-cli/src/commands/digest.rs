@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+use retro_core::config::retro_dir;
+use retro_core::digest::{self, DigestInput};
+use retro_core::store::{Store, queue};
+
+/// Render a Markdown digest of the last `since` window (e.g. `7d`, `2w`) to
+/// stdout or `output`. Read-only: safe to run from cron and pipe to `mail`.
+pub fn run(since: &str, output: Option<String>) -> Result<()> {
+    let dir = retro_dir();
+    if !dir.join("knowledge").exists() {
+        anyhow::bail!("retro is not initialized — run `retro init`");
+    }
+    let days = super::parse_window(since)?;
+    let until = chrono::Local::now().date_naive();
+    let since_date = retro_core::util::days_before(until, days);
+
+    let store = Store::open(&dir);
+    let loaded = store.load_all()?;
+    let nodes: Vec<_> = loaded.nodes.into_iter().map(|(_, n)| n).collect();
+    let subjects = retro_core::store::git::subjects_since(&dir, &since_date.to_string())?;
+    let queued = queue::list(&dir).map(|q| q.len()).unwrap_or(0);
+    let warnings = retro_core::health::Health::load(&dir)?.warnings();
+    let state = retro_core::store::state::RunnerState::load(&dir)?;
+
+    let markdown = digest::render_markdown(&DigestInput {
+        since: since_date,
+        until,
+        nodes: &nodes,
+        commit_subjects: &subjects,
+        queued_sessions: queued,
+        health_warnings: &warnings,
+        config_changes: &state.config_changes,
+    });
+    match output {
+        Some(path) => {
+            std::fs::write(&path, markdown).with_context(|| format!("writing {path}"))?;
+            println!("Digest written to {path}");
+        }
+        None => print!("{markdown}"),
+    }
+    Ok(())
+}
@@ -3,13 +3,19 @@ use colored::Colorize;
 use retro_core::config::{Config, retro_dir};
 use retro_core::doctor;
 
-/// End-to-end v3 health verification. Read-only; the claude CLI probe is
-/// a --version subprocess (no tokens).
-pub fn run() -> Result<()> {
+/// End-to-end v3 health verification. Read-only (except `--repair-sources`);
+/// the claude CLI probe is a --version subprocess (no tokens).
+pub fn run(verify_content: bool, json: bool, repair_sources: bool) -> Result<()> {
     let dir = retro_dir();
     if !dir.join("knowledge").exists() {
         anyhow::bail!("retro is not initialized — run `retro init`");
     }
+    if verify_content {
+        return run_verify_content(&dir, json);
+    }
+    if repair_sources {
+        return run_repair_sources(&dir);
+    }
     let config = Config::load(&dir.join("config.toml"))?;
     let report = doctor::run_checks(&dir, &config, true);
     for check in &report.checks {
@@ -24,3 +30,97 @@ pub fn run() -> Result<()> {
         std::process::exit(1);
     }
 }
+
+/// `retro doctor --verify-content`: compare each managed block with what
+/// retro last wrote there. Report-only; exits 1 when content was injected.
+fn run_verify_content(dir: &std::path::Path, json: bool) -> Result<()> {
+    use retro_core::projection::integrity::{self, ContentStatus};
+
+    let checks = integrity::verify(dir);
+    let injected = checks
+        .iter()
+        .any(|c| matches!(c.status, ContentStatus::Injected { .. }));
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else if checks.is_empty() {
+        println!("  no projections recorded yet — run `retro run` first");
+    } else {
+        for c in &checks {
+            let path = c.path.display();
+            match &c.status {
+                ContentStatus::Untouched => println!("  {} {path}", "✓".green()),
+                ContentStatus::UserEdited { edited, removed } => println!(
+                    "  {} {path}: edited by hand ({} reworded, {} removed) — the next projection restores retro's rules",
+                    "~".yellow(),
+                    edited.len(),
+                    removed.len()
+                ),
+                ContentStatus::Injected { lines, .. } => {
+                    println!(
+                        "  {} {path}: {} line(s) inside the managed block were not written by retro:",
+                        "✗".red(),
+                        lines.len()
+                    );
+                    for line in lines {
+                        println!("      {line}");
+                    }
+                }
+                ContentStatus::MarkersRemoved => {
+                    println!("  {} {path}: managed markers removed", "~".yellow())
+                }
+                ContentStatus::FileMissing => println!("  {} {path}: file missing", "~".yellow()),
+            }
+        }
+    }
+    if injected {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `retro doctor --repair-sources`: rewrite malformed `sources:` lines under
+/// the run lock, then the usual post-write discipline: commit (restoring
+/// the files if that fails), reindex, reproject.
+fn run_repair_sources(dir: &std::path::Path) -> Result<()> {
+    use retro_core::store::{Store, git as store_git, index, repair};
+
+    let config = Config::load(&dir.join("config.toml"))?;
+    let lock_path = dir.join("run.lock");
+    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&lock_path) else {
+        anyhow::bail!("{}", super::in_progress(&lock_path));
+    };
+    let store = Store::open(dir);
+    let repairs = repair::scan(&store)?;
+    if repairs.is_empty() {
+        println!("  {} every sources line is well-formed", "✓".green());
+        return Ok(());
+    }
+    for r in &repairs {
+        println!("  {} {}", "~".yellow(), retro_core::util::shorten_path_buf(&r.path));
+        println!("      {}", r.notes.join("; "));
+        println!("      {} → {}", r.before.trim_end(), r.after.trim_end());
+    }
+    let paths: Vec<_> = repairs.iter().map(|r| r.path.clone()).collect();
+    let rollback = store_git::Rollback::capture(&paths);
+    let written = repair::apply(&repairs).map_err(|e| rollback.undo(e))?;
+    store_git::commit_or_rollback(
+        dir,
+        &format!("user: repair sources in {written} node(s)"),
+        &rollback,
+    )?;
+    if let Err(e) = index::build(&store) {
+        retro_core::health::record(dir, "index", false, &e.to_string())?;
+    }
+    let report = retro_core::projection::reproject::reproject(
+        &store,
+        &config,
+        None,
+        Some(&dir.join("backups")),
+    )?;
+    for line in report.skipped() {
+        println!("  not reprojected — {line}");
+    }
+    println!("
+Repaired {written} node file(s); the old lines are in the store's git history.");
+    Ok(())
+}
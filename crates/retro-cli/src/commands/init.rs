@@ -11,6 +11,12 @@ pub fn run(from: Option<String>) -> Result<()> {
     use retro_core::store::{git as store_git, index, Store};
 
     let dir = retro_dir();
+    // Fail up front, naming the path, rather than half-way through with a
+    // bare "Permission denied" (unwritable HOME, read-only ~/.claude).
+    retro_core::util::check_writable_dir(&dir)?;
+    if let Ok(config) = Config::load(&dir.join("config.toml")) {
+        retro_core::util::check_writable_dir(&config.claude_dir())?;
+    }
 
     if let Some(remote) = from.as_deref() {
         // Clone path: target must not already be a store.
@@ -35,6 +41,7 @@ pub fn run(from: Option<String>) -> Result<()> {
             .args(["clone", remote, &dir.display().to_string()])
             .status()?;
         anyhow::ensure!(status.success(), "git clone failed");
+        retro_core::store::meta::check_compatible(&dir)?;
         // Clone bypasses ensure_repo's create branch: apply local config explicitly.
         store_git::apply_local_config(&dir)?;
         println!("Cloned knowledge store from {remote}");
@@ -45,11 +52,14 @@ pub fn run(from: Option<String>) -> Result<()> {
     // migrate and the dashboard write handlers. Fresh machines have no
     // retro dir yet, and the lock file needs a parent to live in.
     std::fs::create_dir_all(&dir)?;
-    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&dir.join("run.lock")) else {
-        anyhow::bail!("a retro run is in progress — retry shortly");
+    let lock_path = dir.join("run.lock");
+    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&lock_path) else {
+        anyhow::bail!("{}", super::in_progress(&lock_path));
     };
     let store = Store::open(&dir);
     store.ensure_layout()?; // BEFORE ensure_repo — see doc comment
+    retro_core::store::meta::check_compatible(&dir)?;
+    retro_core::store::meta::stamp(&dir)?;
     let created = store_git::ensure_repo(&dir)?;
     if created {
         println!("Initialized knowledge store repo at {}", dir.display());
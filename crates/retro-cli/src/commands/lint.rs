@@ -2,17 +2,23 @@ use anyhow::Result;
 use colored::Colorize;
 use retro_core::config::{Config, retro_dir};
 use retro_core::lint;
-use retro_core::store::{Store, state::RunnerState};
+use retro_core::projection::reproject::reproject;
+use retro_core::store::{Scope, Store, git as store_git, index, state::RunnerState};
 
 /// Free lint pass (no AI calls). Without --dry-run, findings are also pushed
 /// as briefing notifications (capped) so they surface in the next session.
-pub fn run(dry_run: bool) -> Result<()> {
+/// `--reconcile` first retires nodes already written by hand in the file
+/// they would project next to.
+pub fn run(dry_run: bool, reconcile: bool) -> Result<()> {
     let dir = retro_dir();
     if !dir.join("knowledge").exists() {
         anyhow::bail!("retro is not initialized — run `retro init`");
     }
     let config = Config::load(&dir.join("config.toml"))?;
     let store = Store::open(&dir);
+    if reconcile {
+        reconcile_external(&dir, &store, &config)?;
+    }
     let report = lint::run_lint(&store, &config)?;
     println!(
         "Scanned {} active node(s): {} finding(s)",
@@ -22,6 +28,14 @@ pub fn run(dry_run: bool) -> Result<()> {
     for f in &report.findings {
         println!("  {} {}", format!("[{}]", f.kind).yellow(), f.detail);
     }
+    // What projection would write, checked before it lands. Advisory unless
+    // [knowledge] strict_lint, where the runner skips the affected file.
+    for (path, findings) in lint::lint_projections(&store, &config)? {
+        println!("{}:", retro_core::util::shorten_path_buf(&path));
+        for f in findings {
+            println!("  {} {}", format!("[{}]", f.kind).yellow(), f.detail);
+        }
+    }
     if !dry_run && !report.findings.is_empty() {
         // state.json writes require the run lock (same discipline as the
         // dashboard write handlers) — a load-modify-save racing a runner
@@ -44,3 +58,56 @@ pub fn run(dry_run: bool) -> Result<()> {
     }
     Ok(())
 }
+
+/// Retire in-repo duplicates under the run lock, then the usual post-write
+/// discipline: commit (restoring the nodes if that fails), reindex,
+/// reproject the affected scopes.
+fn reconcile_external(dir: &std::path::Path, store: &Store, config: &Config) -> Result<()> {
+    let lock_path = dir.join("run.lock");
+    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&lock_path) else {
+        anyhow::bail!("{}", super::in_progress(&lock_path));
+    };
+    let retired = lint::external_duplicates(store, config)?;
+    if retired.is_empty() {
+        println!("No nodes matched rules already written in CLAUDE.md.");
+        return Ok(());
+    }
+    println!(
+        "{} node(s) matched rules already written in CLAUDE.md — retired:",
+        retired.len()
+    );
+    for m in &retired {
+        println!(
+            "  {} ({}) — {}",
+            m.node_id,
+            m.scope,
+            retro_core::util::shorten_path_buf(&m.path)
+        );
+    }
+    let touched: Vec<_> = retired
+        .iter()
+        .map(|m| store.node_path(&m.scope, &m.node_id))
+        .collect();
+    let rollback = store_git::Rollback::capture(&touched);
+    lint::retire_external(store, &retired).map_err(|e| rollback.undo(e))?;
+    store_git::commit_or_rollback(
+        dir,
+        &format!("user: retire {} node(s) already in CLAUDE.md", retired.len()),
+        &rollback,
+    )?;
+    if let Err(e) = index::build(store) {
+        retro_core::health::record(dir, "index", false, &e.to_string())?;
+    }
+    let mut scopes: Vec<Scope> = Vec::new();
+    for m in retired {
+        if !scopes.contains(&m.scope) {
+            scopes.push(m.scope);
+        }
+    }
+    let report = reproject(store, config, Some(&scopes), Some(&dir.join("backups")))?;
+    for line in report.skipped() {
+        println!("  not reprojected — {line}");
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use colored::Colorize;
+use retro_core::config::retro_dir;
+use retro_core::projection::writes::{self, DiffLine, FileState};
+use retro_core::util::{fmt_rfc3339, shorten_path};
+
+/// What retro wrote into projected files. Entries are numbered oldest first;
+/// `show_files` prints one entry's file against what is on disk now, with a
+/// diff when a snapshot was kept (`[log] store_apply_snapshots`).
+pub fn run(show_files: Option<usize>, limit: usize) -> Result<()> {
+    let dir = retro_dir();
+    if !dir.join("knowledge").exists() {
+        anyhow::bail!("retro is not initialized — run `retro init`");
+    }
+    let records = writes::load(&dir);
+    if let Some(n) = show_files {
+        let Some(record) = n.checked_sub(1).and_then(|i| records.get(i)) else {
+            anyhow::bail!("no entry {n} — `retro log` lists {} entr(ies)", records.len());
+        };
+        return show(&dir, n, record);
+    }
+    if records.is_empty() {
+        println!("no projected files recorded yet");
+        return Ok(());
+    }
+    let skip = records.len().saturating_sub(limit);
+    for (i, r) in records.iter().enumerate().skip(skip) {
+        let snapshot = if r.snapshot { " [snapshot]" } else { "" };
+        println!(
+            "{:>4}  {}  {}  {} bytes  {}{snapshot}",
+            i + 1,
+            fmt_rfc3339(&r.at, true),
+            shorten_path(&r.path),
+            r.bytes,
+            &r.digest[..8.min(r.digest.len())]
+        );
+    }
+    println!("\n  retro log --show-files <entry> — compare an entry with the file now");
+    Ok(())
+}
+
+fn show(dir: &std::path::Path, n: usize, r: &writes::WriteRecord) -> Result<()> {
+    println!("{} {n}: written {}", "Entry".bold(), fmt_rfc3339(&r.at, false));
+    println!("  {}", shorten_path(&r.path));
+    println!("    written: {} bytes, digest {}", r.bytes, r.digest);
+    if !r.git.is_empty() {
+        let short = |c: &Option<String>| {
+            c.as_deref().map(|c| c[..12.min(c.len())].to_string()).unwrap_or_else(|| "-".into())
+        };
+        let repo = match (&r.git.branch, &r.git.head) {
+            (None, None) => "not in a repository".to_string(),
+            (branch, _) => format!(
+                "{} at {}",
+                branch.as_deref().unwrap_or("detached HEAD"),
+                short(&r.git.head)
+            ),
+        };
+        println!("    git:     store {}; repo {repo}", short(&r.git.store_commit));
+    }
+    let state = writes::current_state(r);
+    match &state {
+        FileState::Unchanged => println!("    now:     {}", "unchanged".green()),
+        FileState::Changed { digest, bytes } => {
+            println!("    now:     {} ({bytes} bytes, digest {digest})", "changed".yellow())
+        }
+        FileState::Missing => println!("    now:     {}", "missing".red()),
+    }
+    if !matches!(state, FileState::Changed { .. }) {
+        return Ok(());
+    }
+    let Some(written) = writes::snapshot(dir, r) else {
+        println!("    (no snapshot — set [log] store_apply_snapshots = true to diff later writes)");
+        return Ok(());
+    };
+    let current = std::fs::read_to_string(&r.path)?;
+    println!("\n  --- written by retro\n  +++ on disk now");
+    for line in writes::line_diff(&written, &current) {
+        match line {
+            DiffLine::Same(_) => {}
+            DiffLine::Removed(l) => println!("  {}", format!("-{l}").red()),
+            DiffLine::Added(l) => println!("  {}", format!("+{l}").green()),
+        }
+    }
+    Ok(())
+}
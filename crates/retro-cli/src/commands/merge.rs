@@ -0,0 +1,63 @@
+use anyhow::Result;
+use retro_core::config::{Config, retro_dir};
+use retro_core::store::merge::{MergeOptions, merge_nodes, resolve};
+use retro_core::store::{Store, git as store_git, index};
+
+/// Manually merge duplicate nodes: the survivor absorbs the others' sources
+/// and confidence, the rest are invalidated in its favour. Same post-write
+/// discipline as the dashboard handlers: commit, reindex, reproject.
+pub fn run(nodes: Vec<String>, into: Option<String>, keep_both_bodies: bool, force: bool) -> Result<()> {
+    let dir = retro_dir();
+    if !dir.join("knowledge").exists() {
+        anyhow::bail!("retro is not initialized — run `retro init`");
+    }
+    let config = Config::load(&dir.join("config.toml"))?;
+    let lock_path = dir.join("run.lock");
+    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&lock_path) else {
+        anyhow::bail!("{}", super::in_progress(&lock_path));
+    };
+    let store = Store::open(&dir);
+    let opts = MergeOptions {
+        into,
+        keep_both_bodies,
+        force,
+    };
+    // Snapshot every file the merge can touch, so a failed merge or commit
+    // restores them instead of leaving an uncommitted, unprojected merge
+    // behind. Only these files are committed: an unrelated hand edit stays
+    // for the next run to commit as the user's.
+    let mut touched = Vec::new();
+    for r in nodes.iter().chain(opts.into.iter()) {
+        let node = resolve(&store, r)?;
+        touched.push(store.node_path(&node.scope, &node.id));
+    }
+    let rollback = store_git::Rollback::capture(&touched);
+    let outcome = merge_nodes(&store, &nodes, &opts).map_err(|e| rollback.undo(e))?;
+    let merged: Vec<&str> = outcome.merged.iter().map(|n| n.id.as_str()).collect();
+    store_git::commit_or_rollback(
+        &dir,
+        &format!("user: merge {} into {}", merged.join(", "), outcome.survivor.id),
+        &rollback,
+    )?;
+    if let Err(e) = index::build(&store) {
+        retro_core::health::record(&dir, "index", false, &e.to_string())?;
+    }
+    let report = retro_core::projection::reproject::reproject(
+        &store,
+        &config,
+        Some(&outcome.affected_scopes()),
+        Some(&dir.join("backups")),
+    )?;
+    println!(
+        "Merged {} into {} ({}, confidence {:.2}, {} source(s))",
+        merged.join(", "),
+        outcome.survivor.id,
+        outcome.survivor.scope,
+        outcome.survivor.confidence,
+        outcome.survivor.sources.len()
+    );
+    for line in report.skipped() {
+        println!("  not reprojected — {line}");
+    }
+    Ok(())
+}
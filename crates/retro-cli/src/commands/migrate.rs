@@ -12,9 +12,10 @@ pub fn run(dry_run: bool) -> Result<()> {
     let _lock = if dry_run {
         None
     } else {
-        match retro_core::lock::LockFile::try_acquire(&dir.join("run.lock")) {
+        let lock_path = dir.join("run.lock");
+        match retro_core::lock::LockFile::try_acquire(&lock_path) {
             Some(l) => Some(l),
-            None => anyhow::bail!("a retro run is in progress — retry shortly"),
+            None => anyhow::bail!("{}", super::in_progress(&lock_path)),
         }
     };
     let store = Store::open(&dir);
@@ -114,25 +115,22 @@ pub fn run(dry_run: bool) -> Result<()> {
     } else {
         retro_core::store::git::commit_all(&dir, "retro: migrate v2 knowledge")?;
         retro_core::store::index::build(&store)?;
-        let threshold = config.knowledge.confidence_threshold;
-        let global_md = config.claude_dir().join("CLAUDE.md");
-        let rules = retro_core::projection::local_md::project_global_md(
+        let report = retro_core::projection::reproject::reproject(
             &store,
-            &global_md,
-            threshold,
+            &config,
+            None,
             Some(&dir.join("backups")),
         )?;
-        println!("  projected {rules} rule(s) to {}", global_md.display());
-        if let Ok(map) = retro_core::store::projects::PathMap::load(&dir) {
-            for (slug, path) in &map.paths {
-                let n = retro_core::projection::local_md::project_local_md(
-                    &store,
-                    slug,
-                    std::path::Path::new(path),
-                    threshold,
-                )?;
-                println!("  projected {n} rule(s) to {path}/CLAUDE.local.md");
-            }
+        println!(
+            "  projected {} rule(s) to {}",
+            report.global_rules.unwrap_or(0),
+            config.claude_dir().join("CLAUDE.md").display()
+        );
+        for (_, root, n) in &report.projects {
+            println!("  projected {n} rule(s) to {}/CLAUDE.local.md", root.display());
+        }
+        for line in report.skipped() {
+            println!("  not projected — {line}");
         }
     }
 
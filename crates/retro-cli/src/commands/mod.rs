@@ -1,14 +1,53 @@
+#[cfg(feature = "dev-tools")]
+pub mod dev;
+pub mod digest;
 pub mod doctor;
 pub mod init;
 pub mod lint;
+pub mod log;
+pub mod merge;
 pub mod migrate;
 pub mod observe;
 pub mod brief;
 pub mod reindex;
+pub mod relocate;
 pub mod run;
+pub mod stats;
 pub mod status;
 pub mod ui;
 pub mod uninstall;
+pub mod version;
+
+/// Spawn a detached `retro run --background` for a hook event in `cwd`,
+/// unless `throttle::claim_spawn` coalesces or defers it. Returns a note for
+/// the hook's health record when no run was spawned. The child inherits this
+/// process's environment (auth works here — the whole point of hook-time
+/// capture); spawn errors are ignored, the next hook spawns again.
+pub fn spawn_background_run(
+    dir: &std::path::Path,
+    cwd: Option<&std::path::Path>,
+) -> Option<String> {
+    use retro_core::throttle::{SpawnDecision, claim_spawn};
+    match claim_spawn(dir, cwd, chrono::Utc::now()) {
+        Ok(SpawnDecision::Coalesced(age)) => {
+            return Some(format!("run coalesced with one spawned {age}s ago"));
+        }
+        Ok(SpawnDecision::RepoBusy(repo, op)) => {
+            return Some(format!("run deferred: {op} in progress in {}", repo.display()));
+        }
+        // Unreadable state must not stop capture — spawn anyway.
+        Ok(SpawnDecision::Spawn) | Err(_) => {}
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        let _ = std::process::Command::new(exe)
+            .args(["run", "--background"])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+    }
+    None
+}
 
 /// Check for v3 pipeline health/queue issues and display a status block.
 /// Silently does nothing if the store isn't initialized or any error occurs.
@@ -30,17 +69,59 @@ pub fn check_and_display_nudge() {
             let oldest = &entries[0].enqueued_at;
             let stale = chrono::DateTime::parse_from_rfc3339(oldest)
                 .map(|t| {
-                    chrono::Utc::now().signed_duration_since(t) > chrono::Duration::hours(24)
+                    let t = t.with_timezone(&chrono::Utc);
+                    retro_core::util::elapsed(t, chrono::Utc::now()) > chrono::Duration::hours(24)
                 })
                 .unwrap_or(false);
-            if stale {
+            // Below [analysis] min_sessions_for_auto the hooks are waiting
+            // on purpose: say so instead of suggesting something is stuck.
+            let min = retro_core::config::Config::load_or_default(&dir.join("config.toml"))
+                .analysis
+                .min_sessions_for_auto;
+            if stale && entries.len() < min {
+                use colored::Colorize;
+                let waiting = retro_core::auto::Skip::BelowMinSessions {
+                    queued: entries.len(),
+                    min,
+                };
+                eprintln!(
+                    "  {} {waiting}, oldest {} — `retro run` analyzes them now",
+                    "retro:".yellow(),
+                    retro_core::util::fmt_rfc3339(oldest, true)
+                );
+            } else if stale {
                 use colored::Colorize;
                 eprintln!(
-                    "  {} {} session(s) queued (oldest > 24h) — run `retro run` or `retro doctor`",
+                    "  {} {} session(s) queued (oldest {}) — run `retro run` or `retro doctor`",
                     "retro:".yellow(),
-                    entries.len()
+                    entries.len(),
+                    retro_core::util::fmt_rfc3339(oldest, true)
                 );
             }
         }
     }
 }
+
+/// Refusal message when `run.lock` at `lock_path` is taken: names the
+/// holder (PID and user) so a shared machine shows whose run is in the way.
+pub fn in_progress(lock_path: &std::path::Path) -> String {
+    match retro_core::lock::holder(lock_path) {
+        Some(holder) => format!("a retro run is in progress ({holder}) — retry shortly"),
+        None => "a retro run is in progress — retry shortly".to_string(),
+    }
+}
+
+/// `7d`, `2w`, or a bare number of days.
+pub fn parse_window(s: &str) -> anyhow::Result<i64> {
+    let s = s.trim();
+    let (num, mult) = match s.strip_suffix('w') {
+        Some(n) => (n, 7),
+        None => (s.strip_suffix('d').unwrap_or(s), 1),
+    };
+    match num.parse::<i64>() {
+        Ok(n) if n > 0 => n
+            .checked_mul(mult)
+            .ok_or_else(|| anyhow::anyhow!("--since value `{s}` is too large")),
+        _ => anyhow::bail!("invalid --since value `{s}` (expected e.g. 7d or 2w)"),
+    }
+}
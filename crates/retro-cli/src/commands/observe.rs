@@ -1,6 +1,7 @@
 use std::io::Read;
 
 use anyhow::Result;
+use retro_core::auto::{self, AutoContext, AutoKind};
 use retro_core::config::{Config, retro_dir};
 use retro_core::health;
 use retro_core::hook_event::HookEvent;
@@ -11,16 +12,17 @@ use retro_core::store::{Store, projects, queue};
 enum ObserveOutcome {
     Enqueued,
     Excluded,
+    /// The session's checkout belongs to this uid, not to us.
+    SharedCheckout(u32),
 }
 
 /// SessionEnd hook entry. Contract: NEVER fail the hook — errors are recorded
 /// in health and swallowed; stdout stays clean; exit code is always 0.
 pub fn run() -> Result<()> {
     let dir = retro_dir();
-    if !dir.join("knowledge").exists() {
+    let Some(AutoContext { config }) = auto::begin(&dir, AutoKind::Observe) else {
         return Ok(());
-    }
-    let config = Config::load(&dir.join("config.toml")).unwrap_or_default();
+    };
     let mut input = String::new();
     let _ = std::io::stdin().read_to_string(&mut input);
     let Some(event) = HookEvent::parse(&input) else {
@@ -39,14 +41,27 @@ pub fn run() -> Result<()> {
                 &format!("excluded {}", event.session_id),
             );
         }
-        Ok(ObserveOutcome::Enqueued) => {
+        Ok(ObserveOutcome::SharedCheckout(owner)) => {
             let _ = health::record(
                 &dir,
                 "observe",
                 true,
-                &format!("enqueued {}", event.session_id),
+                &format!(
+                    "skipped {}: checkout owned by {} — set [hooks] allow_shared_checkout = true \
+                     to observe it",
+                    event.session_id,
+                    retro_core::user::name_of(owner)
+                ),
             );
-            spawn_worker();
+        }
+        Ok(ObserveOutcome::Enqueued) => {
+            let cwd =
+                Some(std::path::Path::new(&event.cwd)).filter(|_| !event.cwd.is_empty());
+            let mut detail = format!("enqueued {}", event.session_id);
+            if let Some(note) = super::spawn_background_run(&dir, cwd) {
+                detail.push_str(&format!(" ({note})"));
+            }
+            let _ = health::record(&dir, "observe", true, &detail);
         }
     }
     Ok(())
@@ -59,9 +74,19 @@ fn observe_event(
 ) -> Result<ObserveOutcome, retro_core::errors::CoreError> {
     if projects::is_excluded(&event.cwd, &config.privacy.exclude_projects)
         || projects::is_store_dir(dir, &event.cwd)
+        || projects::opt_out_marker(&event.cwd).is_some()
     {
         return Ok(ObserveOutcome::Excluded);
     }
+    // Another user's checkout (shared deploy checkout): both users' runs
+    // would race on its CLAUDE.local.md.
+    if !config.hooks.allow_shared_checkout {
+        let cwd = std::path::Path::new(&event.cwd);
+        let me = retro_core::user::effective_uid();
+        if let Some(owner) = retro_core::user::foreign_owner(cwd, me) {
+            return Ok(ObserveOutcome::SharedCheckout(owner));
+        }
+    }
     let store = Store::open(dir);
     store.ensure_layout()?;
 
@@ -97,17 +122,3 @@ fn observe_event(
     state.save(dir)?;
     Ok(ObserveOutcome::Enqueued)
 }
-
-/// Detached background worker; inherits this process's environment (auth works
-/// here — the whole point of hook-time capture). Errors ignored: the next
-/// observe/brief will spawn again.
-fn spawn_worker() {
-    if let Ok(exe) = std::env::current_exe() {
-        let _ = std::process::Command::new(exe)
-            .args(["run", "--background"])
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn();
-    }
-}
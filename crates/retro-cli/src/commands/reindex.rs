@@ -3,8 +3,9 @@ use colored::Colorize;
 use retro_core::config::retro_dir;
 use retro_core::store::{Store, index};
 
-/// Rebuild the v3 store index (`index.db`) from the markdown files.
-/// The index is disposable — this is always safe to run.
+/// Rebuild the v3 store index (`index.db`) from the markdown files, and
+/// drop tool-log entries for nodes whose file is gone. Both are derived
+/// state — this is always safe to run.
 ///
 /// Exit 0 even with warnings: the index was built; warnings identify
 /// individual skipped files.
@@ -33,5 +34,13 @@ pub fn run() -> Result<()> {
         store.knowledge_dir().display(),
         warn_suffix
     );
+    let orphans = retro_core::store::tools::prune(&store)?;
+    if !orphans.is_empty() {
+        println!(
+            "Dropped {} tool-log entr(ies) for deleted nodes: {}",
+            orphans.len(),
+            orphans.join(", ")
+        );
+    }
     Ok(())
 }
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use retro_core::config::{Config, retro_dir};
+use retro_core::store::{Scope, Store, projects};
+
+/// Re-point a moved project (by old path or slug) at its new directory, then
+/// reproject its CLAUDE.local.md there. Knowledge is keyed by slug, so only
+/// machine-local state changes; nothing is committed.
+pub fn run(old: &str, new: &str, dry_run: bool) -> Result<()> {
+    let dir = retro_dir();
+    if !dir.join("knowledge").exists() {
+        anyhow::bail!("retro is not initialized — run `retro init`");
+    }
+    let config = Config::load(&dir.join("config.toml"))?;
+    let lock_path = dir.join("run.lock");
+    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&lock_path) else {
+        anyhow::bail!("{}", super::in_progress(&lock_path));
+    };
+    let store = Store::open(&dir);
+    let moved = projects::relocate(&store, old, new, dry_run)?;
+    if moved.projects.is_empty() {
+        anyhow::bail!("no registered project at `{old}` (give its old path or its slug)");
+    }
+    let verb = if dry_run { "would move" } else { "moved" };
+    for (slug, path) in &moved.projects {
+        println!("{slug}: {verb} {path} -> {new}");
+    }
+    println!(
+        "  {} projection record(s), {} history-only prompt(s) re-pointed",
+        moved.projection_records, moved.history_records
+    );
+    if dry_run {
+        return Ok(());
+    }
+    let scopes: Vec<Scope> = moved
+        .projects
+        .iter()
+        .map(|(slug, _)| Scope::Project(slug.clone()))
+        .collect();
+    let report =
+        retro_core::projection::reproject::reproject(&store, &config, Some(&scopes), None)?;
+    for (slug, root, n) in &report.projects {
+        println!("  {slug}: {n} rule(s) projected to {}", root.join("CLAUDE.local.md").display());
+    }
+    Ok(())
+}
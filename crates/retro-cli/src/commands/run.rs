@@ -1,15 +1,83 @@
 use anyhow::Result;
+use retro_core::analysis::claude_cli;
+use retro_core::analysis::metrics::MeteredBackend;
+use retro_core::auto::{self, AutoContext, AutoKind, Skip};
 use retro_core::config::{retro_dir, Config};
+use retro_core::runner_v3::ProjectRunSummary;
 
 /// Run the v3 pipeline: drain queue -> analyze -> project -> commit -> push.
-pub fn run(_verbose: bool, dry_run: bool, background: bool) -> Result<()> {
+pub fn run(
+    dry_run: bool,
+    background: bool,
+    heuristic: bool,
+    include_ignored: bool,
+    include_history: bool,
+    limit: Option<u32>,
+) -> Result<()> {
     let dir = retro_dir();
-    let config = Config::load(&dir.join("config.toml"))?;
-    let backend = retro_core::analysis::claude_cli::ClaudeCliBackend::new(&config.ai);
-    let summary = retro_core::runner_v3::run_v3(&dir, &config, &backend, dry_run)?;
+    if background && dry_run {
+        print_auto_trace(&dir);
+        return Ok(());
+    }
+    let mut config = if background {
+        let Some(AutoContext { config }) = auto::begin(&dir, AutoKind::Run) else {
+            return Ok(());
+        };
+        config
+    } else {
+        // Without this the lock acquisition fails and the run misreports
+        // "another retro run is in progress".
+        retro_core::util::check_writable_dir(&dir)?;
+        Config::load(&dir.join("config.toml"))?
+    };
+    let base = config.clone();
+    if heuristic {
+        config.ai.backend = "heuristic".to_string();
+    }
+    if include_ignored {
+        config.ingest.ignore_session_if.clear();
+    }
+    if include_history {
+        config.ingest.include_history = true;
+    }
+    if let Some(n) = limit {
+        config.analysis.max_new_nodes_per_run = n as usize;
+    }
+    // Catch a mistyped [ai].model before any prompt is built — only when
+    // there is queued work to analyze (the check itself is a ping).
+    let has_work = config.ingest.include_history
+        || retro_core::store::queue::list(&dir).is_ok_and(|q| !q.is_empty());
+    let model_check = if !dry_run && has_work && config.ai.backend != "heuristic" {
+        claude_cli::validate_model_cached(
+            &dir,
+            &config.ai.model,
+            config.runner.max_ai_calls_per_day,
+        )
+    } else {
+        Ok(())
+    };
+    if let Err(e) = model_check {
+        let _ = retro_core::health::record(&dir, "analyze", false, &e.to_string());
+        return Err(e.into());
+    }
+    let cli_backend = claude_cli::ClaudeCliBackend::new(&config.ai);
+    // Per-call duration/token records for `retro status --ai`.
+    let backend = MeteredBackend::new(&cli_backend, &dir, &config.ai.model);
+    let summary = retro_core::runner_v3::run_v3_with_base(&dir, &config, &base, &backend, dry_run)?;
+    if !dry_run {
+        // Opt-in, at most daily, failure-silent; the briefing shows the result.
+        let _ = retro_core::version::check_for_update(
+            &dir,
+            &config,
+            chrono::Utc::now(),
+            retro_core::version::fetch_latest_release,
+        );
+    }
     match summary {
         None => {
-            if !background {
+            if background {
+                auto::log_skip(AutoKind::Run, &Skip::Locked);
+            } else {
                 println!("Another retro run is in progress — skipped.");
             }
         }
@@ -21,21 +89,146 @@ pub fn run(_verbose: bool, dry_run: bool, background: bool) -> Result<()> {
                     } else {
                         String::new()
                     };
+                    let ignored = if s.sessions_ignored > 0 {
+                        format!(", {} ignored by [ingest] rules", s.sessions_ignored)
+                    } else {
+                        String::new()
+                    };
                     println!(
-                        "v3 dry run: {} session(s) pending, {} skipped{stale} — no AI calls, no writes",
+                        "v3 dry run: {} session(s) pending, {} skipped{stale}{ignored} — no AI calls, no writes",
                         s.sessions_pending, s.sessions_skipped
                     );
                 } else {
                     println!(
-                        "v3 run: {} session(s) analyzed ({} AI call(s)) — +{} nodes, {} updated, {} merged, {} invalidated; {} global rule(s) projected{}{}",
+                        "v3 run: {} session(s) analyzed ({} AI call(s)) — +{} nodes, {} updated, {} merged, {} invalidated; {} global rule(s) projected{}{}{}{}",
                         s.sessions_processed, s.ai_calls, s.nodes_created, s.nodes_updated,
                         s.nodes_merged, s.nodes_invalidated, s.rules_projected_global,
                         if s.sessions_pending > 0 { format!("; {} pending (budget)", s.sessions_pending) } else { String::new() },
                         if s.ops_skipped > 0 { format!("; {} op(s) skipped", s.ops_skipped) } else { String::new() },
+                        if s.candidates_deferred > 0 { format!("; {} candidate(s) deferred (cap {})", s.candidates_deferred, config.analysis.max_new_nodes_per_run) } else { String::new() },
+                        if s.orphaned_updates > 0 { format!("; {} update(s) to unknown nodes", s.orphaned_updates) } else { String::new() },
                     );
+                    if !s.deferred.is_empty() {
+                        println!("  deferred until re-observed (most confident first):");
+                        for (i, body) in s.deferred.iter().enumerate() {
+                            let first_line = body.lines().next().unwrap_or(body);
+                            println!(
+                                "    {}. {}",
+                                i + 1,
+                                retro_core::util::truncate_str(first_line, 100)
+                            );
+                        }
+                    }
+                    if s.projects.len() > 1 || s.projects.iter().any(|p| p.error.is_some()) {
+                        print_project_table(&s.projects);
+                    }
+                }
+                if s.sessions_processed == 0 && s.sessions_pending == 0 {
+                    print_nothing_to_do_hint(&dir, &config);
                 }
             }
         }
     }
     Ok(())
 }
+
+/// `retro run --background --dry-run`: what a hook firing in the current
+/// directory would do, gate by gate. Nothing is spawned, claimed or locked.
+fn print_auto_trace(dir: &std::path::Path) {
+    use colored::Colorize;
+
+    let cwd = std::env::current_dir().ok();
+    let gates = auto::trace(dir, cwd.as_deref(), chrono::Utc::now());
+    for g in &gates {
+        let mark = if g.passed { "✓".green() } else { "✗".red() };
+        println!("  {mark} {:<7} {}", format!("{}:", g.name), g.detail);
+    }
+    match gates.last() {
+        Some(last) if !last.passed && last.name == "budget" => println!(
+            "verdict: would run — project and commit, but analyze nothing until the budget resets"
+        ),
+        Some(last) if !last.passed => println!("verdict: would stop at `{}`", last.name),
+        _ => println!("verdict: would run — `retro run --dry-run` previews the sessions"),
+    }
+}
+
+/// Explain an empty run from the current directory's point of view: Claude
+/// Code never used here, or every session already analyzed, or sessions
+/// that predate retro's capture.
+fn print_nothing_to_do_hint(dir: &std::path::Path, config: &Config) {
+    use retro_core::ingest::diagnose::{Diagnosis, project_sessions};
+
+    let Ok(cwd) = std::env::current_dir() else {
+        return;
+    };
+    let cwd = cwd.display().to_string();
+    if retro_core::store::projects::is_store_dir(dir, &cwd) {
+        return;
+    }
+    let state = retro_core::store::state::RunnerState::load(dir).unwrap_or_default();
+    let found = project_sessions(&config.claude_dir(), &cwd, &state);
+    let date = |unix: Option<u64>| {
+        unix.and_then(|u| chrono::DateTime::from_timestamp(u as i64, 0))
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    match found.diagnosis() {
+        Diagnosis::NoSessionsDir => println!(
+            "  no Claude Code sessions for {cwd} (expected {}) — check the project path; retro learns from sessions in every project, not just this one",
+            found.sessions_dir.display()
+        ),
+        Diagnosis::NoSessions => println!(
+            "  {} exists but holds no sessions yet",
+            found.sessions_dir.display()
+        ),
+        Diagnosis::AllProcessed => println!(
+            "  all {} session(s) for this project are already analyzed (newest {}) — new ones are captured when they end",
+            found.sessions,
+            date(found.newest_unix)
+        ),
+        Diagnosis::BeforeWatermark => println!(
+            "  {} session(s) here predate retro's capture (newest {}, capture started {}) and are not analyzed automatically",
+            found.sessions - found.processed,
+            date(found.newest_unix),
+            date(Some(found.watermark_unix))
+        ),
+        Diagnosis::Pending => println!(
+            "  {} session(s) here not analyzed yet — the next session start catches them up",
+            found.sessions - found.processed
+        ),
+    }
+}
+
+/// Per-project breakdown of a run that analyzed several projects (or had a
+/// failure): failed projects stay queued and are retried by the next run.
+fn print_project_table(projects: &[ProjectRunSummary]) {
+    let width = projects.iter().map(|p| p.slug.len()).max().unwrap_or(0).max(7);
+    println!(
+        "  {:<width$}  {:>8}  {:>4}  {:>7}  {:>8}",
+        "project", "sessions", "new", "updated", "tokens"
+    );
+    for p in projects {
+        match &p.error {
+            None => println!(
+                "  {:<width$}  {:>8}  {:>4}  {:>7}  {:>8}",
+                p.slug, p.sessions, p.nodes_created, p.nodes_updated, p.tokens
+            ),
+            Some(e) => println!(
+                "  {:<width$}  {:>8}  failed — stays queued: {}",
+                p.slug,
+                p.sessions,
+                retro_core::util::truncate_str(e, 80)
+            ),
+        }
+    }
+    let done = projects.iter().filter(|p| p.error.is_none());
+    let sum = |f: fn(&ProjectRunSummary) -> u64| done.clone().map(f).sum::<u64>();
+    println!(
+        "  {:<width$}  {:>8}  {:>4}  {:>7}  {:>8}",
+        "total",
+        sum(|p| p.sessions as u64),
+        sum(|p| p.nodes_created as u64),
+        sum(|p| p.nodes_updated as u64),
+        sum(|p| p.tokens)
+    );
+}
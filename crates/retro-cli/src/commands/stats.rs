@@ -0,0 +1,84 @@
+use anyhow::Result;
+use colored::Colorize;
+use retro_core::config::{Config, retro_dir};
+use retro_core::dismissals::{self, DismissalStats};
+use retro_core::store::Store;
+
+/// Read-only store statistics. `--dismissals`: what the user vetoed over the
+/// `since` window, and what that suggests tuning.
+pub fn run(dismissals: bool, since: &str) -> Result<()> {
+    if !dismissals {
+        anyhow::bail!("nothing to report — try `retro stats --dismissals`");
+    }
+    let dir = retro_dir();
+    if !dir.join("knowledge").exists() {
+        anyhow::bail!("retro is not initialized — run `retro init`");
+    }
+    let days = super::parse_window(since)?;
+    let since_date = retro_core::util::days_before(chrono::Local::now().date_naive(), days);
+    let config = Config::load(&dir.join("config.toml"))?;
+    let nodes: Vec<_> = Store::open(&dir)
+        .load_all()?
+        .nodes
+        .into_iter()
+        .map(|(_, n)| n)
+        .collect();
+    let threshold = config.knowledge.confidence_threshold;
+    let stats = dismissals::aggregate(&nodes, since_date, threshold);
+
+    println!(
+        "{} since {since_date}: {} dismissed, {} learned and kept",
+        "Dismissals".bold(),
+        stats.dismissed,
+        stats.kept
+    );
+    if stats.dismissed == 0 {
+        return Ok(());
+    }
+    print_table(&stats);
+    let hints = dismissals::hints(&stats, threshold);
+    if hints.is_empty() && stats.dismissed < dismissals::MIN_SAMPLE {
+        println!("\n(too few dismissals for tuning hints)");
+    }
+    if !hints.is_empty() {
+        println!("\n{}", "Hints".bold());
+        for h in hints {
+            println!("  {} {h}", "→".cyan());
+        }
+    }
+    Ok(())
+}
+
+fn print_table(s: &DismissalStats) {
+    let row = |label: &str, n: usize| {
+        let pct = n as f64 * 100.0 / s.dismissed as f64;
+        println!("    {label:<24} {n:>4}  {pct:>3.0}%");
+    };
+    println!("  by type:");
+    for (kind, n) in &s.by_type {
+        row(kind, *n);
+    }
+    println!("  by scope:");
+    for (scope, n) in &s.by_scope {
+        row(scope, *n);
+    }
+    println!("  by confidence:");
+    for bucket in dismissals::confidence_buckets() {
+        row(bucket, s.by_confidence.get(bucket).copied().unwrap_or(0));
+    }
+    if !s.by_project.is_empty() {
+        println!("  by project:");
+        for (slug, n) in &s.by_project {
+            row(slug, *n);
+        }
+    }
+    println!("  evidence:");
+    row("single session", s.single_session);
+    row("had been projected", s.projected);
+    if !s.by_reason.is_empty() {
+        println!("  reasons given:");
+        for (reason, n) in &s.by_reason {
+            row(retro_core::util::truncate_str(reason, 24), *n);
+        }
+    }
+}
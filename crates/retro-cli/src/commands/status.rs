@@ -1,8 +1,9 @@
 use anyhow::Result;
 use colored::Colorize;
 use retro_core::config::{retro_dir, Config};
+use retro_core::util::fmt_rfc3339;
 
-pub fn run() -> Result<()> {
+pub fn run(ai: bool) -> Result<()> {
     let dir = retro_dir();
     if !dir.join("knowledge").exists() {
         anyhow::bail!("retro is not initialized — run `retro init`");
@@ -10,7 +11,47 @@ pub fn run() -> Result<()> {
     let config_path = dir.join("config.toml");
     let config = Config::load(&config_path)?;
 
-    print_v3_status(&dir, &config)
+    print_v3_status(&dir, &config)?;
+    if ai {
+        print_ai_stats(&dir);
+    }
+    Ok(())
+}
+
+/// Last-24h AI backend metrics recorded by `MeteredBackend`.
+fn print_ai_stats(dir: &std::path::Path) {
+    use retro_core::analysis::metrics;
+
+    let calls = metrics::load(dir);
+    let since = chrono::Utc::now() - chrono::Duration::hours(24);
+    let stats = metrics::summarize(&calls, since);
+    println!();
+    println!("{}", "AI backend (last 24h)".bold());
+    if stats.calls == 0 {
+        println!("  no AI calls recorded");
+        return;
+    }
+    println!(
+        "  calls:    {} ({} failed, {} timed out)",
+        stats.calls, stats.failures, stats.timeouts
+    );
+    println!(
+        "  duration: p50 {:.1}s, p95 {:.1}s",
+        stats.p50_ms as f64 / 1000.0,
+        stats.p95_ms as f64 / 1000.0
+    );
+    println!(
+        "  tokens:   {} in, {} out",
+        stats.input_tokens, stats.output_tokens
+    );
+    if let Some(last) = calls.last() {
+        println!(
+            "  last:     {} ({}, {})",
+            fmt_rfc3339(&last.at, true),
+            last.model,
+            if last.ok { "ok".green() } else { "failed".red() }
+        );
+    }
 }
 
 fn print_v3_status(dir: &std::path::Path, config: &Config) -> Result<()> {
@@ -25,7 +66,7 @@ fn print_v3_status(dir: &std::path::Path, config: &Config) -> Result<()> {
         .iter()
         .filter(|(_, n)| n.is_active() && n.scope == retro_core::store::Scope::Global)
         .count();
-    let queue_len = queue::list(dir).map(|q| q.len()).unwrap_or(0);
+    let queued = queue::list(dir).unwrap_or_default();
     let state = RunnerState::load(dir)?;
     let today = chrono::Utc::now().date_naive().to_string();
     let budget_left = state.budget_remaining(&today, config.runner.max_ai_calls_per_day);
@@ -35,12 +76,117 @@ fn print_v3_status(dir: &std::path::Path, config: &Config) -> Result<()> {
         "  nodes:   {active} active ({global} global, {} project), {invalidated} invalidated",
         active - global
     );
-    println!("  queue:   {queue_len} pending session(s)");
+    let written_by = match retro_core::store::meta::StoreMeta::load(dir) {
+        Ok(Some(meta)) => format!(
+            "format {}, newest writer retro {}{}",
+            meta.format,
+            meta.written_by,
+            meta.commit.map(|c| format!(" ({c})")).unwrap_or_default()
+        ),
+        Ok(None) => "format 1 (unmarked), not yet stamped".to_string(),
+        Err(e) => format!("unreadable store.toml: {e}"),
+    };
+    let build = retro_core::version::BUILD;
+    println!(
+        "  store:   {written_by}; this binary is {} ({}, {}, format {})",
+        build.version,
+        build.commit,
+        build.build_date,
+        retro_core::store::meta::STORE_FORMAT
+    );
+    if let Some(skew) = retro_core::version::store_skew(dir) {
+        println!("  version: {} {skew}", "⚠".yellow());
+    }
+    let update = retro_core::version::check_for_update(
+        dir,
+        config,
+        chrono::Utc::now(),
+        retro_core::version::fetch_latest_release,
+    );
+    if let Ok(Some(notice)) = update {
+        println!("  update:  {notice}");
+    }
+    println!(
+        "  claude:  {} ({})",
+        config.claude_dir().display(),
+        config.claude_dir_source().describe()
+    );
+    if let Some(warning) = retro_core::user::home().mismatch {
+        println!("  home:    {} {warning}", "⚠".yellow());
+    }
+    match queued.first() {
+        // list is sorted oldest first
+        Some(oldest) => println!(
+            "  queue:   {} pending session(s), oldest queued {}{}",
+            queued.len(),
+            fmt_rfc3339(&oldest.enqueued_at, true),
+            if queued.len() < config.analysis.min_sessions_for_auto {
+                format!(" (hooks analyze at {}+)", config.analysis.min_sessions_for_auto)
+            } else {
+                String::new()
+            }
+        ),
+        None => println!("  queue:   0 pending session(s)"),
+    }
     println!(
         "  budget:  {budget_left}/{} AI call(s) left today",
         config.runner.max_ai_calls_per_day
     );
-    if let Ok(health) = retro_core::health::Health::load(dir) {
+    for (entry_type, count) in &state.unknown_entry_types {
+        println!(
+            "  transcripts: {} seen {count} entr(ies) of unknown type '{entry_type}' — retro may need an update",
+            "⚠".yellow()
+        );
+    }
+    let ignored: u64 = state.ignored_sessions.values().sum();
+    if ignored > 0 {
+        let per_rule: Vec<String> = state
+            .ignored_sessions
+            .iter()
+            .map(|(rule, n)| format!("rule {rule}: {n}"))
+            .collect();
+        println!(
+            "  ignored: {ignored} session(s) matched [[ingest.ignore_session_if]] ({})",
+            per_rule.join(", ")
+        );
+    }
+    let history = retro_core::ingest::history::counts(dir, &config.claude_dir());
+    if history.prompts > 0 {
+        let hint = if config.ingest.include_history {
+            String::new()
+        } else {
+            " — `retro run --include-history` to analyze them".to_string()
+        };
+        println!(
+            "  history: {} prompt(s) in {} project(s) have no transcript left, {} analyzed{hint}",
+            history.prompts, history.projects, history.analyzed
+        );
+    }
+    match retro_core::analysis::overrides::active(dir) {
+        Ok(found) if !found.is_empty() => {
+            let files: Vec<&str> = found.iter().map(|o| o.template.file).collect();
+            println!("  prompts: overridden in prompts/: {}", files.join(", "));
+            for w in found.iter().flat_map(|o| &o.warnings) {
+                println!("  prompts: {} {w}", "⚠".yellow());
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            println!("  prompts: {} {e} — runs will fail until it is fixed", "⚠".yellow());
+        }
+    }
+    if let Some(change) = state.config_changes.last() {
+        println!(
+            "  config:  changed {}: {}",
+            fmt_rfc3339(&change.at, true),
+            change.changes.join("; ")
+        );
+    }
+    let health = retro_core::health::Health::load(dir).ok();
+    if let Some(health) = &health {
+        if let Some(run) = health.stages.get("run") {
+            println!("  last run: {}", fmt_rfc3339(&run.at, true));
+        }
         let warnings = health.warnings();
         if warnings.is_empty() {
             println!("  health:  {}", "ok".green());
@@ -51,5 +197,61 @@ fn print_v3_status(dir: &std::path::Path, config: &Config) -> Result<()> {
         }
     }
     println!("  hint:    retro ui — dashboard; retro doctor — full checks");
+
+    let snapshot = status_snapshot(dir, config, &store, queued.len(), budget_left, health.as_ref());
+    let recommendations = retro_core::status::recommend(&snapshot);
+    println!();
+    println!("{}", "Recommended next steps".bold());
+    if recommendations.is_empty() {
+        println!("  all caught up");
+    }
+    for r in recommendations.iter().take(3) {
+        println!("  → {}", r.message);
+    }
     Ok(())
 }
+
+/// Gather what `status::recommend` looks at. Cheap checks only: no AI call
+/// (auth problems are read from the last analysis failure).
+fn status_snapshot(
+    dir: &std::path::Path,
+    config: &Config,
+    store: &retro_core::store::Store,
+    queued: usize,
+    budget_left: u32,
+    health: Option<&retro_core::health::Health>,
+) -> retro_core::status::StatusSnapshot {
+    use retro_core::status::{ClaudeCli, StatusSnapshot};
+
+    let auth_failed = health
+        .and_then(|h| h.stages.get("analyze"))
+        .is_some_and(|a| !a.ok && (a.detail.contains("auth") || a.detail.contains("/login")));
+    let claude_cli = if config.ai.backend == "heuristic" {
+        ClaudeCli::NotUsed
+    } else if !retro_core::analysis::claude_cli::ClaudeCliBackend::is_available() {
+        ClaudeCli::Missing
+    } else if auth_failed {
+        ClaudeCli::Unauthenticated
+    } else {
+        ClaudeCli::Ok
+    };
+    let oversize_projections = retro_core::lint::lint_projections(store, config)
+        .map(|files| {
+            files
+                .iter()
+                .filter(|(_, findings)| findings.iter().any(|f| f.kind == "file-length"))
+                .count()
+        })
+        .unwrap_or(0);
+    StatusSnapshot {
+        queued,
+        ai_budget_left: budget_left,
+        claude_cli,
+        lock: Some(retro_core::lock::inspect(&dir.join("run.lock"))),
+        lint_findings: retro_core::lint::run_lint(store, config)
+            .map(|r| r.findings.len())
+            .unwrap_or(0),
+        oversize_projections,
+        health_warnings: health.map(|h| h.warnings().len()).unwrap_or(0),
+    }
+}
@@ -16,14 +16,15 @@ fn write_atomic(path: &std::path::Path, content: &str) -> Result<()> {
 pub fn run(purge: bool) -> Result<()> {
     let dir = retro_dir();
     // A corrupt config must not block uninstall — defaults suffice here.
-    let config = Config::load(&dir.join("config.toml")).unwrap_or_default();
+    let config = Config::load_or_default(&dir.join("config.toml"));
     let claude_dir = config.claude_dir();
     // Projection files race the runner — same lock discipline as migrate.
     // The dir may be gone after a prior --purge; recreate so reruns stay
     // idempotent (and --purge below re-removes it).
     std::fs::create_dir_all(&dir)?;
-    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&dir.join("run.lock")) else {
-        anyhow::bail!("a retro run is in progress — retry shortly");
+    let lock_path = dir.join("run.lock");
+    let Some(_lock) = retro_core::lock::LockFile::try_acquire(&lock_path) else {
+        anyhow::bail!("{}", super::in_progress(&lock_path));
     };
     let backups = dir.join("backups");
 
@@ -164,10 +165,10 @@ pub fn run(purge: bool) -> Result<()> {
     Ok(())
 }
 
-/// $HOME, falling back to the temp dir (the rescue location must exist
-/// somewhere OUTSIDE the store being purged).
+/// The effective user's home (`user::home`), falling back to the temp dir
+/// (the rescue location must exist somewhere OUTSIDE the store being purged).
 fn dirs_home() -> std::path::PathBuf {
-    std::env::var("HOME")
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(|_| std::env::temp_dir())
+    Some(retro_core::user::home().path)
+        .filter(|home| home.as_os_str() != ".")
+        .unwrap_or_else(std::env::temp_dir)
 }
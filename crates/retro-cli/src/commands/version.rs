@@ -0,0 +1,17 @@
+use anyhow::Result;
+use retro_core::version::BUILD;
+
+/// `retro version`: the build this binary came from. The JSON form is for
+/// comparing machines; its keys are [`retro_core::version::BuildInfo`]'s.
+pub fn run(json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(&BUILD)?);
+        return Ok(());
+    }
+    println!("retro {}", BUILD.version);
+    println!("  commit:  {}", BUILD.commit);
+    println!("  built:   {}", BUILD.build_date);
+    println!("  target:  {}", BUILD.target);
+    println!("  rustc:   {}", BUILD.rustc);
+    Ok(())
+}
@@ -4,12 +4,25 @@ mod ui;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
-#[command(name = "retro", about = "Active context curator for AI coding agents")]
+#[command(
+    name = "retro",
+    about = "Active context curator for AI coding agents",
+    version,
+    long_version = retro_core::version::LONG_VERSION
+)]
 struct Cli {
-    /// Enable verbose debug output
+    /// Verbose diagnostics on stderr (RETRO_LOG=<filter> for per-module control)
     #[arg(long, global = true)]
     verbose: bool,
 
+    /// Show timestamps in UTC instead of local time
+    #[arg(long, global = true)]
+    utc: bool,
+
+    /// Print a timing breakdown to stderr on exit (also RETRO_PROFILE=1)
+    #[arg(long, global = true)]
+    profile: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -33,28 +46,129 @@ enum Commands {
         /// Show detailed output
         #[arg(long)]
         verbose: bool,
-        /// Preview only, don't make changes
+        /// Preview only, don't make changes (with --background: walk the hook
+        /// path's checks and print each verdict instead)
         #[arg(long)]
         dry_run: bool,
         /// Quiet background mode: exit silently if another run holds the lock
         #[arg(long)]
         background: bool,
+        /// Extract explicit directives without AI calls (same as [ai] backend = "heuristic")
+        #[arg(long)]
+        heuristic: bool,
+        /// Analyze sessions matching [[ingest.ignore_session_if]] rules this once
+        #[arg(long)]
+        include_ignored: bool,
+        /// Also analyze history.jsonl prompts whose transcripts are gone (same as
+        /// [ingest] include_history = true)
+        #[arg(long)]
+        include_history: bool,
+        /// Write at most N new nodes this run, most confident first; the rest
+        /// are deferred until re-observed (overrides [analysis]
+        /// max_new_nodes_per_run)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        limit: Option<u32>,
     },
     /// (v3 hook entry) Enqueue a finished session for analysis — called by the SessionEnd hook
     Observe,
     /// (v3 hook entry) Catch-up scan + session briefing — called by the SessionStart hook
-    Brief,
+    Brief {
+        /// List every session directory, ignoring the cached listings of
+        /// unchanged ones
+        #[arg(long)]
+        full_scan: bool,
+    },
     /// Rebuild the v3 store index from knowledge files (safe anytime)
     Reindex,
     /// Show retro status: store stats, queue, budget, health
-    Status,
+    Status {
+        /// Also show AI backend metrics for the last 24h
+        #[arg(long)]
+        ai: bool,
+    },
+    /// Show how this binary was built (commit, date, target, rustc)
+    Version {
+        /// Print the build info as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// End-to-end health verification (read-only)
-    Doctor,
-    /// Store-wide lint: near-duplicates and stale candidates (no AI calls)
+    Doctor {
+        /// Report managed blocks whose content no longer matches what retro
+        /// wrote (user edits vs. unknown insertions). Never modifies files
+        #[arg(long)]
+        verify_content: bool,
+        /// With --verify-content: machine-readable output
+        #[arg(long, requires = "verify_content")]
+        json: bool,
+        /// Rewrite hand-edited `sources:` lines in node files into a clean
+        /// list (committed, so the old lines stay in the store's history)
+        #[arg(long, conflicts_with = "verify_content")]
+        repair_sources: bool,
+    },
+    /// Markdown digest of what retro learned recently (pipe to mail/Slack)
+    Digest {
+        /// Window to cover: e.g. 7d, 2w
+        #[arg(long, default_value = "7d")]
+        since: String,
+        /// Write to a file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+    },
+    /// Aggregate statistics over the store (read-only)
+    Stats {
+        /// Nodes you dismissed in the dashboard, by type, scope, confidence
+        /// and project, with tuning hints
+        #[arg(long)]
+        dismissals: bool,
+        /// Window to cover: e.g. 30d, 4w
+        #[arg(long, default_value = "30d")]
+        since: String,
+    },
+    /// Store-wide lint: near-duplicates, stale candidates, rules already in the repo (no AI calls)
     Lint {
         /// Report only; don't queue findings as briefing notifications
         #[arg(long)]
         dry_run: bool,
+        /// Retire nodes whose rule is already written by hand in the
+        /// project's CLAUDE.md (or the global one), then reproject
+        #[arg(long, conflicts_with = "dry_run")]
+        reconcile: bool,
+    },
+    /// What retro wrote into projected files (CLAUDE.md, CLAUDE.local.md)
+    Log {
+        /// Compare one entry's file with what is on disk now (a diff when
+        /// [log] store_apply_snapshots kept its content)
+        #[arg(long, value_name = "ENTRY")]
+        show_files: Option<usize>,
+        /// Most recent entries to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Merge duplicate nodes into one (ids, unique prefixes, or scope/id)
+    Merge {
+        /// Nodes to merge; the first survives unless --into is given
+        #[arg(required = true, num_args = 2..)]
+        nodes: Vec<String>,
+        /// Node to keep (may be one of the listed nodes)
+        #[arg(long, value_name = "NODE")]
+        into: Option<String>,
+        /// Append the merged nodes' text to the survivor's
+        #[arg(long)]
+        keep_both_bodies: bool,
+        /// Allow merging nodes from different scopes
+        #[arg(long)]
+        force: bool,
+    },
+    /// Point a moved or renamed project at its new directory
+    Relocate {
+        /// The project's old path (as registered) or its slug
+        old: String,
+        /// Where the project lives now
+        new: String,
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Open the dashboard (local web UI)
     Ui {
@@ -68,24 +182,55 @@ enum Commands {
         #[arg(long)]
         purge: bool,
     },
+    /// Developer fixtures (built with --features dev-tools)
+    #[cfg(feature = "dev-tools")]
+    #[command(subcommand)]
+    Dev(DevCommands),
+}
+
+#[cfg(feature = "dev-tools")]
+#[derive(Subcommand)]
+enum DevCommands {
+    /// Populate an empty RETRO_HOME with a canned scenario
+    Seed {
+        /// One of: fresh, populated, stale-candidates, pending-queue, failing-health
+        #[arg(long)]
+        scenario: String,
+    },
+    /// Print the store (nodes, queue, state, health) as JSON
+    Dump,
 }
 
 fn main() {
     let cli = Cli::parse();
-    let verbose = cli.verbose;
+    let verbose = cli.verbose || matches!(cli.command, Commands::Run { verbose: true, .. });
+    retro_core::util::set_display_utc(cli.utc);
+    let profile = cli.profile || std::env::var("RETRO_PROFILE").is_ok_and(|v| v == "1");
+    if profile {
+        retro_core::profile::enable();
+    }
 
     // Show nudge for interactive commands (not hook entries or background runs)
     let is_auto = matches!(
         &cli.command,
         Commands::Observe
-            | Commands::Brief
+            | Commands::Brief { .. }
             // suppress the "run `retro run`" nudge on the way out the door
             | Commands::Uninstall { .. }
+            // --dry-run with it is run by hand to trace the hook path
             | Commands::Run {
                 background: true,
+                dry_run: false,
                 ..
             }
     );
+    init_logging(verbose, is_auto);
+    retro_core::config::capture_env();
+    // sudo/su keeping the caller's $HOME: home lookups follow the effective
+    // user's passwd entry instead (see `user::home`); say so.
+    if let Some(warning) = retro_core::user::home().mismatch {
+        log::warn!(target: retro_core::logging::CLI, "{warning}");
+    }
     if !is_auto {
         commands::check_and_display_nudge();
     }
@@ -93,21 +238,80 @@ fn main() {
     let result = match cli.command {
         Commands::Init { from } => commands::init::run(from),
         Commands::Migrate { dry_run } => commands::migrate::run(dry_run),
-        Commands::Run { verbose: run_verbose, dry_run, background } => {
-            commands::run::run(verbose || run_verbose, dry_run, background)
-        }
+        Commands::Run {
+            verbose: _,
+            dry_run,
+            background,
+            heuristic,
+            include_ignored,
+            include_history,
+            limit,
+        } => commands::run::run(
+            dry_run,
+            background,
+            heuristic,
+            include_ignored,
+            include_history,
+            limit,
+        ),
         Commands::Observe => commands::observe::run(),
-        Commands::Brief => commands::brief::run(),
+        Commands::Brief { full_scan } => commands::brief::run(full_scan),
         Commands::Reindex => commands::reindex::run(),
-        Commands::Status => commands::status::run(),
-        Commands::Doctor => commands::doctor::run(),
-        Commands::Lint { dry_run } => commands::lint::run(dry_run),
+        Commands::Status { ai } => commands::status::run(ai),
+        Commands::Version { json } => commands::version::run(json),
+        Commands::Doctor {
+            verify_content,
+            json,
+            repair_sources,
+        } => commands::doctor::run(verify_content, json, repair_sources),
+        Commands::Digest { since, output } => commands::digest::run(&since, output),
+        Commands::Stats { dismissals, since } => commands::stats::run(dismissals, &since),
+        Commands::Lint { dry_run, reconcile } => commands::lint::run(dry_run, reconcile),
+        Commands::Log { show_files, limit } => commands::log::run(show_files, limit),
+        Commands::Merge {
+            nodes,
+            into,
+            keep_both_bodies,
+            force,
+        } => commands::merge::run(nodes, into, keep_both_bodies, force),
+        Commands::Relocate { old, new, dry_run } => commands::relocate::run(&old, &new, dry_run),
         Commands::Ui { no_open } => commands::ui::run(no_open),
         Commands::Uninstall { purge } => commands::uninstall::run(purge),
+        #[cfg(feature = "dev-tools")]
+        Commands::Dev(DevCommands::Seed { scenario }) => commands::dev::seed(&scenario),
+        #[cfg(feature = "dev-tools")]
+        Commands::Dev(DevCommands::Dump) => commands::dev::dump(),
     };
 
+    if profile {
+        eprint!("{}", retro_core::profile::take().render());
+    }
+
     if let Err(e) = result {
         eprintln!("error: {e:#}");
         std::process::exit(1);
     }
 }
+
+/// `RETRO_LOG` (see `retro_core::logging`) wins; otherwise `--verbose` shows
+/// retro's debug output and everyone else's info. Hook entries and background
+/// runs have no terminal to print to: their warnings append to
+/// ~/.retro/hook-stderr.log instead of vanishing (rotated to
+/// hook-stderr.log.1 once past `LOG_FILE_MAX_BYTES`).
+fn init_logging(verbose: bool, auto: bool) {
+    use retro_core::logging::{ENV_VAR, Filter, LOG_FILE_MAX_BYTES, Logger, open_log_file};
+    let default = if verbose { "info,retro_core=debug" } else { "warn" };
+    let spec = std::env::var(ENV_VAR).unwrap_or_else(|_| default.to_string());
+    let filter = Filter::parse(&spec, log::LevelFilter::Warn);
+    let log_file = auto
+        .then(|| {
+            let path = retro_core::config::retro_dir().join("hook-stderr.log");
+            open_log_file(&path, LOG_FILE_MAX_BYTES).ok()
+        })
+        .flatten();
+    let logger = match log_file {
+        Some(file) => Logger::new(filter, Box::new(file)).timestamped(),
+        None => Logger::new(filter, Box::new(std::io::stderr())),
+    };
+    let _ = logger.install();
+}
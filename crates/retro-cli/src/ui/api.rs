@@ -125,10 +125,13 @@ fn parse_json_body(reader: &mut dyn Read) -> Result<serde_json::Value, (serde_js
 fn acquire_write_lock(
     store_root: &Path,
 ) -> Result<retro_core::lock::LockFile, (serde_json::Value, u16)> {
-    retro_core::lock::LockFile::try_acquire(&store_root.join("run.lock")).ok_or((
-        json!({"error": "a retro run is in progress — retry shortly"}),
-        503,
-    ))
+    let lock_path = store_root.join("run.lock");
+    retro_core::lock::LockFile::try_acquire(&lock_path).ok_or_else(|| {
+        (
+            json!({"error": crate::commands::in_progress(&lock_path)}),
+            503,
+        )
+    })
 }
 
 /// Shared post-write pipeline: commit the store, rebuild the index (index
@@ -141,40 +144,28 @@ fn after_write(
     scope: &retro_core::store::Scope,
     message: &str,
 ) -> Result<(), retro_core::errors::CoreError> {
-    use retro_core::store::{Store, git as store_git, index, projects::PathMap};
+    use retro_core::store::{Store, git as store_git, index};
     let store = Store::open(store_root);
     store_git::commit_all(store_root, message).map(|_| ())?;
     if let Err(e) = index::build(&store) {
         retro_core::health::record(store_root, "index", false, &e.to_string())?;
     }
-    let threshold = config.knowledge.confidence_threshold;
-    match scope {
-        retro_core::store::Scope::Global => {
-            let path = config.claude_dir().join("CLAUDE.md");
-            retro_core::projection::local_md::project_global_md(
-                &store,
-                &path,
-                threshold,
-                Some(&store_root.join("backups")),
-            )?;
-        }
-        retro_core::store::Scope::Project(slug) => {
-            let map = PathMap::load(store_root)?;
-            if let Some(p) = map.paths.get(slug) {
-                retro_core::projection::local_md::project_local_md(
-                    &store,
-                    slug,
-                    Path::new(p),
-                    threshold,
-                )?;
-            }
-        }
-    }
+    let report = retro_core::projection::reproject::reproject(
+        &store,
+        config,
+        Some(std::slice::from_ref(scope)),
+        Some(&store_root.join("backups")),
+    )?;
+    record_skipped(store_root, &report)?;
     Ok(())
 }
 
-/// `POST /api/node/invalidate` — body `{"scope","id"}`. Marks the node
-/// inactive (never deletes) and reprojects the affected scope.
+/// Longest dismissal reason accepted (one line of free text).
+const MAX_REASON_CHARS: usize = 500;
+
+/// `POST /api/node/invalidate` — body `{"scope","id","reason"?}`. Marks the
+/// node inactive (never deletes), keeping the optional reason on the node and
+/// in the commit message, and reprojects the affected scope.
 fn api_node_invalidate(
     store_root: &Path,
     config: &Config,
@@ -195,6 +186,17 @@ fn api_node_invalidate(
     if !retro_core::store::is_valid_slug(id) {
         return (json!({"error": "invalid id"}), 400);
     }
+    let reason = match body.get("reason") {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(r)) if r.chars().count() > MAX_REASON_CHARS => {
+            return (
+                json!({"error": format!("reason longer than {MAX_REASON_CHARS} chars")}),
+                400,
+            );
+        }
+        Some(serde_json::Value::String(r)) => Some(r.trim()).filter(|r| !r.is_empty()),
+        Some(_) => return (json!({"error": "reason must be a string"}), 400),
+    };
     let _lock = match acquire_write_lock(store_root) {
         Ok(l) => l,
         Err(resp) => return resp,
@@ -209,14 +211,17 @@ fn api_node_invalidate(
         Ok(None) => return (json!({"error": "node not found"}), 404),
         Err(e) => return (json!({"error": e.to_string()}), 500),
     }
-    match store.invalidate(&scope, id, "user") {
+    match store.dismiss(&scope, id, reason) {
         Ok(true) => {}
         Ok(false) => return (json!({"error": "node not found"}), 404),
         // I/O failure or malformed frontmatter on an existing node file is a
         // server-state problem, not a bad request.
         Err(e) => return (json!({"error": e.to_string()}), 500),
     }
-    let message = format!("user: invalidate {id} (dashboard)");
+    let mut message = format!("user: invalidate {id} (dashboard)");
+    if let Some(reason) = reason {
+        message.push_str(&format!("\n\nreason: {reason}"));
+    }
     if let Err(e) = after_write(store_root, config, &scope, &message) {
         return (
             json!({"error": format!("change saved, but post-write processing failed: {e}")}),
@@ -539,6 +544,9 @@ fn api_xray(store_root: &Path, config: &Config) -> (serde_json::Value, u16) {
     )
 }
 
+/// Commits the node detail lists.
+const NODE_HISTORY_LIMIT: usize = 5;
+
 fn api_node(store_root: &Path, url: &str) -> (serde_json::Value, u16) {
     let Some(scope_param) = query_param(url, "scope") else {
         return (json!({"error": "missing scope param"}), 400);
@@ -557,6 +565,18 @@ fn api_node(store_root: &Path, url: &str) -> (serde_json::Value, u16) {
     match store.get(&scope, &id) {
         Ok(Some(node)) => {
             let path = store.node_path(&scope, &id);
+            // Patterns: how many source sessions used each tool.
+            let tools: Vec<serde_json::Value> = retro_core::store::tools::load(store_root)
+                .get(&retro_core::store::tools::key(&scope, &id))
+                .map(retro_core::store::tools::histogram)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(tool, sessions)| json!({"tool": tool, "sessions": sessions}))
+                .collect();
+            // Why the node reads as it does: the reasoning learn commits record.
+            let history =
+                retro_core::store::git::file_history(store_root, &path, NODE_HISTORY_LIMIT)
+                    .unwrap_or_default();
             (
                 json!({
                     "id": node.id,
@@ -567,7 +587,14 @@ fn api_node(store_root: &Path, url: &str) -> (serde_json::Value, u16) {
                     "created": node.created.to_string(),
                     "updated": node.updated.to_string(),
                     "invalidated_by": node.invalidated_by,
+                    "dismissal_reason": node.dismissal_reason,
+                    "section": node.section,
+                    // What the managed block shows: one bullet per rule
+                    // when the body holds several.
+                    "projected_as": retro_core::projection::local_md::projected_bullets(&node.body),
                     "body": node.body,
+                    "tools": tools,
+                    "history": history,
                     "path": path.display().to_string(),
                 }),
                 200,
@@ -773,18 +800,24 @@ fn reproject_all(
     store_root: &Path,
     config: &Config,
 ) -> Result<(), retro_core::errors::CoreError> {
-    use retro_core::store::{Store, projects::PathMap};
-    let store = Store::open(store_root);
-    let threshold = config.knowledge.confidence_threshold;
-    retro_core::projection::local_md::project_global_md(
+    let store = retro_core::store::Store::open(store_root);
+    let report = retro_core::projection::reproject::reproject(
         &store,
-        &config.claude_dir().join("CLAUDE.md"),
-        threshold,
+        config,
+        None,
         Some(&store_root.join("backups")),
     )?;
-    let map = PathMap::load(store_root)?;
-    for (slug, p) in &map.paths {
-        retro_core::projection::local_md::project_local_md(&store, slug, Path::new(p), threshold)?;
+    record_skipped(store_root, &report)
+}
+
+/// Projects whose registered path is gone were skipped — surface that as a
+/// health warning (the write that triggered the reprojection succeeded).
+fn record_skipped(
+    store_root: &Path,
+    report: &retro_core::projection::reproject::ReprojectReport,
+) -> Result<(), retro_core::errors::CoreError> {
+    for line in report.skipped() {
+        retro_core::health::record(store_root, "project", false, &line)?;
     }
     Ok(())
 }
@@ -797,17 +830,11 @@ mod tests {
     use tempfile::TempDir;
 
     fn node(id: &str, scope: Scope, t: NodeType, body: &str) -> Node {
-        Node {
-            id: id.to_string(),
-            scope,
-            node_type: t,
-            confidence: 0.8,
-            sources: vec!["session:abc".to_string()],
-            created: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
-            updated: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
-            invalidated_by: None,
-            body: body.to_string(),
-        }
+        let mut node = Node::new(id, scope, t, 0.8, body);
+        node.sources = vec!["session:abc".to_string()];
+        node.created = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        node.updated = node.created;
+        node
     }
 
     #[test]
@@ -992,6 +1019,9 @@ mod tests {
         let (body, status) = api_node(tmp.path(), "/api/node?scope=global&id=real-rule");
         assert_eq!(status, 200);
         assert_eq!(body["id"], "real-rule");
+        assert_eq!(body["projected_as"], json!(["a rule"]));
+        assert_eq!(body["tools"], json!([]), "no tool usage recorded");
+        assert_eq!(body["history"], json!([]), "no store repo, no history");
 
         // full percent-decoding (URLSearchParams-style encoding)
         assert_eq!(percent_decode("caf%C3%A9+au%20lait"), "café au lait");
@@ -1051,10 +1081,16 @@ mod tests {
         assert!(before.contains("always run smoke tests first"), "got: {before}");
 
         let commits_before = commit_count(store_tmp.path());
+        let (_, status) = api_node_invalidate(
+            store_tmp.path(),
+            &config,
+            &json!({"scope": "global", "id": "old-rule", "reason": 3}),
+        );
+        assert_eq!(status, 400, "non-string reason rejected");
         let (body, status) = api_node_invalidate(
             store_tmp.path(),
             &config,
-            &json!({"scope": "global", "id": "old-rule"}),
+            &json!({"scope": "global", "id": "old-rule", "reason": " duplicate of existing rule "}),
         );
         assert_eq!(status, 200, "body: {body}");
         assert_eq!(body["ok"], true);
@@ -1062,8 +1098,18 @@ mod tests {
         let n = store.get(&Scope::Global, "old-rule").unwrap().unwrap();
         assert!(!n.is_active());
         assert_eq!(n.invalidated_by.as_deref(), Some("user"));
+        assert_eq!(n.dismissal_reason.as_deref(), Some("duplicate of existing rule"));
+        let (body, _) = api_node(store_tmp.path(), "/api/node?scope=global&id=old-rule");
+        assert_eq!(body["dismissal_reason"], "duplicate of existing rule");
 
         assert!(commit_count(store_tmp.path()) > commits_before);
+        let log = std::process::Command::new("git")
+            .args(["log", "-1", "--format=%B"])
+            .current_dir(store_tmp.path())
+            .output()
+            .unwrap();
+        let message = String::from_utf8_lossy(&log.stdout);
+        assert!(message.contains("reason: duplicate of existing rule"), "{message}");
 
         let after = std::fs::read_to_string(&claude_md_path).unwrap();
         assert!(
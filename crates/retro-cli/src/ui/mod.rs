@@ -28,7 +28,7 @@ pub fn serve(store_root: PathBuf, config: Config) -> Result<()> {
         let cfg = Config::load(&config_path).unwrap_or_else(|_| config.clone());
         let response = api::route(&store_root, &cfg, &method, &url, request);
         if let Err(e) = response {
-            eprintln!("ui: request error: {e}");
+            log::warn!(target: retro_core::logging::UI, "request error: {e}");
         }
     }
     Ok(())
@@ -0,0 +1,79 @@
+//! `retro status` against each `seed` scenario: the binary runs with
+//! `RETRO_HOME` on a seeded temp store and `[paths] claude_dir` in a temp
+//! dir, so nothing touches the real ~/.retro or ~/.claude.
+
+use std::process::Command;
+
+use retro_core::seed::{SCENARIOS, seed};
+use tempfile::TempDir;
+
+/// Seed `scenario` and return `retro status`'s stdout and stderr.
+fn status(scenario: &str) -> (String, String) {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path().join("home");
+    let claude = tmp.path().join("claude");
+    std::fs::create_dir_all(&claude).unwrap();
+    seed(&home, scenario).unwrap();
+    std::fs::write(
+        home.join("config.toml"),
+        format!("[paths]\nclaude_dir = {:?}\n", claude.display().to_string()),
+    )
+    .unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_retro"))
+        .arg("status")
+        .env("RETRO_HOME", &home)
+        .env("NO_COLOR", "1")
+        .env_remove("CLAUDE_CONFIG_DIR")
+        .env_remove("RETRO_LOG")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+    assert!(out.status.success(), "{scenario}: {stdout}{stderr}");
+    (stdout, stderr)
+}
+
+#[test]
+fn every_scenario_renders() {
+    for scenario in SCENARIOS {
+        let (stdout, _) = status(scenario);
+        assert!(stdout.contains("v3 knowledge store"), "{scenario}: {stdout}");
+        assert!(stdout.contains("Recommended next steps"), "{scenario}: {stdout}");
+    }
+}
+
+#[test]
+fn fresh_store_is_empty_and_caught_up() {
+    let (stdout, _) = status("fresh");
+    assert!(stdout.contains("nodes:   0 active (0 global, 0 project), 0 invalidated"), "{stdout}");
+    assert!(stdout.contains("queue:   0 pending session(s)"), "{stdout}");
+    assert!(stdout.contains("health:  ok"), "{stdout}");
+    assert!(stdout.contains("all caught up"), "{stdout}");
+}
+
+#[test]
+fn populated_store_counts_nodes_by_scope() {
+    let (stdout, _) = status("populated");
+    assert!(stdout.contains("nodes:   6 active (3 global, 3 project), 1 invalidated"), "{stdout}");
+}
+
+#[test]
+fn stale_candidates_recommend_lint() {
+    let (stdout, _) = status("stale-candidates");
+    assert!(stdout.contains("nodes:   9 active (5 global, 4 project), 1 invalidated"), "{stdout}");
+    assert!(stdout.contains("lint finding(s) — `retro lint`"), "{stdout}");
+}
+
+#[test]
+fn pending_queue_shows_the_oldest_entry() {
+    let (stdout, _) = status("pending-queue");
+    assert!(stdout.contains("queue:   3 pending session(s), oldest queued 2026-01-01"), "{stdout}");
+}
+
+#[test]
+fn failing_health_lists_each_failed_stage() {
+    let (stdout, _) = status("failing-health");
+    assert!(stdout.contains("retro analyze failed"), "{stdout}");
+    assert!(stdout.contains("retro push failed"), "{stdout}");
+    assert!(stdout.contains("2 health warning(s) — `retro doctor`"), "{stdout}");
+}
@@ -0,0 +1,62 @@
+//! Driving retro-core as a library through `retro_core::prelude`: enqueue a
+//! session, run the v3 pipeline against a scripted backend, and read back
+//! what was learned and projected. Everything lives in temp dirs.
+//!
+//! cargo run -p retro-core --example library_usage --features test-support
+
+use retro_core::analysis::backend::MockBackend;
+use retro_core::prelude::*;
+
+fn main() -> Result<(), CoreError> {
+    let store_dir = tempfile::TempDir::new().map_err(|e| CoreError::Io(e.to_string()))?;
+    let claude_dir = tempfile::TempDir::new().map_err(|e| CoreError::Io(e.to_string()))?;
+    let project_dir = tempfile::TempDir::new().map_err(|e| CoreError::Io(e.to_string()))?;
+
+    // Never let a library run project into the real ~/.claude.
+    let mut config = Config::default();
+    config.paths.claude_dir = claude_dir.path().display().to_string();
+
+    // A two-message transcript in Claude Code's JSONL format.
+    let cwd = project_dir.path().display().to_string();
+    let transcript = store_dir.path().join("demo-session.jsonl");
+    let line = |n: u32, text: &str| {
+        format!(
+            r#"{{"type":"user","uuid":"demo-{n}","sessionId":"demo-session","cwd":"{cwd}","timestamp":"2026-07-06T10:00:0{n}Z","message":{{"role":"user","content":"{text}"}}}}"#
+        )
+    };
+    std::fs::write(
+        &transcript,
+        format!("{}\n{}\n", line(0, "set up the tests"), line(1, "always use uv, never pip")),
+    )
+    .map_err(|e| CoreError::Io(e.to_string()))?;
+    enqueue(
+        store_dir.path(),
+        &QueueEntry {
+            session_id: "demo-session".to_string(),
+            transcript_path: transcript.display().to_string(),
+            cwd: Some(cwd),
+            enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+        },
+    )?;
+
+    let backend = MockBackend::with_responses(vec![
+        r#"{"reasoning":"explicit tooling preference","operations":[
+            {"action":"create_node","node_type":"rule","scope":"global","content":"Use uv for Python, never pip.","confidence":0.9}
+        ]}"#
+        .to_string(),
+    ]);
+    let summary = run_v3(store_dir.path(), &config, &backend, false)?
+        .ok_or_else(|| CoreError::Lock("another run holds the lock".to_string()))?;
+    println!(
+        "analyzed {} session(s): +{} node(s), {} global rule(s) projected",
+        summary.sessions_processed, summary.nodes_created, summary.rules_projected_global
+    );
+
+    let store = Store::open(store_dir.path());
+    for rule in projectable_rules(&store, &Scope::Global, config.knowledge.confidence_threshold)? {
+        println!("  - {rule}");
+    }
+    let health = Health::load(store_dir.path())?;
+    println!("health warnings: {}", health.warnings().len());
+    Ok(())
+}
@@ -16,26 +16,45 @@ pub trait AnalysisBackend {
     /// When `json_schema` is provided, the backend passes it to `--json-schema`
     /// for constrained decoding (guaranteed valid JSON matching the schema).
     fn execute(&self, prompt: &str, json_schema: Option<&str>) -> Result<BackendResponse, CoreError>;
+
+    /// [`execute`](Self::execute) on `model` instead of the configured one,
+    /// for backends that can switch (cheap side calls such as session
+    /// summaries). The default ignores `model`.
+    fn execute_with_model(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+        model: &str,
+    ) -> Result<BackendResponse, CoreError> {
+        let _ = model;
+        self.execute(prompt, json_schema)
+    }
 }
 
 /// Scripted backend for tests: returns canned responses in order, recording
-/// prompts. Lives in production code (not cfg(test)) so retro-cli integration
-/// tests and runner_v3 tests can use it too.
+/// prompts. Outside this crate's own tests it needs the `test-support`
+/// feature (dependents' tests, the library example).
+#[cfg(any(test, feature = "test-support"))]
 #[derive(Default)]
 pub struct MockBackend {
     pub responses: std::sync::Mutex<Vec<String>>,
     pub prompts_seen: std::sync::Mutex<Vec<String>>,
+    /// Models requested through `execute_with_model`, in call order.
+    pub models_seen: std::sync::Mutex<Vec<String>>,
 }
 
+#[cfg(any(test, feature = "test-support"))]
 impl MockBackend {
     pub fn with_responses(responses: Vec<String>) -> Self {
         MockBackend {
             responses: std::sync::Mutex::new(responses),
             prompts_seen: std::sync::Mutex::new(Vec::new()),
+            models_seen: std::sync::Mutex::new(Vec::new()),
         }
     }
 }
 
+#[cfg(any(test, feature = "test-support"))]
 impl AnalysisBackend for MockBackend {
     fn execute(
         &self,
@@ -53,4 +72,14 @@ impl AnalysisBackend for MockBackend {
             output_tokens: 50,
         })
     }
+
+    fn execute_with_model(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+        model: &str,
+    ) -> Result<BackendResponse, CoreError> {
+        self.models_seen.lock().unwrap().push(model.to_string());
+        self.execute(prompt, json_schema)
+    }
 }
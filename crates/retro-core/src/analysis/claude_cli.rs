@@ -1,8 +1,10 @@
 use crate::config::AiConfig;
 use crate::errors::CoreError;
+use crate::logging;
 use crate::models::ClaudeCliOutput;
 use std::io::{Read, Write};
 use std::process::Command;
+use std::sync::OnceLock;
 use std::thread;
 use std::time::{Duration, Instant};
 use super::backend::{AnalysisBackend, BackendResponse};
@@ -53,7 +55,7 @@ impl ClaudeCliBackend {
             .map_err(|e| CoreError::Analysis(format!("auth check failed to spawn: {e}")))?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        if let Ok(cli_output) = serde_json::from_str::<ClaudeCliOutput>(&stdout) {
+        if let Ok(cli_output) = parse_cli_output(&stdout) {
             if cli_output.is_error {
                 let msg = cli_output.result.unwrap_or_default();
                 return Err(CoreError::Analysis(format!(
@@ -78,6 +80,204 @@ impl ClaudeCliBackend {
     }
 }
 
+/// How long a probe that failed for another reason (offline, auth, a CLI
+/// hiccup) stands before the next run pings again.
+const INCONCLUSIVE_RETRY_MINUTES: i64 = 60;
+
+/// Cached result of the last model validation (`state/model_check.json`),
+/// so the check costs at most one ping per model per day — or per
+/// [`INCONCLUSIVE_RETRY_MINUTES`] while the CLI itself is failing.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ModelCheck {
+    pub model: String,
+    /// Day the check ran (YYYY-MM-DD, UTC).
+    pub date: String,
+    pub ok: bool,
+    pub detail: String,
+    /// The probe failed for a reason other than the model: nothing is known
+    /// about the model yet (`ok` stays true so the run goes ahead).
+    #[serde(default)]
+    pub inconclusive: bool,
+    /// RFC3339 time the check ran.
+    #[serde(default)]
+    pub at: String,
+}
+
+impl ModelCheck {
+    fn path(store_root: &std::path::Path) -> std::path::PathBuf {
+        store_root.join("state").join("model_check.json")
+    }
+
+    /// Missing or corrupt cache loads as None (re-check).
+    pub fn load(store_root: &std::path::Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(store_root)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub(crate) fn save(&self, store_root: &std::path::Path) {
+        let path = Self::path(store_root);
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+/// Recognize the claude CLI's model-not-found failures and translate them
+/// into an error that points at the config key. None for anything else
+/// (auth, network), which the caller must not treat as a bad model.
+pub fn translate_model_error(model: &str, output: &str) -> Option<String> {
+    let lower = output.to_lowercase();
+    let is_model_error = lower.contains("issue with the selected model")
+        || lower.contains("invalid model")
+        || lower.contains("unknown model")
+        || lower.contains("model not found")
+        || (lower.contains("not_found_error") && lower.contains("model"));
+    is_model_error.then(|| {
+        format!("unknown model '{model}' — configured in ~/.retro/config.toml [ai].model")
+    })
+}
+
+/// What a model probe call returned.
+struct Probe {
+    /// The CLI's error output; empty when the model answered.
+    error: String,
+    duration_ms: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+/// Validate `model` with a cheap ping, at most once per model per day.
+/// Only a recognized model-not-found failure is an error: other probe
+/// failures (offline, auth) pass so the real call reports them in context,
+/// and aren't retried for [`INCONCLUSIVE_RETRY_MINUTES`]. The ping is a
+/// model call: it counts against `max_calls_per_day` (no budget left, no
+/// ping) and is recorded in the backend metrics.
+pub fn validate_model_cached(
+    store_root: &std::path::Path,
+    model: &str,
+    max_calls_per_day: u32,
+) -> Result<(), CoreError> {
+    validate_model_with(store_root, model, max_calls_per_day, chrono::Utc::now(), || {
+        probe_model(model)
+    })
+}
+
+/// [`validate_model_cached`] with the clock and the probe injected. `probe`
+/// is None when claude can't be spawned.
+fn validate_model_with(
+    store_root: &std::path::Path,
+    model: &str,
+    max_calls_per_day: u32,
+    now: chrono::DateTime<chrono::Utc>,
+    probe: impl FnOnce() -> Option<Probe>,
+) -> Result<(), CoreError> {
+    let today = now.date_naive().to_string();
+    if let Some(cached) = ModelCheck::load(store_root).filter(|c| c.model == model) {
+        if cached.inconclusive {
+            let retry = chrono::Duration::minutes(INCONCLUSIVE_RETRY_MINUTES);
+            let recent = chrono::DateTime::parse_from_rfc3339(&cached.at)
+                .is_ok_and(|at| now.signed_duration_since(at) < retry);
+            if recent {
+                return Ok(());
+            }
+        } else if cached.date == today {
+            return if cached.ok {
+                Ok(())
+            } else {
+                Err(CoreError::Config(cached.detail))
+            };
+        }
+    }
+    // The run lock serializes the budget update with a running pipeline; a
+    // run in progress will report a bad model itself.
+    let Some(_lock) = crate::lock::LockFile::try_acquire(&store_root.join("run.lock")) else {
+        return Ok(());
+    };
+    let mut state = crate::store::state::RunnerState::load(store_root)?;
+    if state.budget_remaining(&today, max_calls_per_day) == 0 {
+        return Ok(()); // the run makes no call either
+    }
+    let Some(probe) = probe() else {
+        return Ok(()); // no CLI: the analysis call reports it
+    };
+    state.record_ai_calls(&today, 1);
+    state.save(store_root)?;
+    let _ = super::metrics::record(
+        store_root,
+        &super::metrics::BackendCall {
+            at: now.to_rfc3339(),
+            model: model.to_string(),
+            duration_ms: probe.duration_ms,
+            ok: probe.error.is_empty(),
+            timed_out: false,
+            input_tokens: probe.input_tokens,
+            output_tokens: probe.output_tokens,
+        },
+    );
+    let failure = translate_model_error(model, &probe.error);
+    let inconclusive = failure.is_none() && !probe.error.is_empty();
+    let detail = match &failure {
+        Some(msg) => msg.clone(),
+        None if inconclusive => format!(
+            "probe failed, model not verified: {}",
+            crate::util::truncate_str(probe.error.trim(), 200)
+        ),
+        None => "model accepted".to_string(),
+    };
+    ModelCheck {
+        model: model.to_string(),
+        date: today,
+        ok: failure.is_none(),
+        detail,
+        inconclusive,
+        at: now.to_rfc3339(),
+    }
+    .save(store_root);
+    match failure {
+        Some(msg) => Err(CoreError::Config(msg)),
+        None => Ok(()),
+    }
+}
+
+/// Ping `model` through the claude CLI. None when claude can't be spawned.
+fn probe_model(model: &str) -> Option<Probe> {
+    let safe_cwd = crate::config::retro_dir();
+    let start = Instant::now();
+    let output = Command::new("claude")
+        .args(["-p", "ping", "--output-format", "json", "--max-turns", "1", "--tools", ""])
+        .args(["--model", model])
+        .env_remove("CLAUDECODE")
+        .current_dir(&safe_cwd)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .ok()?;
+    let duration_ms = start.elapsed().as_millis() as u64;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed = parse_cli_output(&stdout);
+    let (input_tokens, output_tokens) = parsed
+        .as_ref()
+        .ok()
+        .and_then(|cli| cli.usage.as_ref())
+        .map_or((0, 0), |u| (u.input_tokens, u.output_tokens));
+    let error = match parsed {
+        Ok(cli) if !cli.is_error => String::new(),
+        Ok(cli) => cli.result.unwrap_or_else(|| "claude reported an error".to_string()),
+        Err(_) if output.status.success() => String::new(),
+        Err(_) => format!("{stdout}{}", String::from_utf8_lossy(&output.stderr)),
+    };
+    Some(Probe {
+        error,
+        duration_ms,
+        input_tokens,
+        output_tokens,
+    })
+}
+
 /// Maximum time to wait for an agentic `claude -p` call (codebase exploration).
 const AGENTIC_TIMEOUT_SECS: u64 = 600; // 10 minutes
 
@@ -150,7 +350,7 @@ fn run_claude_child(
 
     let stdout = String::from_utf8_lossy(&stdout_bytes);
 
-    let cli_output: ClaudeCliOutput = serde_json::from_str(&stdout).map_err(|e| {
+    let cli_output = parse_cli_output(&stdout).map_err(|e| {
         CoreError::Analysis(format!(
             "failed to parse claude CLI {label} output: {e}\nraw output: {}",
             truncate_for_error(&stdout)
@@ -234,6 +434,15 @@ impl ClaudeCliBackend {
 
 impl AnalysisBackend for ClaudeCliBackend {
     fn execute(&self, prompt: &str, json_schema: Option<&str>) -> Result<BackendResponse, CoreError> {
+        self.execute_with_model(prompt, json_schema, &self.model)
+    }
+
+    fn execute_with_model(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+        model: &str,
+    ) -> Result<BackendResponse, CoreError> {
         // Pipe prompt via stdin to avoid ARG_MAX limits on large prompts.
         //
         // When --json-schema is used:
@@ -255,7 +464,7 @@ impl AnalysisBackend for ClaudeCliBackend {
             "--output-format",
             "json",
             "--model",
-            &self.model,
+            model,
             "--max-turns",
             max_turns,
         ];
@@ -272,6 +481,13 @@ impl AnalysisBackend for ClaudeCliBackend {
         // worker inherits an arbitrary cwd. macOS TCC attributes child process
         // file access to the parent (retro), causing spurious permission dialogs.
         let safe_cwd = crate::config::retro_dir();
+        log::debug!(
+            target: logging::ANALYSIS,
+            "claude -p (model {}, {} prompt bytes, schema: {})",
+            model,
+            prompt.len(),
+            json_schema.is_some()
+        );
         let child = Command::new("claude")
             .args(&args)
             // Clear CLAUDECODE to avoid nested-session rejection: retro's
@@ -292,6 +508,12 @@ impl AnalysisBackend for ClaudeCliBackend {
 
         let input_tokens = cli_output.total_input_tokens();
         let output_tokens = cli_output.total_output_tokens();
+        log::debug!(
+            target: logging::ANALYSIS,
+            "claude -p done in {}ms: {input_tokens} in / {output_tokens} out, {} turn(s)",
+            cli_output.duration_ms,
+            cli_output.num_turns
+        );
 
         // When --json-schema is used, the structured JSON appears in
         // `structured_output` (as a parsed JSON value) rather than `result`.
@@ -320,6 +542,51 @@ impl AnalysisBackend for ClaudeCliBackend {
     }
 }
 
+/// Keys only the CLI's `--output-format json` envelope carries. A bare
+/// `{...}` in a banner or an API error body (`{"type":"error",...}`) must
+/// not be mistaken for it — every `ClaudeCliOutput` field has a default.
+const ENVELOPE_KEYS: &[&str] = &["result", "is_error", "structured_output", "session_id"];
+
+fn ansi_escape() -> &'static regex::Regex {
+    static ANSI: OnceLock<regex::Regex> = OnceLock::new();
+    ANSI.get_or_init(|| regex::Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap())
+}
+
+/// Parse the CLI's JSON envelope out of stdout, tolerating whatever the CLI
+/// prints around it (update notices, ANSI colour codes, trailing newlines).
+/// Tries each `{` in order and takes the first that starts a complete
+/// envelope object; anything after it is ignored. On failure the error names
+/// the non-JSON prefix that was skipped, if any.
+pub(crate) fn parse_cli_output(stdout: &str) -> Result<ClaudeCliOutput, String> {
+    let cleaned = ansi_escape().replace_all(stdout, "");
+    let text = cleaned.trim();
+    let mut first_error = None;
+    for (i, _) in text.match_indices('{') {
+        let mut stream = serde_json::Deserializer::from_str(&text[i..]).into_iter::<serde_json::Value>();
+        match stream.next() {
+            Some(Ok(value))
+                if value
+                    .as_object()
+                    .is_some_and(|o| ENVELOPE_KEYS.iter().any(|k| o.contains_key(*k))) =>
+            {
+                return serde_json::from_value(value).map_err(|e| e.to_string());
+            }
+            Some(Err(e)) if first_error.is_none() => first_error = Some(e.to_string()),
+            _ => {}
+        }
+    }
+    let skipped = text.find('{').map_or(text, |i| &text[..i]).trim();
+    let reason = first_error.unwrap_or_else(|| "no claude CLI JSON envelope in output".to_string());
+    if skipped.is_empty() {
+        Err(reason)
+    } else {
+        Err(format!(
+            "{reason} (after skipping non-JSON prefix {:?})",
+            truncate_for_error(skipped)
+        ))
+    }
+}
+
 fn truncate_for_error(s: &str) -> &str {
     if s.len() <= 500 {
         s
@@ -331,3 +598,142 @@ fn truncate_for_error(s: &str) -> &str {
         &s[..i]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_envelope_behind_banners_ansi_and_trailing_noise() {
+        let payload = r#"{"type":"result","is_error":false,"result":"ok","duration_ms":12}"#;
+        let fixtures = [
+            payload.to_string(),
+            format!("{payload}\n\n"),
+            format!("Update available: 2.1.0 → 2.2.0 {{run claude update}}\n{payload}\n"),
+            format!("\x1b[33mA new version is available\x1b[0m\n{payload}"),
+            format!("\x1b[1m{payload}\x1b[0m\ntrailing log line"),
+        ];
+        for f in &fixtures {
+            let out = parse_cli_output(f).unwrap_or_else(|e| panic!("{f:?}: {e}"));
+            assert_eq!(out.result.as_deref(), Some("ok"), "{f:?}");
+            assert_eq!(out.duration_ms, 12);
+        }
+    }
+
+    #[test]
+    fn rejects_non_envelope_json_and_reports_skipped_prefix() {
+        // an API error body is JSON, but not the CLI envelope
+        let err = parse_cli_output(r#"API Error: 404 {"type":"error","error":{"type":"not_found_error"}}"#)
+            .unwrap_err();
+        assert!(err.contains("API Error: 404"), "got: {err}");
+        let err = parse_cli_output("Update available\n{\"result\": ").unwrap_err();
+        assert!(err.contains("Update available"), "got: {err}");
+        assert_eq!(parse_cli_output("").unwrap_err(), "no claude CLI JSON envelope in output");
+    }
+
+    #[test]
+    fn translates_model_not_found_outputs() {
+        let samples = [
+            "There's an issue with the selected model (claude-sonet-4). It may not exist or you may not have access to it. Run --model to pick a different model.",
+            r#"API Error: 404 {"type":"error","error":{"type":"not_found_error","message":"model: claude-sonet-4"}}"#,
+            "Error: Invalid model name: claude-sonet-4",
+        ];
+        for out in samples {
+            let msg = translate_model_error("claude-sonet-4", out)
+                .unwrap_or_else(|| panic!("not recognized: {out}"));
+            assert!(msg.contains("unknown model 'claude-sonet-4'"));
+            assert!(msg.contains("[ai].model"));
+        }
+    }
+
+    #[test]
+    fn other_failures_are_not_model_errors() {
+        for out in [
+            "Invalid API key · Please run /login",
+            "Not logged in · Please run /login",
+            r#"API Error: 529 {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+            "",
+        ] {
+            assert!(translate_model_error("sonnet", out).is_none(), "{out}");
+        }
+    }
+
+    #[test]
+    fn cached_result_short_circuits_for_same_model_and_day() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let today = chrono::Utc::now().date_naive().to_string();
+        ModelCheck {
+            model: "bogus".to_string(),
+            date: today,
+            ok: false,
+            detail: "unknown model 'bogus'".to_string(),
+            inconclusive: false,
+            at: chrono::Utc::now().to_rfc3339(),
+        }
+        .save(tmp.path());
+        // cached failure is returned without spawning claude
+        let err = validate_model_cached(tmp.path(), "bogus", 10).unwrap_err();
+        assert!(err.to_string().contains("unknown model 'bogus'"));
+    }
+
+    fn probe(error: &str) -> Option<Probe> {
+        Some(Probe {
+            error: error.to_string(),
+            duration_ms: 900,
+            input_tokens: 4,
+            output_tokens: 1,
+        })
+    }
+
+    #[test]
+    fn probe_is_budgeted_and_metered() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let now = chrono::DateTime::parse_from_rfc3339("2026-07-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let state = || crate::store::state::RunnerState::load(tmp.path()).unwrap();
+
+        validate_model_with(tmp.path(), "sonnet", 3, now, || probe("")).unwrap();
+        assert_eq!(state().budget_remaining("2026-07-01", 3), 2);
+        let calls = crate::analysis::metrics::load(tmp.path());
+        assert_eq!(calls.len(), 1);
+        assert_eq!((calls[0].model.as_str(), calls[0].ok), ("sonnet", true));
+        assert!(ModelCheck::load(tmp.path()).unwrap().ok);
+
+        // No budget left: no ping, nothing learned.
+        let mut st = state();
+        st.record_ai_calls("2026-07-01", 2);
+        st.save(tmp.path()).unwrap();
+        validate_model_with(tmp.path(), "opus", 3, now, || panic!("pinged")).unwrap();
+        assert_eq!(ModelCheck::load(tmp.path()).unwrap().model, "sonnet");
+    }
+
+    #[test]
+    fn inconclusive_probe_is_not_retried_for_a_while() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let at = |s: &str| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let offline = || probe("connect ECONNREFUSED api.anthropic.com");
+        validate_model_with(tmp.path(), "sonnet", 10, at("2026-07-01T10:00:00Z"), offline)
+            .unwrap();
+        let check = ModelCheck::load(tmp.path()).unwrap();
+        assert!(check.ok && check.inconclusive, "{check:?}");
+        assert!(check.detail.contains("ECONNREFUSED"));
+
+        // Within the hour: no ping.
+        validate_model_with(tmp.path(), "sonnet", 10, at("2026-07-01T10:30:00Z"), || {
+            panic!("pinged")
+        })
+        .unwrap();
+        // Later: pinged again, and a model error is now reported.
+        let bad = || probe("There's an issue with the selected model (sonnet).");
+        let err = validate_model_with(tmp.path(), "sonnet", 10, at("2026-07-01T11:05:00Z"), bad)
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown model 'sonnet'"));
+        let state = crate::store::state::RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(state.budget_remaining("2026-07-01", 10), 8, "both pings counted");
+    }
+}
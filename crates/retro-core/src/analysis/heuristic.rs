@@ -0,0 +1,398 @@
+//! AI-free fallback analysis: deterministic extraction of explicit user
+//! directives ("always run clippy before committing", "never push to main").
+//! These are exactly what the analysis prompt tells the model to prioritize,
+//! so they're the one signal worth harvesting when the claude CLI is
+//! unavailable. Candidates get conservative confidence (≤ 0.6, below the
+//! default projection threshold) — they accumulate as evidence and only
+//! reach CLAUDE.md once a real analysis pass confirms them.
+
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use regex::Regex;
+
+use super::v3::V3AnalyzeResult;
+use crate::errors::CoreError;
+use crate::models::Session;
+use crate::store::{Node, NodeType, Scope, Store};
+use crate::util::normalized_similarity;
+
+/// Confidence for a directive seen in one session / repeated across sessions.
+const SINGLE_CONFIDENCE: f64 = 0.5;
+const REPEATED_CONFIDENCE: f64 = 0.6;
+/// Normalized similarity above which two directives are the same rule
+/// (same cut-off as `retro lint`'s near-duplicate check).
+const DUPLICATE_SIMILARITY: f64 = 0.8;
+const MIN_DIRECTIVE_CHARS: usize = 12;
+const MAX_DIRECTIVE_CHARS: usize = 200;
+
+struct Patterns {
+    /// Sentence-initial directive keyword followed by the next word.
+    directive: Regex,
+    fenced_code: Regex,
+    inline_quoted: Regex,
+}
+
+static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+
+fn patterns() -> &'static Patterns {
+    PATTERNS.get_or_init(|| Patterns {
+        directive: Regex::new(
+            r"(?i)^(?:please\s+|and\s+|also\s+|so\s+)?(?:you\s+)?(always|never|must not|must|don't ever|do not ever)\s+([a-z'-]+)",
+        )
+        .unwrap(),
+        fenced_code: Regex::new(r"(?s)```.*?(```|$)").unwrap(),
+        inline_quoted: Regex::new(r#"`[^`]*`|"[^"]*"|“[^”]*”"#).unwrap(),
+    })
+}
+
+/// Words that, right after the keyword, mark a statement rather than an
+/// instruction ("always has been", "never the right call").
+const NON_IMPERATIVE: &[&str] = &[
+    "a", "an", "the", "is", "was", "were", "are", "has", "have", "had", "seems", "seemed", "it",
+    "i", "we", "they", "he", "she", "this", "that", "been", "gonna", "mind",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectiveCandidate {
+    /// Normalized rule text: first letter uppercased, terminal period.
+    pub text: String,
+    pub confidence: f64,
+    pub session_ids: Vec<String>,
+}
+
+/// Extract explicit directives from user messages. Code blocks, quoted text
+/// and questions are ignored; near-identical directives merge across
+/// sessions (and gain confidence when repeated in more than one).
+pub fn extract_directive_candidates(sessions: &[Session]) -> Vec<DirectiveCandidate> {
+    let mut out: Vec<DirectiveCandidate> = Vec::new();
+    for session in sessions {
+        for msg in &session.user_messages {
+            for text in directives_in(&msg.text) {
+                let key = text.to_lowercase();
+                match out.iter_mut().find(|c| {
+                    normalized_similarity(&c.text.to_lowercase(), &key) > DUPLICATE_SIMILARITY
+                }) {
+                    Some(c) => {
+                        if !c.session_ids.contains(&session.session_id) {
+                            c.session_ids.push(session.session_id.clone());
+                            c.confidence = REPEATED_CONFIDENCE;
+                        }
+                    }
+                    None => out.push(DirectiveCandidate {
+                        text,
+                        confidence: SINGLE_CONFIDENCE,
+                        session_ids: vec![session.session_id.clone()],
+                    }),
+                }
+            }
+        }
+    }
+    out
+}
+
+fn directives_in(message: &str) -> Vec<String> {
+    let without_code = patterns().fenced_code.replace_all(message, "\n");
+    let mut found = Vec::new();
+    for line in without_code.lines() {
+        let line = line.trim();
+        // Quoted replies ("> always use X" from a pasted doc) aren't the user's voice.
+        if line.starts_with('>') {
+            continue;
+        }
+        let line = patterns().inline_quoted.replace_all(line, "…");
+        for sentence in split_sentences(&line) {
+            if let Some(d) = directive(sentence) {
+                found.push(d);
+            }
+        }
+    }
+    found
+}
+
+/// Split on sentence terminators, keeping `?` attached so questions can be
+/// recognized and dropped. A `.` only ends a sentence before whitespace or
+/// end of line, so `.env` and `main.rs` stay intact.
+fn split_sentences(line: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let ends = match ch {
+            '!' | '?' | ';' => true,
+            '.' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends {
+            let end = i + ch.len_utf8();
+            out.push(line[start..end].trim());
+            start = end;
+        }
+    }
+    out.push(line[start..].trim());
+    out.retain(|s| !s.is_empty());
+    out
+}
+
+fn directive(sentence: &str) -> Option<String> {
+    if sentence.ends_with('?') || sentence.contains('…') {
+        return None;
+    }
+    let len = sentence.chars().count();
+    if !(MIN_DIRECTIVE_CHARS..=MAX_DIRECTIVE_CHARS).contains(&len) {
+        return None;
+    }
+    let caps = patterns().directive.captures(sentence)?;
+    let next = caps.get(2)?.as_str().to_lowercase();
+    if NON_IMPERATIVE.contains(&next.as_str()) {
+        return None;
+    }
+    let body = sentence.trim_end_matches(['.', '!', ';']).trim();
+    let mut chars = body.chars();
+    let first = chars.next()?;
+    Some(format!("{}{}.", first.to_uppercase(), chars.as_str()))
+}
+
+/// Heuristic counterpart of `v3::analyze_sessions`: no backend call. Each
+/// candidate not already covered by an active node in scope becomes a new
+/// rule node (project-scoped when `project_slug` is given).
+pub fn analyze_sessions(
+    store: &Store,
+    sessions: &[Session],
+    project_slug: Option<&str>,
+) -> Result<V3AnalyzeResult, CoreError> {
+    let mut result = V3AnalyzeResult::default();
+    let signal: Vec<Session> = sessions
+        .iter()
+        .filter(|s| s.user_messages.len() >= 2)
+        .cloned()
+        .collect();
+    if signal.is_empty() {
+        return Ok(result);
+    }
+    result.sessions_analyzed = signal.len();
+
+    let loaded = store.load_all()?;
+    let existing: Vec<String> = loaded
+        .nodes
+        .iter()
+        .map(|(_, n)| n)
+        .filter(|n| n.is_active())
+        .filter(|n| match (&n.scope, project_slug) {
+            (Scope::Global, _) => true,
+            (Scope::Project(slug), Some(p)) => slug == p,
+            (Scope::Project(_), None) => false,
+        })
+        .map(|n| n.body.to_lowercase())
+        .collect();
+    let scope = match project_slug {
+        Some(slug) => Scope::Project(slug.to_string()),
+        None => Scope::Global,
+    };
+    let today = Utc::now().date_naive();
+
+    let candidates = extract_directive_candidates(&signal);
+    result.reasoning = format!(
+        "heuristic: {} explicit directive(s) found, no AI call",
+        candidates.len()
+    );
+    for c in candidates {
+        let key = c.text.to_lowercase();
+        if existing
+            .iter()
+            .any(|body| normalized_similarity(body, &key) > DUPLICATE_SIMILARITY)
+        {
+            continue;
+        }
+        let id = store.unique_slug(
+            &c.text
+                .split_whitespace()
+                .take(8)
+                .collect::<Vec<_>>()
+                .join(" "),
+            &scope,
+        );
+        let node = Node {
+            id,
+            scope: scope.clone(),
+            node_type: NodeType::Rule,
+            confidence: c.confidence,
+            sources: c
+                .session_ids
+                .iter()
+                .map(|s| format!("session:{s}"))
+                .collect(),
+            created: today,
+            updated: today,
+            invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
+            body: c.text,
+        };
+        store.write_node(&node)?;
+        result.learned.push(node.body);
+        result.nodes_created += 1;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ParsedUserMessage, SessionMetadata};
+    use tempfile::TempDir;
+
+    fn session(id: &str, msgs: &[&str]) -> Session {
+        Session {
+            session_id: id.to_string(),
+            project: "/p".to_string(),
+            session_path: String::new(),
+            user_messages: msgs
+                .iter()
+                .map(|t| ParsedUserMessage {
+                    text: t.to_string(),
+                    timestamp: None,
+                    is_correction: false,
+                })
+                .collect(),
+            assistant_messages: vec![],
+            summaries: vec![],
+            tools_used: vec![],
+            errors: vec![],
+            metadata: SessionMetadata {
+                cwd: None,
+                version: None,
+                git_branch: None,
+                model: None,
+                git_branches: vec![],
+            },
+            unknown_entry_types: Default::default(),
+        }
+    }
+
+    fn texts(msg: &str) -> Vec<String> {
+        directives_in(msg)
+    }
+
+    #[test]
+    fn positive_examples() {
+        for (msg, want) in [
+            (
+                "always run cargo clippy before committing",
+                "Always run cargo clippy before committing.",
+            ),
+            (
+                "Never push directly to main!",
+                "Never push directly to main.",
+            ),
+            (
+                "ok thanks. please always use uv instead of pip",
+                "Please always use uv instead of pip.",
+            ),
+            (
+                "You must not edit generated files",
+                "You must not edit generated files.",
+            ),
+            (
+                "don't ever force-push shared branches",
+                "Don't ever force-push shared branches.",
+            ),
+            (
+                "never edit src/main.rs by hand. thanks",
+                "Never edit src/main.rs by hand.",
+            ),
+            (
+                "Looks good; also never skip the migration tests",
+                "Also never skip the migration tests.",
+            ),
+        ] {
+            assert_eq!(texts(msg), vec![want.to_string()], "input: {msg}");
+        }
+    }
+
+    #[test]
+    fn negative_examples() {
+        for msg in [
+            "does it always fail on CI?",
+            "why do we never see this in prod?",
+            "it has always been like this",
+            "That was never the plan",
+            "always", // too short
+            "I said \"always use tabs\" in the doc, ignore that",
+            "the comment says `never call unwrap here`",
+            "> Always prefer composition over inheritance\nthoughts on this?",
+            "here is the config:\n```\n# always run setup first\nnever = true\n```",
+            "we always deploy on fridays",
+        ] {
+            assert!(
+                texts(msg).is_empty(),
+                "unexpected directive in: {msg:?} -> {:?}",
+                texts(msg)
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_directives_merge_and_gain_confidence() {
+        let sessions = vec![
+            session("a", &["always run cargo fmt before committing", "thanks"]),
+            session("b", &["Always run cargo fmt before committing.", "ok"]),
+            session("c", &["never commit .env files", "done"]),
+        ];
+        let c = extract_directive_candidates(&sessions);
+        assert_eq!(c.len(), 2);
+        assert_eq!(c[0].session_ids, vec!["a", "b"]);
+        assert!((c[0].confidence - REPEATED_CONFIDENCE).abs() < f64::EPSILON);
+        assert!((c[1].confidence - SINGLE_CONFIDENCE).abs() < f64::EPSILON);
+        assert!(c.iter().all(|c| c.confidence <= 0.6));
+    }
+
+    #[test]
+    fn analyze_writes_new_rules_and_skips_known_ones() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let today = Utc::now().date_naive();
+        store
+            .write_node(&Node {
+                id: "never-commit-env-files".to_string(),
+                scope: Scope::Global,
+                node_type: NodeType::Rule,
+                confidence: 0.8,
+                sources: vec![],
+                created: today,
+                updated: today,
+                invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
+                body: "Never commit .env files.".to_string(),
+            })
+            .unwrap();
+        let sessions = vec![session(
+            "s1",
+            &["never commit .env files", "always run the linter first"],
+        )];
+        let r = analyze_sessions(&store, &sessions, Some("app")).unwrap();
+        assert_eq!(r.nodes_created, 1);
+        let loaded = store.load_all().unwrap();
+        let created = loaded
+            .nodes
+            .iter()
+            .map(|(_, n)| n)
+            .find(|n| n.scope == Scope::Project("app".into()))
+            .unwrap();
+        assert_eq!(created.body, "Always run the linter first.");
+        assert_eq!(created.sources, vec!["session:s1"]);
+        assert!(created.confidence < 0.7, "below the projection threshold");
+    }
+
+    #[test]
+    fn low_signal_sessions_are_ignored() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let r = analyze_sessions(&store, &[session("s", &["always do X now"])], None).unwrap();
+        assert_eq!(r.sessions_analyzed, 0);
+        assert_eq!(r.nodes_created, 0);
+    }
+}
@@ -0,0 +1,195 @@
+//! `[analysis] output_language`: the language node content is written in,
+//! whatever language the sessions were in. The prompt asks for it; since the
+//! model doesn't always comply, [`detect`] is a cheap check on what came
+//! back — script for non-Latin languages, stopword counts for Latin ones. It
+//! only answers when the text is obviously one language, so short or mixed
+//! rules pass.
+
+/// Languages [`detect`] knows: (code, English name, stopwords). Stopwords
+/// are distinctive, lowercase, and common in imperative rules.
+const LATIN: &[(&str, &str, &[&str])] = &[
+    (
+        "en",
+        "English",
+        &[
+            "the", "and", "is", "are", "to", "of", "for", "with", "before", "after", "use",
+            "always", "never", "when", "not", "don't", "instead", "this", "that", "run", "prefer",
+        ],
+    ),
+    (
+        "de",
+        "German",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "mit", "für", "vor", "immer", "nie",
+            "niemals", "wenn", "statt", "anstatt", "ein", "eine", "zu", "den", "dem", "verwende",
+            "verwenden", "benutze", "auf", "bei", "keine", "oder",
+        ],
+    ),
+    (
+        "fr",
+        "French",
+        &[
+            "le", "la", "les", "et", "est", "pas", "avec", "pour", "avant", "toujours", "jamais",
+            "quand", "une", "des", "du", "ne", "utiliser", "utilise", "dans", "au", "lieu",
+        ],
+    ),
+    (
+        "es",
+        "Spanish",
+        &[
+            "el", "los", "las", "y", "es", "con", "para", "antes", "siempre", "nunca", "cuando",
+            "una", "del", "usar", "usa", "que", "en", "vez",
+        ],
+    ),
+    (
+        "it",
+        "Italian",
+        &[
+            "il", "gli", "è", "non", "con", "per", "prima", "sempre", "mai", "quando", "invece",
+            "una", "usare", "usa", "che", "di", "della",
+        ],
+    ),
+    (
+        "nl",
+        "Dutch",
+        &[
+            "het", "niet", "met", "voor", "altijd", "nooit", "wanneer", "een", "gebruik", "van",
+            "zijn", "geen", "eerst",
+        ],
+    ),
+    (
+        "pt",
+        "Portuguese",
+        &[
+            "os", "não", "com", "para", "antes", "sempre", "nunca", "quando", "uma", "usar", "que",
+            "em", "vez", "do", "da",
+        ],
+    ),
+];
+
+/// Whether a character belongs to a script.
+type InScript = fn(char) -> bool;
+
+/// Non-Latin scripts: (code, English name, character test).
+const SCRIPTS: &[(&str, &str, InScript)] = &[
+    ("ja", "Japanese", |c| matches!(c, '\u{3040}'..='\u{30ff}')),
+    ("ko", "Korean", |c| matches!(c, '\u{ac00}'..='\u{d7af}')),
+    ("zh", "Chinese", |c| matches!(c, '\u{4e00}'..='\u{9fff}')),
+    ("ru", "Russian", |c| matches!(c, '\u{0400}'..='\u{04ff}')),
+    ("el", "Greek", |c| matches!(c, '\u{0370}'..='\u{03ff}')),
+    ("ar", "Arabic", |c| matches!(c, '\u{0600}'..='\u{06ff}')),
+];
+
+/// Stopword hits the winning language needs (and must beat the runner-up by).
+const MIN_HITS: usize = 2;
+
+/// The code [`detect`] uses for a configured language: an ISO 639-1 code
+/// (`de`, `en-US`) or an English name (`German`), case-insensitive. None
+/// for an empty setting or a language the heuristic doesn't know (the
+/// prompt still asks for it; nothing is checked).
+pub fn code(configured: &str) -> Option<&'static str> {
+    let c = configured.trim().to_lowercase();
+    let primary = c.split(['-', '_']).next().unwrap_or_default();
+    LATIN
+        .iter()
+        .map(|(code, name, _)| (*code, *name))
+        .chain(SCRIPTS.iter().map(|(code, name, _)| (*code, *name)))
+        .find(|(code, name)| *code == primary || name.eq_ignore_ascii_case(&c))
+        .map(|(code, _)| code)
+}
+
+/// How the prompt names `configured`: the English name when known.
+pub fn display_name(configured: &str) -> String {
+    let name = code(configured).and_then(|code| {
+        LATIN
+            .iter()
+            .map(|(c, n, _)| (*c, *n))
+            .chain(SCRIPTS.iter().map(|(c, n, _)| (*c, *n)))
+            .find(|(c, _)| *c == code)
+            .map(|(_, n)| n)
+    });
+    name.map(String::from).unwrap_or_else(|| configured.trim().to_string())
+}
+
+/// The language `text` is obviously written in, if any. Inline code
+/// (`` `...` ``) is ignored: commands and identifiers are not prose.
+pub fn detect(text: &str) -> Option<&'static str> {
+    let prose: String = text
+        .split('`')
+        .enumerate()
+        .filter(|(i, _)| i % 2 == 0)
+        .map(|(_, part)| part)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let letters = prose.chars().filter(|c| c.is_alphabetic()).count();
+    for (code, _, is_script) in SCRIPTS {
+        // Kana marks Japanese even among kanji, so it is checked first.
+        if letters > 0 && prose.chars().filter(|c| is_script(*c)).count() * 3 >= letters {
+            return Some(code);
+        }
+    }
+    let words: Vec<String> = prose
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut scores: Vec<(usize, &'static str)> = LATIN
+        .iter()
+        .map(|(code, _, stop)| {
+            let hits = words.iter().filter(|w| stop.contains(&w.as_str())).count();
+            (hits, *code)
+        })
+        .collect();
+    scores.sort_by_key(|(hits, _)| std::cmp::Reverse(*hits));
+    let (best, code) = scores[0];
+    let runner_up = scores[1].0;
+    (best >= MIN_HITS && best >= runner_up + MIN_HITS).then_some(code)
+}
+
+/// The language `text` is obviously written in when that isn't
+/// `configured` (and the heuristic knows `configured`).
+pub fn mismatch(text: &str, configured: &str) -> Option<&'static str> {
+    let want = code(configured)?;
+    detect(text).filter(|got| *got != want)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_languages_resolve_by_code_or_name() {
+        assert_eq!(code("en"), Some("en"));
+        assert_eq!(code("en-US"), Some("en"));
+        assert_eq!(code("German"), Some("de"));
+        assert_eq!(code(""), None);
+        assert_eq!(code("klingon"), None);
+        assert_eq!(display_name("de"), "German");
+        assert_eq!(display_name("klingon"), "klingon");
+    }
+
+    #[test]
+    fn obvious_languages_are_detected_and_short_rules_pass() {
+        assert_eq!(detect("Always run the smoke tests before pushing."), Some("en"));
+        assert_eq!(
+            detect("Verwende immer `uv` statt `pip` für die Python-Installation."),
+            Some("de")
+        );
+        assert_eq!(detect("Utiliser toujours les tests avant le commit."), Some("fr"));
+        assert_eq!(detect("テストを先に実行してください"), Some("ja"));
+        assert_eq!(detect("Используйте uv вместо pip"), Some("ru"));
+        // too little prose to call: never flagged
+        assert_eq!(detect("`cargo clippy -- -D warnings`"), None);
+        assert_eq!(detect("Prefer rg."), None);
+    }
+
+    #[test]
+    fn mismatch_needs_a_known_configured_language() {
+        let german = "Führe die Tests immer vor dem Push aus, nicht danach.";
+        assert_eq!(mismatch(german, "en"), Some("de"));
+        assert_eq!(mismatch(german, "de"), None);
+        assert_eq!(mismatch(german, ""), None);
+        assert_eq!(mismatch(german, "klingon"), None);
+        assert_eq!(mismatch("Run the tests before every push.", "en"), None);
+    }
+}
@@ -0,0 +1,246 @@
+//! Per-call AI backend metrics at `<store>/state/backend_calls.jsonl`.
+//! `MeteredBackend` wraps any `AnalysisBackend` and appends one record per
+//! call; `retro status --ai` summarizes them. Machine-local and best-effort:
+//! a metrics write never fails the call it measures.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::backend::{AnalysisBackend, BackendResponse};
+use crate::errors::CoreError;
+
+/// Records older than this are swept on the next write.
+const RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendCall {
+    /// RFC3339 (UTC) time the call finished.
+    pub at: String,
+    pub model: String,
+    pub duration_ms: u64,
+    pub ok: bool,
+    /// The backend killed the call at its timeout.
+    #[serde(default)]
+    pub timed_out: bool,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+fn metrics_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("backend_calls.jsonl")
+}
+
+/// All recorded calls, oldest first. Unreadable lines are skipped.
+pub fn load(store_root: &Path) -> Vec<BackendCall> {
+    std::fs::read_to_string(metrics_path(store_root))
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|l| serde_json::from_str(l).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append one call, sweeping records past retention. Errors are returned so
+/// tests can see them; `MeteredBackend` discards them.
+pub fn record(store_root: &Path, call: &BackendCall) -> Result<(), CoreError> {
+    let cutoff = Utc::now() - chrono::Duration::days(RETENTION_DAYS);
+    let mut calls: Vec<BackendCall> = load(store_root)
+        .into_iter()
+        .filter(|c| parse_at(c).is_some_and(|at| at >= cutoff))
+        .collect();
+    calls.push(call.clone());
+    let mut out = String::new();
+    for c in &calls {
+        out.push_str(&serde_json::to_string(c).map_err(|e| CoreError::Parse(e.to_string()))?);
+        out.push('\n');
+    }
+    let io = |e: std::io::Error| CoreError::Io(e.to_string());
+    let path = metrics_path(store_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io)?;
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, out).map_err(io)?;
+    std::fs::rename(&tmp, &path).map_err(io)
+}
+
+fn parse_at(call: &BackendCall) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&call.at)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Aggregate over calls at or after `since`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BackendStats {
+    pub calls: usize,
+    pub failures: usize,
+    pub timeouts: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+pub fn summarize(calls: &[BackendCall], since: DateTime<Utc>) -> BackendStats {
+    let window: Vec<&BackendCall> = calls
+        .iter()
+        .filter(|c| parse_at(c).is_some_and(|at| at >= since))
+        .collect();
+    let mut durations: Vec<u64> = window.iter().map(|c| c.duration_ms).collect();
+    durations.sort_unstable();
+    BackendStats {
+        calls: window.len(),
+        failures: window.iter().filter(|c| !c.ok).count(),
+        timeouts: window.iter().filter(|c| c.timed_out).count(),
+        p50_ms: percentile(&durations, 50),
+        p95_ms: percentile(&durations, 95),
+        input_tokens: window.iter().map(|c| c.input_tokens).sum(),
+        output_tokens: window.iter().map(|c| c.output_tokens).sum(),
+    }
+}
+
+/// Nearest-rank percentile over sorted values (0 when empty).
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Wraps a backend and records a `BackendCall` for every `execute`.
+pub struct MeteredBackend<'a, B: AnalysisBackend> {
+    inner: &'a B,
+    store_root: &'a Path,
+    model: String,
+}
+
+impl<'a, B: AnalysisBackend> MeteredBackend<'a, B> {
+    pub fn new(inner: &'a B, store_root: &'a Path, model: &str) -> Self {
+        MeteredBackend {
+            inner,
+            store_root,
+            model: model.to_string(),
+        }
+    }
+
+    /// Run `call`, recording it as a call to `model`.
+    fn metered(
+        &self,
+        model: &str,
+        call: impl FnOnce() -> Result<BackendResponse, CoreError>,
+    ) -> Result<BackendResponse, CoreError> {
+        let start = Instant::now();
+        let result = call();
+        let (ok, timed_out, input_tokens, output_tokens) = match &result {
+            Ok(r) => (true, false, r.input_tokens, r.output_tokens),
+            Err(e) => (false, e.to_string().contains("timed out"), 0, 0),
+        };
+        let _ = record(
+            self.store_root,
+            &BackendCall {
+                at: Utc::now().to_rfc3339(),
+                model: model.to_string(),
+                duration_ms: start.elapsed().as_millis() as u64,
+                ok,
+                timed_out,
+                input_tokens,
+                output_tokens,
+            },
+        );
+        result
+    }
+}
+
+impl<B: AnalysisBackend> AnalysisBackend for MeteredBackend<'_, B> {
+    fn execute(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+    ) -> Result<BackendResponse, CoreError> {
+        self.metered(&self.model, || self.inner.execute(prompt, json_schema))
+    }
+
+    fn execute_with_model(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+        model: &str,
+    ) -> Result<BackendResponse, CoreError> {
+        self.metered(model, || self.inner.execute_with_model(prompt, json_schema, model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::backend::MockBackend;
+    use tempfile::TempDir;
+
+    fn call(at: &str, duration_ms: u64, ok: bool) -> BackendCall {
+        BackendCall {
+            at: at.to_string(),
+            model: "sonnet".to_string(),
+            duration_ms,
+            ok,
+            timed_out: false,
+            input_tokens: 10,
+            output_tokens: 5,
+        }
+    }
+
+    #[test]
+    fn summarize_windows_and_percentiles() {
+        let mut calls: Vec<BackendCall> = (1..=20)
+            .map(|i| call("2026-03-02T00:00:00Z", i * 100, true))
+            .collect();
+        calls.push(call("2026-02-01T00:00:00Z", 99_999, false)); // outside window
+        calls[0].ok = false;
+        calls[1].timed_out = true;
+        let since = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let stats = summarize(&calls, since);
+        assert_eq!(stats.calls, 20);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.p50_ms, 1000);
+        assert_eq!(stats.p95_ms, 1900);
+        assert_eq!(stats.input_tokens, 200);
+        assert_eq!(stats.output_tokens, 100);
+        assert_eq!(summarize(&[], since), BackendStats::default());
+    }
+
+    #[test]
+    fn metered_backend_records_success_and_failure() {
+        let tmp = TempDir::new().unwrap();
+        let mock = MockBackend::with_responses(vec!["{}".to_string()]);
+        let metered = MeteredBackend::new(&mock, tmp.path(), "haiku");
+        assert!(metered.execute("p", None).is_ok());
+        assert!(metered.execute("p", None).is_err()); // mock exhausted
+        let calls = load(tmp.path());
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].ok);
+        assert_eq!(calls[0].input_tokens, 100);
+        assert_eq!(calls[0].model, "haiku");
+        assert!(!calls[1].ok);
+    }
+
+    #[test]
+    fn record_sweeps_expired_entries() {
+        let tmp = TempDir::new().unwrap();
+        record(tmp.path(), &call("2020-01-01T00:00:00Z", 1, true)).unwrap();
+        record(tmp.path(), &call(&Utc::now().to_rfc3339(), 2, true)).unwrap();
+        let calls = load(tmp.path());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].duration_ms, 2);
+    }
+}
@@ -1,15 +1,18 @@
 pub mod backend;
 pub mod claude_cli;
-pub mod prompts;
+pub mod heuristic;
+pub mod language;
+pub mod metrics;
+pub mod overrides;
+pub(crate) mod prompts;
+pub mod summarize;
 pub mod v3;
 
 use crate::errors::CoreError;
 use crate::models::{EdgeType, GraphAnalysisResponse, GraphOperation, NodeScope, NodeType};
 
-pub const BATCH_SIZE: usize = 20;
-
 /// JSON schema for v2 graph-based analysis responses.
-pub const GRAPH_ANALYSIS_RESPONSE_SCHEMA: &str = r#"{
+pub(crate) const GRAPH_ANALYSIS_RESPONSE_SCHEMA: &str = r#"{
     "type": "object",
     "properties": {
         "reasoning": { "type": "string", "description": "1-2 sentence summary of what you observed" },
@@ -24,6 +27,7 @@ pub const GRAPH_ANALYSIS_RESPONSE_SCHEMA: &str = r#"{
                     "project_id": { "type": "string" },
                     "content": { "type": "string" },
                     "confidence": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                    "section": { "type": ["string", "null"] },
                     "node_id": { "type": "string" },
                     "new_confidence": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
                     "new_content": { "type": "string" },
@@ -76,6 +80,12 @@ pub fn parse_graph_response_full(
                     project_id,
                     content: op_resp.content.clone().unwrap_or_default(),
                     confidence: op_resp.confidence.unwrap_or(0.5),
+                    section: op_resp
+                        .section
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
                 });
             }
             "update_node" => {
@@ -159,4 +169,28 @@ mod tests {
             _ => panic!("Expected UpdateNode"),
         }
     }
+
+    #[test]
+    fn test_parse_graph_response_section_is_optional() {
+        let schema: serde_json::Value =
+            serde_json::from_str(GRAPH_ANALYSIS_RESPONSE_SCHEMA).unwrap();
+        let props = &schema["properties"]["operations"]["items"]["properties"];
+        assert_eq!(props["section"]["type"], serde_json::json!(["string", "null"]));
+
+        let json = r#"{"reasoning": "", "operations": [
+            {"action": "create_node", "content": "a", "section": " Testing "},
+            {"action": "create_node", "content": "b", "section": null},
+            {"action": "create_node", "content": "c", "section": ""},
+            {"action": "create_node", "content": "d"}
+        ]}"#;
+        let sections: Vec<Option<String>> = parse_graph_response(json, Some("app"))
+            .unwrap()
+            .into_iter()
+            .map(|op| match op {
+                GraphOperation::CreateNode { section, .. } => section,
+                _ => panic!("Expected CreateNode"),
+            })
+            .collect();
+        assert_eq!(sections, vec![Some("Testing".to_string()), None, None, None]);
+    }
 }
@@ -0,0 +1,188 @@
+//! Prompt overrides: a file in `<store>/prompts/` replaces (or, with
+//! `{builtin}`, wraps) part of a built-in prompt. The store commits it, so
+//! a team sharing the store shares its prompts.
+//!
+//! Overrides are plain text with `{name}` placeholders. A placeholder the
+//! template doesn't know is an error when the overrides load (the run stops
+//! instead of sending a prompt with a typo in it); a known one the override
+//! leaves out is only a warning, shown by `retro status`.
+
+use std::path::{Path, PathBuf};
+
+use crate::errors::CoreError;
+use crate::logging;
+
+/// Override directory under the store root.
+pub const PROMPTS_DIR: &str = "prompts";
+
+/// One overridable piece of a built-in prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Template {
+    pub file: &'static str,
+    /// Placeholders it knows. `builtin` (the built-in text) is always one.
+    pub placeholders: &'static [&'static str],
+}
+
+/// The opening line of the analysis prompt.
+pub const ANALYSIS_PREAMBLE: Template = Template {
+    file: "analysis_preamble.md",
+    placeholders: &["builtin", "project", "session_count"],
+};
+
+/// What a large-session summary call asks for (the reply must still fill
+/// the `instructions`, `corrections` and `errors` lists).
+pub const SESSION_SUMMARY: Template = Template {
+    file: "session_summary.md",
+    placeholders: &["builtin", "session_id", "project", "part", "total"],
+};
+
+pub const TEMPLATES: &[Template] = &[ANALYSIS_PREAMBLE, SESSION_SUMMARY];
+
+/// An override file found in the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Override {
+    pub template: Template,
+    pub text: String,
+    /// Known placeholders (other than `builtin`) the text leaves out.
+    pub warnings: Vec<String>,
+}
+
+/// The overrides a run uses; `None` keeps the built-in text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptOverrides {
+    pub analysis_preamble: Option<String>,
+    pub session_summary: Option<String>,
+}
+
+pub fn dir(store_root: &Path) -> PathBuf {
+    store_root.join(PROMPTS_DIR)
+}
+
+/// The `{name}` placeholders in `text`, in order. Braces around anything
+/// but a lowercase identifier (JSON, code) are left alone.
+pub fn placeholders(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find('}') else { break };
+        let name = &rest[..close];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+            found.push(name);
+            rest = &rest[close + 1..];
+        }
+    }
+    found
+}
+
+/// Check `text` against `template`: an unknown placeholder is an error;
+/// the known ones it leaves out come back as warnings.
+pub fn check(template: &Template, text: &str) -> Result<Vec<String>, CoreError> {
+    let used = placeholders(text);
+    if let Some(unknown) = used.iter().find(|p| !template.placeholders.contains(p)) {
+        return Err(CoreError::Config(format!(
+            "{PROMPTS_DIR}/{}: unknown placeholder {{{unknown}}} (known: {})",
+            template.file,
+            template
+                .placeholders
+                .iter()
+                .map(|p| format!("{{{p}}}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    Ok(template
+        .placeholders
+        .iter()
+        .filter(|p| **p != "builtin" && !used.contains(p))
+        .map(|p| format!("{PROMPTS_DIR}/{} doesn't use {{{p}}}", template.file))
+        .collect())
+}
+
+/// Substitute `vars` into `text`. Anything else in braces stays as it is.
+pub fn render(text: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(text.to_string(), |out, (name, value)| out.replace(&format!("{{{name}}}"), value))
+}
+
+/// The override files present in the store, checked. Empty files don't
+/// count.
+pub fn active(store_root: &Path) -> Result<Vec<Override>, CoreError> {
+    let mut found = Vec::new();
+    for template in TEMPLATES {
+        let path = dir(store_root).join(template.file);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text.trim().to_string(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(CoreError::Io(format!("{}: {e}", path.display()))),
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let warnings = check(template, &text)?;
+        found.push(Override { template: *template, text, warnings });
+    }
+    Ok(found)
+}
+
+/// Load the overrides for a run.
+pub fn load(store_root: &Path) -> Result<PromptOverrides, CoreError> {
+    let mut overrides = PromptOverrides::default();
+    for o in active(store_root)? {
+        for w in &o.warnings {
+            log::debug!(target: logging::ANALYSIS, "{w}");
+        }
+        if o.template == ANALYSIS_PREAMBLE {
+            overrides.analysis_preamble = Some(o.text);
+        } else if o.template == SESSION_SUMMARY {
+            overrides.session_summary = Some(o.text);
+        }
+    }
+    Ok(overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn placeholders_render_and_other_braces_survive() {
+        let text = "Project {project}: {session_count} sessions. {builtin} {\"a\": 1} {}";
+        assert_eq!(placeholders(text), vec!["project", "session_count", "builtin"]);
+        let out = render(text, &[("project", "app"), ("session_count", "3"), ("builtin", "B.")]);
+        assert_eq!(out, "Project app: 3 sessions. B. {\"a\": 1} {}");
+    }
+
+    #[test]
+    fn unknown_placeholders_error_and_missing_ones_warn() {
+        let err = check(&ANALYSIS_PREAMBLE, "Hi {projct}.").unwrap_err().to_string();
+        assert!(err.contains("analysis_preamble.md: unknown placeholder {projct}"), "{err}");
+        assert!(err.contains("{session_count}"), "lists the known ones: {err}");
+
+        let warnings = check(&ANALYSIS_PREAMBLE, "{builtin} Be terse.").unwrap();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings[0].contains("doesn't use {project}"));
+        assert!(check(&SESSION_SUMMARY, "{builtin}").unwrap().iter().all(|w| !w.contains("builtin")));
+    }
+
+    #[test]
+    fn partial_overrides_leave_the_rest_built_in() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(load(tmp.path()).unwrap(), PromptOverrides::default());
+
+        std::fs::create_dir_all(dir(tmp.path())).unwrap();
+        std::fs::write(dir(tmp.path()).join("session_summary.md"), "  List only {project} rules.\n")
+            .unwrap();
+        std::fs::write(dir(tmp.path()).join("analysis_preamble.md"), "\n").unwrap();
+        let loaded = load(tmp.path()).unwrap();
+        assert_eq!(loaded.session_summary.as_deref(), Some("List only {project} rules."));
+        assert_eq!(loaded.analysis_preamble, None, "an empty file is no override");
+        let found = active(tmp.path()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].template, SESSION_SUMMARY);
+
+        std::fs::write(dir(tmp.path()).join("analysis_preamble.md"), "{oops}").unwrap();
+        assert!(load(tmp.path()).is_err(), "a bad override stops the load");
+    }
+}
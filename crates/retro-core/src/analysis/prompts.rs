@@ -1,23 +1,51 @@
+use super::v3::BatchContext;
 use crate::models::{CompactSession, CompactUserMessage, KnowledgeNode, Session};
 
 const MAX_USER_MSG_LEN: usize = 500;
-const MAX_USER_MSGS_PER_SESSION: usize = 300;
+pub(crate) const MAX_USER_MSGS_PER_SESSION: usize = 300;
+const MAX_CORRECTION_EXCERPTS: usize = 5;
+const MAX_CORRECTION_LEN: usize = 200;
+
+/// Opening line of the analysis prompt (`{builtin}` in an
+/// `analysis_preamble.md` override).
+pub(crate) const ANALYSIS_PREAMBLE: &str =
+    "You are analyzing coding session transcripts to discover patterns, rules, preferences, and skills.";
 
 /// Build the v2 analysis prompt with graph context and scope classification instructions.
+/// `total_existing` is how many nodes the caller had before capping
+/// `existing_nodes`; when larger, the prompt says the list is partial.
+/// From `batch`: global rules earlier batches of the same run proposed
+/// (listed in full so this batch doesn't propose them again), the language
+/// content must be in, and the preamble override.
 pub fn build_graph_analysis_prompt(
     sessions: &[CompactSession],
     existing_nodes: &[KnowledgeNode],
+    total_existing: usize,
     project: Option<&str>,
+    headings: &[String],
+    batch: &BatchContext,
 ) -> String {
     let mut prompt = String::new();
 
-    prompt.push_str("You are analyzing coding session transcripts to discover patterns, rules, preferences, and skills.\n\n");
+    match batch.preamble {
+        Some(preamble) => prompt.push_str(&super::overrides::render(
+            preamble,
+            &[
+                ("builtin", ANALYSIS_PREAMBLE),
+                ("project", project.unwrap_or("all projects")),
+                ("session_count", &sessions.len().to_string()),
+            ],
+        )),
+        None => prompt.push_str(ANALYSIS_PREAMBLE),
+    }
+    prompt.push_str("\n\n");
 
     prompt.push_str("## Scope Classification\n\n");
     prompt.push_str("For each piece of knowledge, classify its scope:\n");
     prompt.push_str("- **global**: Personal style, communication preferences, general coding habits (e.g., 'always use snake_case', 'prefer concise responses')\n");
     prompt.push_str("- **project**: Code-specific conventions, architecture decisions, project tooling (e.g., 'this project uses SQLite WAL mode', 'run cargo test before committing')\n");
-    prompt.push_str("- When ambiguous, default to **project**\n\n");
+    prompt.push_str("- When ambiguous, default to **project**\n");
+    prompt.push_str("- `git_branches` is where a session ran: an instruction given only on a release or feature branch may not hold on the main branch — keep it project-scoped and name the branch in the content when it is clearly branch-specific\n\n");
 
     prompt.push_str("## Node Types\n\n");
     prompt.push_str("- **preference**: How the user likes things done\n");
@@ -30,7 +58,7 @@ pub fn build_graph_analysis_prompt(
     // Include existing knowledge for dedup and relationship detection
     if !existing_nodes.is_empty() {
         prompt.push_str("## Existing Knowledge\n\n");
-        for node in existing_nodes.iter().take(50) {
+        for node in existing_nodes {
             prompt.push_str(&format!(
                 "- [{}] {} ({}) conf={:.2}: {}\n",
                 node.id,
@@ -39,13 +67,34 @@ pub fn build_graph_analysis_prompt(
                 node.confidence,
                 crate::util::truncate_str(&node.content, 200),
             ));
+            if !node.tools.is_empty() {
+                prompt.push_str(&format!("  tools: {}\n", node.tools));
+            }
         }
         prompt.push_str("\n");
+        if existing_nodes.iter().any(|n| !n.tools.is_empty()) {
+            prompt.push_str("`tools` counts the source sessions that used each tool. When updating such a pattern into a reusable workflow, name the tools it actually relies on.\n");
+        }
+        if total_existing > existing_nodes.len() {
+            prompt.push_str(&format!(
+                "This list is partial: the {} entries most relevant to these sessions, out of {total_existing}. Knowledge you don't see may already exist — be conservative about creating nodes for common conventions.\n",
+                existing_nodes.len()
+            ));
+        }
         prompt.push_str("If a session reinforces existing knowledge, emit an update_node with higher confidence.\n");
         prompt.push_str("If new knowledge contradicts existing, note it but still create the new node.\n");
         prompt.push_str("If new knowledge is semantically identical to existing, emit merge_nodes.\n\n");
     }
 
+    if !batch.proposed_earlier.is_empty() {
+        prompt.push_str("## Rules Proposed Earlier in This Run\n\n");
+        prompt.push_str("Earlier batches of this run already proposed these global rules; they are not in CLAUDE.md yet. Do not create them again — a session that only repeats one adds nothing new.\n");
+        for body in batch.proposed_earlier {
+            prompt.push_str(&format!("- {}\n", crate::util::truncate_str(body, 200)));
+        }
+        prompt.push('\n');
+    }
+
     // Include sessions
     prompt.push_str("## Sessions to Analyze\n\n");
     let sessions_json = serde_json::to_string_pretty(&sessions).unwrap_or_default();
@@ -64,8 +113,25 @@ pub fn build_graph_analysis_prompt(
     prompt.push_str("- merge_nodes: Duplicate knowledge detected\n\n");
     prompt.push_str("Be selective. Only emit operations for clear, actionable knowledge. Prefer fewer high-quality nodes over many weak ones.\n");
     prompt.push_str("Explicit user directives ('always', 'never', 'must') get confidence 0.7-0.85.\n");
+    prompt.push_str("`corrections` are user messages that pushed back on the assistant ('no, do X instead', 'you forgot Y') — high-value evidence of recurring mistakes; the same correction across sessions warrants a rule.\n");
     prompt.push_str("Single-session observations get confidence 0.4-0.5.\n");
 
+    if !headings.is_empty() {
+        prompt.push_str("\n## Project CLAUDE.md Sections\n\n");
+        prompt.push_str("The project's CLAUDE.md is organized under these headings:\n");
+        for h in headings {
+            prompt.push_str(&format!("- {h}\n"));
+        }
+        prompt.push_str("For each project-scoped create_node, set `section` to the heading the rule belongs under, copied exactly from this list, or null when none fits. Never invent a heading.\n");
+    }
+
+    if !batch.output_language.trim().is_empty() {
+        prompt.push_str(&format!(
+            "\n## Output Language\n\nWrite all node content in {} regardless of the language of the sessions — translate what the user said. Keep commands, code, and identifiers verbatim.\n",
+            super::language::display_name(batch.output_language)
+        ));
+    }
+
     prompt
 }
 
@@ -86,6 +152,13 @@ pub fn to_compact_session(session: &Session) -> CompactSession {
         .filter_map(|m| m.thinking_summary.clone())
         .collect();
 
+    let corrections: Vec<&str> = session
+        .user_messages
+        .iter()
+        .filter(|m| m.is_correction)
+        .map(|m| m.text.as_str())
+        .collect();
+
     CompactSession {
         session_id: session.session_id.clone(),
         project: session.project.clone(),
@@ -94,6 +167,14 @@ pub fn to_compact_session(session: &Session) -> CompactSession {
         errors: session.errors.clone(),
         thinking_highlights,
         summaries: session.summaries.clone(),
+        git_branches: session.metadata.git_branches.clone(),
+        claude_version: session.metadata.version.clone(),
+        corrections_count: corrections.len(),
+        corrections: corrections
+            .iter()
+            .take(MAX_CORRECTION_EXCERPTS)
+            .map(|t| truncate_str(t, MAX_CORRECTION_LEN))
+            .collect(),
     }
 }
 
@@ -123,6 +204,7 @@ mod tests {
                 .map(|t| crate::models::ParsedUserMessage {
                     text: t.to_string(),
                     timestamp: None,
+                    is_correction: false,
                 })
                 .collect(),
             assistant_messages: vec![],
@@ -134,7 +216,9 @@ mod tests {
                 version: None,
                 git_branch: None,
                 model: None,
+                git_branches: vec![],
             },
+            unknown_entry_types: Default::default(),
         }
     }
 
@@ -158,17 +242,85 @@ mod tests {
             scope: crate::models::NodeScope::Global,
             content: "Always run tests".to_string(),
             confidence: 0.8,
+            tools: String::new(),
         }];
-        let prompt = build_graph_analysis_prompt(&compact, &nodes, Some("my-app"));
+        let none = BatchContext::default();
+        let prompt = build_graph_analysis_prompt(&compact, &nodes, 1, Some("my-app"), &[], &none);
         assert!(prompt.contains("existing-rule"));
+        assert!(!prompt.contains("This list is partial"));
+        let capped = build_graph_analysis_prompt(&compact, &nodes, 120, None, &[], &none);
+        assert!(capped.contains("the 1 entries most relevant to these sessions, out of 120"));
         assert!(prompt.contains("please add tests"));
         assert!(prompt.contains("Current project: my-app"));
+        assert!(!prompt.contains("Proposed Earlier"));
+        let proposed = ["Prefer rg over grep.".to_string()];
+        let batch = BatchContext { proposed_earlier: &proposed, ..Default::default() };
+        let again = build_graph_analysis_prompt(&compact, &nodes, 1, None, &[], &batch);
+        assert!(again.contains("## Rules Proposed Earlier in This Run"));
+        assert!(again.contains("- Prefer rg over grep.\n"));
+    }
+
+    #[test]
+    fn test_to_compact_session_surfaces_capped_corrections() {
+        let mut s = session("sess-1", &["add a flag"; 8]);
+        for m in s.user_messages.iter_mut().skip(1) {
+            m.text = "no, use the helper".to_string();
+            m.is_correction = true;
+        }
+        let compact = to_compact_session(&s);
+        assert_eq!(compact.corrections_count, 7);
+        assert_eq!(compact.corrections.len(), MAX_CORRECTION_EXCERPTS);
+        let json = serde_json::to_string(&compact).unwrap();
+        assert!(json.contains("\"corrections_count\":7"));
+
+        let mut clean = session("sess-2", &["hello"]);
+        clean.metadata.git_branches = vec!["main".to_string(), "release/2.x".to_string()];
+        let clean = to_compact_session(&clean);
+        assert_eq!(clean.git_branches, vec!["main", "release/2.x"]);
+        let json = serde_json::to_string(&clean).unwrap();
+        assert!(!json.contains("corrections"), "omitted when none: {json}");
     }
 
     #[test]
     fn test_build_graph_analysis_prompt_no_existing_nodes() {
         let compact = vec![to_compact_session(&session("sess-1", &["hello"]))];
-        let prompt = build_graph_analysis_prompt(&compact, &[], None);
+        let none = BatchContext::default();
+        let prompt = build_graph_analysis_prompt(&compact, &[], 0, None, &[], &none);
         assert!(!prompt.contains("## Existing Knowledge"));
+        assert!(!prompt.contains("section"), "no headings: no section guidance");
+        let headings = ["Build".to_string(), "Testing".to_string()];
+        let prompt = build_graph_analysis_prompt(&compact, &[], 0, None, &headings, &none);
+        assert!(prompt.contains("## Project CLAUDE.md Sections\n\n"));
+        assert!(prompt.contains("- Build\n- Testing\n"));
+        assert!(prompt.contains("or null when none fits"));
+        assert!(!prompt.contains("## Output Language"), "unset: no instruction");
+    }
+
+    #[test]
+    fn test_build_graph_analysis_prompt_output_language() {
+        let compact = vec![to_compact_session(&session("sess-1", &["Bitte teste zuerst"]))];
+        let batch = BatchContext { output_language: "en", ..Default::default() };
+        let prompt = build_graph_analysis_prompt(&compact, &[], 0, None, &[], &batch);
+        assert!(prompt.contains("## Output Language"));
+        assert!(prompt.contains("Write all node content in English regardless"));
+        let batch = BatchContext { output_language: "Tagalog", ..Default::default() };
+        let prompt = build_graph_analysis_prompt(&compact, &[], 0, None, &[], &batch);
+        assert!(prompt.contains("content in Tagalog regardless"), "unknown names pass through");
+    }
+
+    #[test]
+    fn test_build_graph_analysis_prompt_preamble_override() {
+        let compact = vec![to_compact_session(&session("sess-1", &["hello"]))];
+        let none = BatchContext::default();
+        let prompt = build_graph_analysis_prompt(&compact, &[], 0, None, &[], &none);
+        assert!(prompt.starts_with(&format!("{ANALYSIS_PREAMBLE}\n\n")));
+        let batch = BatchContext {
+            preamble: Some("{builtin} Look at {session_count} session(s) of {project}; be terse."),
+            ..Default::default()
+        };
+        let prompt = build_graph_analysis_prompt(&compact, &[], 0, Some("app"), &[], &batch);
+        assert!(prompt.starts_with(&format!(
+            "{ANALYSIS_PREAMBLE} Look at 1 session(s) of app; be terse.\n\n## Scope"
+        )));
     }
 }
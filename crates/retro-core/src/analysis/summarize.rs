@@ -0,0 +1,491 @@
+//! `[analysis] summarize_large_sessions`: sessions too large for the
+//! analysis prompt are condensed instead of truncated. Map: the session's
+//! user messages are split into parts, each summarized by a cheap call on
+//! `[ai] summary_model` into key instructions, corrections and errors.
+//! Reduce: the parts are merged locally (deduplicated, capped). The summary
+//! then stands in for the raw messages in the session's `CompactSession`.
+//!
+//! Summaries are cached in `<store>/state/session_summaries.json`, keyed by
+//! session id and a digest of the transcript, so a session is summarized
+//! once. A failed call leaves the session as it was: the analysis prompt
+//! truncates it as before.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::backend::AnalysisBackend;
+use crate::errors::CoreError;
+use crate::logging;
+use crate::models::{ParsedUserMessage, Session};
+
+/// Raw user text above which a session counts as large (the prompt keeps
+/// 500 chars of each of the first 300 messages).
+pub const LARGE_SESSION_BYTES: usize = 60_000;
+
+/// User text per summary call.
+const PART_BYTES: usize = 100_000;
+
+/// Errors listed in the first part's prompt, each cut to `MAX_ERROR_LEN`.
+const MAX_ERRORS: usize = 30;
+const MAX_ERROR_LEN: usize = 300;
+
+/// Entries per summary list after the reduce step.
+const MAX_ITEMS: usize = 40;
+
+/// Cached summaries kept; the oldest are evicted first.
+const MAX_CACHED: usize = 200;
+
+pub(crate) const SESSION_SUMMARY_SCHEMA: &str = r#"{
+    "type": "object",
+    "properties": {
+        "instructions": { "type": "array", "items": { "type": "string" } },
+        "corrections": { "type": "array", "items": { "type": "string" } },
+        "errors": { "type": "array", "items": { "type": "string" } }
+    },
+    "required": ["instructions", "corrections", "errors"],
+    "additionalProperties": false
+}"#;
+
+/// What a large session comes down to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// Instructions and preferences the user gave.
+    #[serde(default)]
+    pub instructions: Vec<String>,
+    /// Times the user pushed back on the assistant.
+    #[serde(default)]
+    pub corrections: Vec<String>,
+    /// Errors the session ran into.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+impl SessionSummary {
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty() && self.corrections.is_empty()
+    }
+}
+
+/// What [`condense`] did, for the run summary and the budget.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SummaryCost {
+    /// Sessions replaced by a summary (cached ones included).
+    pub summarized: usize,
+    /// Of those, served from the cache.
+    pub cached: usize,
+    /// Large sessions left to truncation (failed call, no budget left).
+    pub fallbacks: usize,
+    /// Summary calls made, failed ones included.
+    pub calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedSummary {
+    /// RFC3339 time the summary was made (eviction order).
+    at: String,
+    summary: SessionSummary,
+}
+
+fn cache_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("session_summaries.json")
+}
+
+fn load_cache(store_root: &Path) -> BTreeMap<String, CachedSummary> {
+    std::fs::read_to_string(cache_path(store_root))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(
+    store_root: &Path,
+    cache: &mut BTreeMap<String, CachedSummary>,
+) -> Result<(), CoreError> {
+    while cache.len() > MAX_CACHED {
+        let Some(oldest) = cache.iter().min_by(|a, b| a.1.at.cmp(&b.1.at)).map(|(k, _)| k.clone())
+        else {
+            break;
+        };
+        cache.remove(&oldest);
+    }
+    let io = |e: std::io::Error| CoreError::Io(e.to_string());
+    let path = cache_path(store_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io)?;
+    }
+    let json = serde_json::to_string_pretty(cache).map_err(|e| CoreError::Parse(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(io)?;
+    std::fs::rename(&tmp, &path).map_err(io)
+}
+
+/// `session_id:digest`, the digest over the transcript file (over the user
+/// messages when the file can't be read), so a transcript that grew after
+/// it was summarized gets a fresh summary.
+fn cache_key(session: &Session) -> String {
+    let content = std::fs::read(&session.session_path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_else(|_| {
+            session
+                .user_messages
+                .iter()
+                .map(|m| m.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+    format!(
+        "{}:{}",
+        session.session_id,
+        crate::projection::integrity::checksum(&content)
+    )
+}
+
+fn user_bytes(session: &Session) -> usize {
+    session.user_messages.iter().map(|m| m.text.len()).sum()
+}
+
+/// Whether the analysis prompt would cut `session` short: more messages
+/// than it keeps, or more text than [`LARGE_SESSION_BYTES`].
+pub fn is_large(session: &Session) -> bool {
+    session.user_messages.len() > super::prompts::MAX_USER_MSGS_PER_SESSION
+        || user_bytes(session) > LARGE_SESSION_BYTES
+}
+
+/// The user messages as numbered lines, split into parts of at most
+/// [`PART_BYTES`] (a single longer message is cut to fit).
+fn parts(session: &Session) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for (i, m) in session.user_messages.iter().enumerate() {
+        let tag = if m.is_correction { " (correction)" } else { "" };
+        let text = crate::util::truncate_str(m.text.trim(), PART_BYTES - 64);
+        let line = format!("[{}]{tag} {text}\n", i + 1);
+        if !current.is_empty() && current.len() + line.len() > PART_BYTES {
+            parts.push(std::mem::take(&mut current));
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// What a summary call asks for (`{builtin}` in a `session_summary.md`
+/// override).
+const SUMMARY_INSTRUCTIONS: &str = "\
+- instructions: every instruction, convention or preference the user stated, one short imperative sentence each, in the user's terms (keep tool names, commands, paths)
+- corrections: each time the user pushed back on the assistant, what was wrong and what they wanted instead
+- errors: recurring or notable errors, one line each
+Leave out one-off task details (\"fix this bug\", \"rename that file\"). Empty lists are fine.";
+
+/// Prompt for part `index` (0-based) of `total`. The session's errors ride
+/// along with the first part. `instructions` is the `session_summary.md`
+/// override, if any.
+pub(crate) fn build_summary_prompt(
+    session: &Session,
+    part: &str,
+    index: usize,
+    total: usize,
+    instructions: Option<&str>,
+) -> String {
+    let mut prompt = String::new();
+    prompt.push_str("You are condensing a long coding session transcript so a later step can learn the user's rules and preferences from it.\n\n");
+    prompt.push_str(&format!(
+        "Session {} in {}, part {} of {total}: the user's messages, numbered, with corrections of the assistant marked.\n\n",
+        session.session_id,
+        session.project,
+        index + 1
+    ));
+    prompt.push_str(part);
+    if index == 0 && !session.errors.is_empty() {
+        prompt.push_str("\nErrors seen in the session:\n");
+        for e in session.errors.iter().take(MAX_ERRORS) {
+            prompt.push_str(&format!("- {}\n", crate::util::truncate_str(e, MAX_ERROR_LEN)));
+        }
+    }
+    prompt.push_str("\n## Instructions\n\n");
+    match instructions {
+        Some(text) => prompt.push_str(&super::overrides::render(
+            text,
+            &[
+                ("builtin", SUMMARY_INSTRUCTIONS),
+                ("session_id", &session.session_id),
+                ("project", &session.project),
+                ("part", &(index + 1).to_string()),
+                ("total", &total.to_string()),
+            ],
+        )),
+        None => prompt.push_str(SUMMARY_INSTRUCTIONS),
+    }
+    prompt.push('\n');
+    prompt
+}
+
+/// Parse a summary response.
+pub fn parse_summary(json: &str) -> Result<SessionSummary, CoreError> {
+    serde_json::from_str(json)
+        .map_err(|e| CoreError::Parse(format!("failed to parse session summary: {e}")))
+}
+
+/// Merge part summaries: concatenated in order, blank and repeated entries
+/// (ignoring case) dropped, each list capped at [`MAX_ITEMS`].
+pub fn reduce(parts: Vec<SessionSummary>) -> SessionSummary {
+    fn merge(lists: impl Iterator<Item = Vec<String>>) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for item in lists.flatten() {
+            let item = item.trim().to_string();
+            if !item.is_empty()
+                && out.len() < MAX_ITEMS
+                && !out.iter().any(|o| o.eq_ignore_ascii_case(&item))
+            {
+                out.push(item);
+            }
+        }
+        out
+    }
+    SessionSummary {
+        instructions: merge(parts.iter().map(|p| p.instructions.clone())),
+        corrections: merge(parts.iter().map(|p| p.corrections.clone())),
+        errors: merge(parts.into_iter().map(|p| p.errors)),
+    }
+}
+
+/// Replace `session`'s user messages and errors with `summary`: each
+/// instruction and correction becomes one message (corrections flagged as
+/// such), and a note in `summaries` says the session was condensed.
+pub fn apply(session: &mut Session, summary: &SessionSummary) {
+    let original = session.user_messages.len();
+    let message = |text: &String, is_correction: bool| ParsedUserMessage {
+        text: text.clone(),
+        timestamp: None,
+        is_correction,
+    };
+    session.user_messages = summary
+        .instructions
+        .iter()
+        .map(|t| message(t, false))
+        .chain(summary.corrections.iter().map(|t| message(t, true)))
+        .collect();
+    session.errors = summary.errors.clone();
+    session.summaries.push(format!(
+        "Condensed by retro from {original} user messages: user_messages are the instructions and corrections they contained."
+    ));
+}
+
+/// Summarize `session` part by part. `None` when a call or parse fails, the
+/// parts would need more than `max_calls`, or nothing came back.
+fn summarize_one(
+    backend: &dyn AnalysisBackend,
+    model: &str,
+    session: &Session,
+    max_calls: u32,
+    instructions: Option<&str>,
+    cost: &mut SummaryCost,
+) -> Option<SessionSummary> {
+    let parts = parts(session);
+    if parts.len() as u64 > u64::from(max_calls) {
+        return None;
+    }
+    let mut summaries = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let prompt = build_summary_prompt(session, part, i, parts.len(), instructions);
+        cost.calls += 1;
+        let response = backend.execute_with_model(&prompt, Some(SESSION_SUMMARY_SCHEMA), model);
+        let parsed = response.and_then(|r| {
+            cost.input_tokens += r.input_tokens;
+            cost.output_tokens += r.output_tokens;
+            parse_summary(&r.text)
+        });
+        match parsed {
+            Ok(s) => summaries.push(s),
+            Err(e) => {
+                log::warn!(
+                    target: logging::ANALYSIS,
+                    "summary of {} failed, truncating instead: {e}",
+                    session.session_id
+                );
+                return None;
+            }
+        }
+    }
+    Some(reduce(summaries)).filter(|s| !s.is_empty())
+}
+
+/// Condense the large sessions in `sessions` in place, from the cache or
+/// with at most `max_calls` calls on `model`, adding what it did to `cost`.
+/// Sessions that can't be summarized are left as they are. `instructions`
+/// overrides what the calls ask for (`prompts/session_summary.md`).
+///
+/// An error (the cache can't be saved) leaves every session as it was, but
+/// `cost` still counts the calls already made.
+pub fn condense(
+    store_root: &Path,
+    backend: &dyn AnalysisBackend,
+    model: &str,
+    sessions: &mut [Session],
+    max_calls: u32,
+    instructions: Option<&str>,
+    cost: &mut SummaryCost,
+) -> Result<(), CoreError> {
+    if !sessions.iter().any(is_large) {
+        return Ok(());
+    }
+    let mut cache = load_cache(store_root);
+    let mut changed = false;
+    let calls_before = cost.calls;
+    let mut condensed: Vec<(usize, SessionSummary)> = Vec::new();
+    for (i, session) in sessions.iter().enumerate().filter(|(_, s)| is_large(s)) {
+        let key = cache_key(session);
+        if let Some(hit) = cache.get(&key) {
+            condensed.push((i, hit.summary.clone()));
+            cost.cached += 1;
+            continue;
+        }
+        let remaining = max_calls.saturating_sub(cost.calls - calls_before);
+        match summarize_one(backend, model, session, remaining, instructions, cost) {
+            Some(summary) => {
+                let at = Utc::now().to_rfc3339();
+                cache.insert(key, CachedSummary { at, summary: summary.clone() });
+                changed = true;
+                condensed.push((i, summary));
+            }
+            None => cost.fallbacks += 1,
+        }
+    }
+    if changed {
+        save_cache(store_root, &mut cache)?;
+    }
+    cost.summarized += condensed.len();
+    for (i, summary) in &condensed {
+        apply(&mut sessions[*i], summary);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::backend::MockBackend;
+    use crate::models::SessionMetadata;
+    use tempfile::TempDir;
+
+    fn session(id: &str, messages: usize, len: usize) -> Session {
+        Session {
+            session_id: id.to_string(),
+            project: "/work/app".to_string(),
+            session_path: format!("/nonexistent/{id}.jsonl"),
+            user_messages: (0..messages)
+                .map(|i| ParsedUserMessage {
+                    text: format!("{i} {}", "x".repeat(len)),
+                    timestamp: None,
+                    is_correction: i == 1,
+                })
+                .collect(),
+            assistant_messages: vec![],
+            summaries: vec![],
+            tools_used: vec![],
+            errors: vec!["E0382 use of moved value".to_string()],
+            metadata: SessionMetadata {
+                cwd: None,
+                version: None,
+                git_branch: None,
+                model: None,
+                git_branches: vec![],
+            },
+            unknown_entry_types: Default::default(),
+        }
+    }
+
+    const REPLY: &str = r#"{"instructions":["Use uv, not pip."],"corrections":["Don't mock the db."],"errors":["E0382"]}"#;
+
+    #[test]
+    fn schema_is_valid_json_and_large_means_the_prompt_would_truncate() {
+        let _: serde_json::Value = serde_json::from_str(SESSION_SUMMARY_SCHEMA).unwrap();
+        assert!(!is_large(&session("s", 10, 100)));
+        assert!(is_large(&session("s", 301, 10)), "more messages than the prompt keeps");
+        assert!(is_large(&session("s", 2, LARGE_SESSION_BYTES)), "more text");
+    }
+
+    #[test]
+    fn parts_split_by_size_and_reduce_dedups() {
+        let big = session("s", 3, 40_000);
+        let parts = parts(&big);
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].starts_with("[1] 0 ") && parts[0].contains("[2] (correction) 1 "));
+        let prompt = build_summary_prompt(&big, &parts[0], 0, 2, None);
+        assert!(prompt.contains("part 1 of 2") && prompt.contains("- E0382 use of moved value"));
+        assert!(!build_summary_prompt(&big, &parts[1], 1, 2, None).contains("Errors seen"));
+        let wrap = Some("Part {part}/{total}: {builtin}");
+        let custom = build_summary_prompt(&big, &parts[1], 1, 2, wrap);
+        assert!(custom.ends_with(&format!("\n\nPart 2/2: {SUMMARY_INSTRUCTIONS}\n")));
+
+        let a = parse_summary(REPLY).unwrap();
+        let b = SessionSummary {
+            instructions: ["use UV, not pip.", " ", "Run clippy."].map(String::from).to_vec(),
+            ..Default::default()
+        };
+        let merged = reduce(vec![a, b]);
+        assert_eq!(merged.instructions, vec!["Use uv, not pip.", "Run clippy."]);
+        assert_eq!(merged.corrections, vec!["Don't mock the db."]);
+        assert!(parse_summary("not json").is_err());
+    }
+
+    #[test]
+    fn summaries_replace_messages_and_are_cached() {
+        let store = TempDir::new().unwrap();
+        let backend = MockBackend::with_responses(vec![REPLY.to_string()]);
+        let mut sessions = vec![session("big", 400, 10), session("small", 3, 10)];
+        let mut cost = SummaryCost::default();
+        condense(store.path(), &backend, "haiku", &mut sessions, 5, None, &mut cost).unwrap();
+        assert_eq!((cost.summarized, cost.cached, cost.calls), (1, 0, 1));
+        assert_eq!((cost.input_tokens, cost.output_tokens), (100, 50));
+        assert_eq!(*backend.models_seen.lock().unwrap(), vec!["haiku"]);
+        let big = &sessions[0];
+        assert_eq!(big.user_messages.len(), 2);
+        assert!(big.user_messages[1].is_correction);
+        assert_eq!(big.errors, vec!["E0382"]);
+        assert!(big.summaries[0].contains("from 400 user messages"));
+        assert_eq!(sessions[1].user_messages.len(), 3, "small sessions untouched");
+
+        // Same transcript again: served from the cache, no call.
+        let empty = MockBackend::with_responses(vec![]);
+        let mut again = vec![session("big", 400, 10)];
+        let mut cost = SummaryCost::default();
+        condense(store.path(), &empty, "haiku", &mut again, 5, None, &mut cost).unwrap();
+        assert_eq!((cost.summarized, cost.cached, cost.calls), (1, 1, 0));
+        let texts = |s: &Session| s.user_messages.iter().map(|m| m.text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(&again[0]), texts(&sessions[0]));
+    }
+
+    #[test]
+    fn failed_or_unaffordable_summaries_fall_back_to_truncation() {
+        let store = TempDir::new().unwrap();
+        let failing = MockBackend::with_responses(vec!["garbage".to_string()]);
+        let mut sessions = vec![session("big", 400, 10)];
+        let mut cost = SummaryCost::default();
+        condense(store.path(), &failing, "haiku", &mut sessions, 5, None, &mut cost).unwrap();
+        assert_eq!((cost.summarized, cost.fallbacks, cost.calls), (0, 1, 1));
+        assert_eq!(sessions[0].user_messages.len(), 400, "left for truncation");
+        assert!(!cache_path(store.path()).exists(), "failures aren't cached");
+
+        // Summarized, but the cache can't be saved: nothing is condensed,
+        // the call still counts.
+        std::fs::create_dir_all(cache_path(store.path())).unwrap();
+        let backend = MockBackend::with_responses(vec![REPLY.to_string()]);
+        let mut cost = SummaryCost::default();
+        assert!(condense(store.path(), &backend, "haiku", &mut sessions, 5, None, &mut cost).is_err());
+        assert_eq!((cost.summarized, cost.calls), (0, 1));
+        assert_eq!(sessions[0].user_messages.len(), 400, "left for truncation");
+
+        let unused = MockBackend::with_responses(vec![REPLY.to_string()]);
+        let mut cost = SummaryCost::default();
+        condense(store.path(), &unused, "haiku", &mut sessions, 0, None, &mut cost).unwrap();
+        assert_eq!((cost.fallbacks, cost.calls), (1, 0), "no budget: no call");
+    }
+}
@@ -2,15 +2,19 @@
 //! response parsing) and apply the resulting GraphOperations to the markdown
 //! store instead of SQLite.
 
+use std::path::Path;
+
 use chrono::Utc;
 
 use crate::analysis::backend::AnalysisBackend;
-use crate::analysis::{GRAPH_ANALYSIS_RESPONSE_SCHEMA, parse_graph_response_full, prompts};
+use crate::analysis::{
+    GRAPH_ANALYSIS_RESPONSE_SCHEMA, language, parse_graph_response_full, prompts,
+};
 use crate::errors::CoreError;
 use crate::models::{
     EdgeType, GraphOperation, KnowledgeNode, NodeScope, NodeType as V2NodeType, Session,
 };
-use crate::store::{Node, NodeType, Scope, Store, is_valid_slug};
+use crate::store::{Node, NodeType, Scope, Store, deferred, is_valid_slug, tools};
 use crate::util::truncate_str;
 
 /// Ceiling on a created node's body. Nothing legitimate is this large — the
@@ -18,6 +22,14 @@ use crate::util::truncate_str;
 /// model garbage, not knowledge.
 const MAX_CREATE_CONTENT_CHARS: usize = 10_000;
 
+/// Confidence of a node salvaged from an orphaned `update_node` (unknown id,
+/// content present). Well below the projection threshold: the evidence is
+/// kept, but it has to be reinforced before it reaches CLAUDE.md.
+const ORPHAN_CONFIDENCE: f64 = 0.3;
+
+/// Shortest id fragment [`recover_id`] will prefix-match.
+const MIN_RECOVER_PREFIX: usize = 4;
+
 /// Result of one v3 analysis batch.
 #[derive(Debug, Default)]
 pub struct V3AnalyzeResult {
@@ -31,10 +43,42 @@ pub struct V3AnalyzeResult {
     /// Short reasons for skipped operations (for health/summary display).
     pub skipped: Vec<String>,
     pub reasoning: String,
+    /// Checksum of the raw response, so a learn commit can be matched to
+    /// the call that produced it.
+    pub response_digest: String,
     pub input_tokens: u64,
     pub output_tokens: u64,
     /// Bodies of nodes created/updated — for briefing notifications.
     pub learned: Vec<String>,
+    /// Existing nodes shown to the model vs. active nodes in scope.
+    pub context_included: usize,
+    pub context_total: usize,
+    /// `update_node` ops naming an unknown id that could not be recovered.
+    /// Kept as a low-confidence new node when the op carried content
+    /// (counted in `nodes_created` too), otherwise skipped.
+    pub orphaned_updates: usize,
+    /// `update_node` ops whose id matched an existing node only after
+    /// case/separator normalization or a unique prefix match.
+    pub update_ids_recovered: usize,
+    /// Creates over `max_new_nodes`, saved to `store::deferred` instead.
+    pub candidates_deferred: usize,
+    /// Their bodies, most confident first.
+    pub deferred: Vec<String>,
+    /// Bodies of global, projectable creates (written or deferred) — the
+    /// runner hands them to later batches as `proposed_earlier`.
+    pub proposed: Vec<String>,
+    /// Creates that re-observed a deferred candidate (and absorbed it).
+    pub candidates_promoted: usize,
+    /// Creates/updates whose content is obviously not in `[analysis]
+    /// output_language` (`analysis::language`): skipped, so the reason
+    /// reaches the briefing instead of the text reaching the store.
+    pub language_mismatches: usize,
+    /// Large sessions condensed before analysis, and the tokens those
+    /// summary calls cost (on `[ai] summary_model`, not in `input_tokens` /
+    /// `output_tokens`). Filled in by the runner.
+    pub sessions_summarized: usize,
+    pub summary_input_tokens: u64,
+    pub summary_output_tokens: u64,
 }
 
 impl V3AnalyzeResult {
@@ -44,10 +88,23 @@ impl V3AnalyzeResult {
     }
 }
 
+/// What a batch's prompt carries besides its sessions and the store.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchContext<'a> {
+    /// Global rules earlier batches of this run proposed (their
+    /// [`V3AnalyzeResult::proposed`]), shown so the model doesn't propose
+    /// them again.
+    pub proposed_earlier: &'a [String],
+    /// `[analysis] output_language`; empty for none.
+    pub output_language: &'a str,
+    /// `prompts/analysis_preamble.md`, unrendered.
+    pub preamble: Option<&'a str>,
+}
+
 /// Shim: present a v3 store node to the v2 prompt builder. Only id, content,
-/// confidence, type, and scope influence the prompt (content truncated to
-/// 200 chars there).
-fn shim(node: &Node) -> KnowledgeNode {
+/// confidence, type, scope, and (patterns) tool usage influence the prompt
+/// (content truncated to 200 chars there).
+fn shim(node: &Node, tool_log: &tools::ToolLog) -> KnowledgeNode {
     KnowledgeNode {
         id: node.id.clone(),
         node_type: match node.node_type {
@@ -62,9 +119,24 @@ fn shim(node: &Node) -> KnowledgeNode {
         },
         content: node.body.clone(),
         confidence: node.confidence,
+        tools: match (node.node_type, tool_log.get(&tools::key(&node.scope, &node.id))) {
+            (NodeType::Pattern, Some(entry)) => tools::compact(entry, tools::PROMPT_TOOLS),
+            _ => String::new(),
+        },
     }
 }
 
+/// Skip reason for content obviously not in `output_language`, if any.
+fn language_refusal(op: &str, body: &str, output_language: &str) -> Option<String> {
+    let got = language::mismatch(body, output_language)?;
+    Some(format!(
+        "{op}: content is in {}, not {} (output_language): {:?}",
+        language::display_name(got),
+        language::display_name(output_language),
+        truncate_str(body.trim(), 60)
+    ))
+}
+
 fn v3_node_type(t: &V2NodeType) -> NodeType {
     match t {
         V2NodeType::Rule | V2NodeType::Directive => NodeType::Rule,
@@ -82,8 +154,39 @@ fn union_sources(existing: &mut Vec<String>, extra: &[String]) {
     }
 }
 
+/// Words too common to signal that a node is about the same thing as a batch.
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "you", "your", "are", "not", "use", "when",
+    "from", "into", "always", "never", "before", "after", "should", "must", "can", "have", "all",
+];
+
+/// Lowercased alphanumeric words of 3+ chars, minus stopwords.
+fn tokens(text: &str) -> std::collections::HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Relevance of an existing node to a batch: the share of the node's words
+/// that appear in the batch's user messages (0.0–1.0). Pure, so ranking is
+/// deterministic and testable.
+pub fn relevance(node_body: &str, batch: &std::collections::HashSet<String>) -> f64 {
+    let words = tokens(node_body);
+    if words.is_empty() {
+        return 0.0;
+    }
+    words.iter().filter(|w| batch.contains(*w)).count() as f64 / words.len() as f64
+}
+
 /// Analyze one batch of parsed sessions against the store and apply the
 /// resulting operations. `project_slug` scopes project-level operations.
+/// At most `max_context` existing nodes go into the prompt, most relevant
+/// to the batch first. At most `max_new_nodes` creates are written (highest
+/// confidence first); the rest are deferred (`store::deferred`).
+/// `batch` adds what earlier batches proposed and the output language;
+/// content obviously in another language is skipped, not written.
 /// Caller is responsible for: session filtering by project, scrubbing,
 /// budget accounting (one backend call per invocation), and committing.
 pub fn analyze_sessions(
@@ -91,6 +194,9 @@ pub fn analyze_sessions(
     backend: &dyn AnalysisBackend,
     sessions: &[Session],
     project_slug: Option<&str>,
+    max_context: usize,
+    max_new_nodes: usize,
+    batch: &BatchContext,
 ) -> Result<V3AnalyzeResult, CoreError> {
     let mut result = V3AnalyzeResult::default();
 
@@ -109,8 +215,9 @@ pub fn analyze_sessions(
         .collect();
 
     // Existing-node context: active nodes for global + this project's scope.
+    let prompt_span = crate::profile::span("prompt");
     let loaded = store.load_all()?;
-    let mut active: Vec<&Node> = loaded
+    let active: Vec<&Node> = loaded
         .nodes
         .iter()
         .map(|(_, n)| n)
@@ -121,30 +228,82 @@ pub fn analyze_sessions(
             (Scope::Project(_), None) => false,
         })
         .collect();
-    // Highest-value nodes first: the prompt builder caps at 50 nodes, so sort
-    // by confidence desc, recency desc, id asc (stable tiebreak) to keep the
-    // most important nodes visible and prevent duplicate re-creation.
-    active.sort_by(|a, b| {
-        b.confidence
-            .partial_cmp(&a.confidence)
+    let known_ids: Vec<(Scope, String)> = active
+        .iter()
+        .map(|n| (n.scope.clone(), n.id.clone()))
+        .collect();
+    // Most relevant nodes first — the ones this batch could duplicate or
+    // reinforce — then confidence desc, recency desc, id asc (stable
+    // tiebreak), so the capped list keeps what matters to this batch.
+    let batch_words: std::collections::HashSet<String> = signal
+        .iter()
+        .flat_map(|s| s.user_messages.iter())
+        .flat_map(|m| tokens(&m.text))
+        .collect();
+    let mut ranked: Vec<(f64, &Node)> = active
+        .into_iter()
+        .map(|n| (relevance(&n.body, &batch_words), n))
+        .collect();
+    ranked.sort_by(|(ra, a), (rb, b)| {
+        rb.partial_cmp(ra)
             .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| {
+                b.confidence
+                    .partial_cmp(&a.confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
             .then_with(|| b.updated.cmp(&a.updated))
             .then_with(|| a.id.cmp(&b.id))
     });
-    let context: Vec<KnowledgeNode> = active.into_iter().map(shim).collect();
+    result.context_total = ranked.len();
+    let mut tool_log = tools::load(store.root());
+    let context: Vec<KnowledgeNode> = ranked
+        .into_iter()
+        .take(max_context)
+        .map(|(_, n)| shim(n, &tool_log))
+        .collect();
+    result.context_included = context.len();
 
+    // Headings a project rule's `section` may name: the project's own files,
+    // when this machine knows where it lives.
+    let headings = match project_slug {
+        Some(slug) => crate::store::projects::PathMap::load(store.root())?
+            .paths
+            .get(slug)
+            .map(|root| crate::projection::local_md::project_headings(Path::new(root)))
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
     let compact: Vec<_> = signal
         .iter()
         .map(|s| prompts::to_compact_session(s))
         .collect();
-    let prompt = prompts::build_graph_analysis_prompt(&compact, &context, project_slug);
+    let prompt = prompts::build_graph_analysis_prompt(
+        &compact,
+        &context,
+        result.context_total,
+        project_slug,
+        &headings,
+        batch,
+    );
+    drop(prompt_span);
+    let backend_span = crate::profile::span("backend");
     let response = backend.execute(&prompt, Some(GRAPH_ANALYSIS_RESPONSE_SCHEMA))?;
+    drop(backend_span);
+    let _apply_span = crate::profile::span("apply-ops");
     result.input_tokens = response.input_tokens;
     result.output_tokens = response.output_tokens;
 
     let (reasoning, operations) = parse_graph_response_full(&response.text, project_slug)?;
     result.reasoning = reasoning;
+    result.response_digest = crate::projection::integrity::checksum(&response.text);
     let today = Utc::now().date_naive();
+    // Creates are collected and written after the other operations so the
+    // per-run cap keeps the most confident ones. The flag marks creates
+    // worth a "Learned:" notification (not salvaged orphan updates).
+    let mut creates: Vec<(Node, bool)> = Vec::new();
+    // Patterns this batch touched get its sessions' tools.
+    let mut tools_changed = false;
 
     for op in operations {
         match op {
@@ -154,6 +313,7 @@ pub fn analyze_sessions(
                 project_id,
                 content,
                 confidence,
+                section,
             } => {
                 // LLM output is untrusted: reject junk content before any write.
                 let body = content.trim().to_string();
@@ -168,6 +328,13 @@ pub fn analyze_sessions(
                     ));
                     continue;
                 }
+                if let Some(reason) =
+                    language_refusal("create_node", &body, batch.output_language)
+                {
+                    result.language_mismatches += 1;
+                    result.skip(reason);
+                    continue;
+                }
                 let v3_scope = match scope {
                     NodeScope::Global => Scope::Global,
                     NodeScope::Project => {
@@ -190,16 +357,14 @@ pub fn analyze_sessions(
                         }
                     }
                 };
-                let id = store.unique_slug(
-                    &body
-                        .split_whitespace()
-                        .take(8)
-                        .collect::<Vec<_>>()
-                        .join(" "),
-                    &v3_scope,
-                );
+                // Only project rules sit next to the project's headings;
+                // projection re-checks the section against the file.
+                let section = match v3_scope {
+                    Scope::Project(_) => section,
+                    Scope::Global => None,
+                };
                 let node = Node {
-                    id,
+                    id: String::new(),
                     scope: v3_scope,
                     node_type: v3_node_type(&node_type),
                     confidence: confidence.clamp(0.0, 1.0),
@@ -207,31 +372,89 @@ pub fn analyze_sessions(
                     created: today,
                     updated: today,
                     invalidated_by: None,
+                    dismissal_reason: None,
+                    section,
                     body,
                 };
-                store.write_node(&node)?;
-                result.learned.push(node.body.clone());
-                result.nodes_created += 1;
+                creates.push((node, true));
             }
             GraphOperation::UpdateNode {
                 id,
                 confidence,
                 content,
             } => {
-                if !is_valid_slug(&id) {
-                    result.skip(format!(
-                        "update_node: invalid id {:?}",
-                        truncate_str(&id, 60)
-                    ));
-                    continue;
-                }
-                let Some((scope, mut node)) = find_node(store, &id, project_slug)? else {
-                    result.skip(format!(
-                        "update_node: unknown id {:?}",
-                        truncate_str(&id, 60)
-                    ));
+                let found = if is_valid_slug(&id) {
+                    find_node(store, &id, project_slug)?
+                } else {
+                    None
+                };
+                let found = match found {
+                    Some(hit) => Some(hit),
+                    None => match recover_id(&id, &known_ids) {
+                        Some((scope, known)) => {
+                            result.update_ids_recovered += 1;
+                            store.get(&scope, &known)?.map(|n| (scope, n))
+                        }
+                        None => None,
+                    },
+                };
+                let Some((scope, mut node)) = found else {
+                    if !is_valid_slug(&id) && content.is_none() {
+                        result.skip(format!(
+                            "update_node: invalid id {:?}",
+                            truncate_str(&id, 60)
+                        ));
+                        continue;
+                    }
+                    result.orphaned_updates += 1;
+                    let body = content.as_deref().map(str::trim).unwrap_or_default();
+                    if body.is_empty() || body.chars().count() > MAX_CREATE_CONTENT_CHARS {
+                        result.skip(format!(
+                            "update_node: unknown id {:?}",
+                            truncate_str(&id, 60)
+                        ));
+                        continue;
+                    }
+                    if let Some(reason) =
+                        language_refusal("update_node", body, batch.output_language)
+                    {
+                        result.language_mismatches += 1;
+                        result.skip(reason);
+                        continue;
+                    }
+                    // Orphaned update with content: the evidence is real even
+                    // if the id is hallucinated — keep it as a new node that
+                    // must be reinforced before it projects.
+                    let scope = match project_slug.filter(|s| is_valid_slug(s)) {
+                        Some(slug) => Scope::Project(slug.to_string()),
+                        None => Scope::Global,
+                    };
+                    let node = Node {
+                        id: String::new(),
+                        scope,
+                        node_type: NodeType::Rule,
+                        confidence: confidence
+                            .unwrap_or(ORPHAN_CONFIDENCE)
+                            .clamp(0.0, ORPHAN_CONFIDENCE),
+                        sources: session_sources.clone(),
+                        created: today,
+                        updated: today,
+                        invalidated_by: None,
+                        dismissal_reason: None,
+                        section: None,
+                        body: body.to_string(),
+                    };
+                    creates.push((node, false));
                     continue;
                 };
+                let refusal = content
+                    .as_deref()
+                    .and_then(|c| language_refusal("update_node", c, batch.output_language));
+                if let Some(reason) = refusal {
+                    result.language_mismatches += 1;
+                    result.skip(format!("{reason} (`{}` unchanged)", node.id));
+                    continue;
+                }
                 if let Some(c) = confidence {
                     node.confidence = c.clamp(0.0, 1.0);
                 }
@@ -243,6 +466,10 @@ pub fn analyze_sessions(
                 node.updated = today;
                 node.scope = scope;
                 store.write_node(&node)?;
+                if node.node_type == NodeType::Pattern {
+                    let entry = tool_log.entry(tools::key(&node.scope, &node.id)).or_default();
+                    tools_changed |= tools::attach(entry, &signal);
+                }
                 if body_changed {
                     result.learned.push(node.body.clone());
                 }
@@ -282,6 +509,12 @@ pub fn analyze_sessions(
                 keep_node.scope = keep_scope;
                 store.write_node(&keep_node)?;
                 store.invalidate(&remove_scope, &remove_node.id, &keep_node.id)?;
+                let keep_key = tools::key(&keep_node.scope, &keep_node.id);
+                tools_changed |=
+                    tools::absorb(&mut tool_log, &keep_key, &tools::key(&remove_scope, &remove_id));
+                if keep_node.node_type == NodeType::Pattern {
+                    tools_changed |= tools::attach(tool_log.entry(keep_key).or_default(), &signal);
+                }
                 result.nodes_merged += 1;
             }
             GraphOperation::CreateEdge {
@@ -331,9 +564,128 @@ pub fn analyze_sessions(
             }
         }
     }
+    for key in write_creates(store, creates, max_new_nodes, &mut result)? {
+        // A fresh slug can still have an entry: an orphan left by a node
+        // deleted by hand. Its sessions aren't this pattern's.
+        let entry = tool_log.entry(key).or_default();
+        tools_changed |= !entry.is_empty();
+        entry.clear();
+        tools_changed |= tools::attach(entry, &signal);
+    }
+    if tools_changed {
+        tools::save(store.root(), &tool_log)?;
+    }
     Ok(result)
 }
 
+/// Write the batch's creates, highest confidence first (ties keep the
+/// model's order), up to `max_new_nodes`; defer the rest. A create that re-observes a deferred
+/// candidate absorbs its sources and confidence. Returns the
+/// [`tools::key`]s of the patterns written.
+fn write_creates(
+    store: &Store,
+    mut creates: Vec<(Node, bool)>,
+    max_new_nodes: usize,
+    result: &mut V3AnalyzeResult,
+) -> Result<Vec<String>, CoreError> {
+    let mut patterns = Vec::new();
+    if creates.is_empty() {
+        return Ok(patterns);
+    }
+    result.proposed.extend(
+        creates
+            .iter()
+            .filter(|(n, _)| n.scope == Scope::Global && n.node_type != NodeType::Memory)
+            .map(|(n, _)| n.body.clone()),
+    );
+    creates.sort_by(|(a, _), (b, _)| {
+        b.confidence
+            .partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let over = creates.split_off(max_new_nodes.min(creates.len()));
+    let mut pending = deferred::load(store.root());
+    let before = pending.clone();
+    for (mut node, announce) in creates {
+        if let Some(earlier) = deferred::take_match(&mut pending, &node.scope, &node.body) {
+            union_sources(&mut node.sources, &earlier.sources);
+            node.confidence = node.confidence.max(earlier.confidence);
+            result.candidates_promoted += 1;
+        }
+        node.id = store.unique_slug(
+            &node.body.split_whitespace().take(8).collect::<Vec<_>>().join(" "),
+            &node.scope,
+        );
+        match store.write_node(&node) {
+            Ok(_) => {}
+            // Refused target (invalid name, path outside the store): the op
+            // fails, the rest of the batch still lands.
+            Err(CoreError::Parse(reason)) => {
+                result.skip(format!("create_node: {reason}"));
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+        if announce {
+            result.learned.push(node.body.clone());
+        }
+        if node.node_type == NodeType::Pattern {
+            patterns.push(tools::key(&node.scope, &node.id));
+        }
+        result.nodes_created += 1;
+    }
+    let deferred_at = Utc::now().to_rfc3339();
+    for (node, _) in over {
+        result.deferred.push(node.body.clone());
+        pending.push(deferred::DeferredCandidate {
+            scope: node.scope.to_string(),
+            node_type: node.node_type.as_str().to_string(),
+            body: node.body,
+            confidence: node.confidence,
+            sources: node.sources,
+            deferred_at: deferred_at.clone(),
+        });
+        result.candidates_deferred += 1;
+    }
+    if pending != before {
+        deferred::save(store.root(), &pending)?;
+    }
+    Ok(patterns)
+}
+
+/// Recover an id the model mangled: match `id` against the in-context ids
+/// after lowercasing and mapping `_`/spaces to `-`, then by unique prefix in
+/// either direction (a truncated id, or one with a suffix appended). None
+/// unless exactly one known id fits.
+fn recover_id(id: &str, known: &[(Scope, String)]) -> Option<(Scope, String)> {
+    let wanted: String = id
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c == '_' || c.is_whitespace() { '-' } else { c })
+        .collect();
+    if wanted.len() < MIN_RECOVER_PREFIX {
+        return None;
+    }
+    let unique = |hits: Vec<&(Scope, String)>| match hits.as_slice() {
+        [one] => Some((*one).clone()),
+        _ => None,
+    };
+    let exact: Vec<_> = known.iter().filter(|(_, k)| *k == wanted).collect();
+    if !exact.is_empty() {
+        return unique(exact);
+    }
+    unique(
+        known
+            .iter()
+            .filter(|(_, k)| {
+                k.starts_with(&wanted)
+                    || (k.len() >= MIN_RECOVER_PREFIX && wanted.starts_with(k.as_str()))
+            })
+            .collect(),
+    )
+}
+
 /// Resolve an operation's node id: try the batch's project scope first, then global.
 fn find_node(
     store: &Store,
@@ -370,6 +722,7 @@ mod tests {
                 .map(|m| ParsedUserMessage {
                     text: m.to_string(),
                     timestamp: None,
+                    is_correction: false,
                 })
                 .collect(),
             assistant_messages: vec![],
@@ -381,7 +734,9 @@ mod tests {
                 version: None,
                 git_branch: None,
                 model: None,
+                git_branches: vec![],
             },
+            unknown_entry_types: Default::default(),
         }
     }
 
@@ -404,6 +759,9 @@ mod tests {
             &backend,
             &[session("s1", &["please smoke test first", "ok run it"])],
             Some("my-proj"),
+            50,
+            usize::MAX,
+            &BatchContext::default(),
         )
         .unwrap();
         assert_eq!(result.nodes_created, 1);
@@ -417,6 +775,33 @@ mod tests {
         assert!(node.body.contains("smoke tests"));
     }
 
+    #[test]
+    fn project_headings_reach_the_prompt_and_sections_are_kept_for_project_rules() {
+        let (_tmp, store) = store();
+        let proj = TempDir::new().unwrap();
+        std::fs::write(proj.path().join("CLAUDE.md"), "# App\n\n## Testing\n").unwrap();
+        let mut map = crate::store::projects::PathMap::default();
+        map.paths.insert("my-proj".to_string(), proj.path().display().to_string());
+        map.save(store.root()).unwrap();
+        let response = r#"{"reasoning":"","operations":[
+            {"action":"create_node","node_type":"rule","scope":"project","content":"Run smoke tests first.","confidence":0.8,"section":"Testing"},
+            {"action":"create_node","node_type":"rule","scope":"global","content":"Answer tersely.","confidence":0.8,"section":"Testing"}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let sessions = [session("s1", &["smoke test first", "ok"])];
+        analyze_sessions(&store, &backend, &sessions, Some("my-proj"), 50, usize::MAX, &BatchContext::default())
+            .unwrap();
+        assert!(backend.prompts_seen.lock().unwrap()[0].contains("- App\n- Testing\n"));
+        let loaded = store.load_all().unwrap();
+        let section = |scope: &Scope| {
+            let n = loaded.nodes.iter().map(|(_, n)| n).find(|n| &n.scope == scope).unwrap();
+            n.section.clone()
+        };
+        let project = Scope::Project("my-proj".to_string());
+        assert_eq!(section(&project).as_deref(), Some("Testing"));
+        assert_eq!(section(&Scope::Global), None, "global rules have no project headings");
+    }
+
     #[test]
     fn update_and_merge_operations_mutate_existing_nodes() {
         let (_tmp, store) = store();
@@ -430,6 +815,8 @@ mod tests {
             created: today,
             updated: today,
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: format!("rule body {id}"),
         };
         store.write_node(&mk("keeper", 0.5)).unwrap();
@@ -445,6 +832,9 @@ mod tests {
             &backend,
             &[session("s2", &["msg one", "msg two"])],
             None,
+            50,
+            usize::MAX,
+            &BatchContext::default(),
         )
         .unwrap();
         assert_eq!(result.nodes_updated, 1);
@@ -475,6 +865,8 @@ mod tests {
             created: today,
             updated: today,
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: format!("body {id}"),
         };
         store.write_node(&mk("new-way")).unwrap();
@@ -486,13 +878,100 @@ mod tests {
         ]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s3", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s3", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.nodes_invalidated, 1);
         assert_eq!(result.edges_ignored, 1);
         let old = store.get(&Scope::Global, "old-way").unwrap().unwrap();
         assert_eq!(old.invalidated_by.as_deref(), Some("new-way"));
     }
 
+    #[test]
+    fn relevance_is_the_share_of_node_words_seen_in_the_batch() {
+        let batch = tokens("please run cargo clippy on the workspace");
+        assert!((relevance("Run cargo clippy before committing.", &batch) - 0.75).abs() < 1e-9);
+        assert_eq!(relevance("Prefer uv over pip.", &batch), 0.0);
+        assert_eq!(relevance("", &batch), 0.0);
+        // stopwords and short words don't count either way
+        assert_eq!(tokens("Always use the CLI"), ["cli".to_string()].into_iter().collect());
+    }
+
+    #[test]
+    fn context_is_capped_to_the_most_relevant_nodes() {
+        let (_tmp, store) = store();
+        let today = chrono::Utc::now().date_naive();
+        for (id, conf, body) in [
+            ("uv-rule", 0.95, "Prefer uv over pip for Python."),
+            ("clippy-rule", 0.6, "Run cargo clippy before committing."),
+            ("tabs-rule", 0.9, "Indent YAML with two spaces."),
+        ] {
+            store
+                .write_node(&Node {
+                    id: id.to_string(),
+                    scope: Scope::Global,
+                    node_type: NodeType::Rule,
+                    confidence: conf,
+                    sources: vec![],
+                    created: today,
+                    updated: today,
+                    invalidated_by: None,
+                    dismissal_reason: None,
+                    section: None,
+                    body: body.to_string(),
+                })
+                .unwrap();
+        }
+        let backend =
+            MockBackend::with_responses(vec![r#"{"reasoning":"","operations":[]}"#.to_string()]);
+        let result = analyze_sessions(
+            &store,
+            &backend,
+            &[session("s", &["cargo clippy is failing", "fix the clippy lints"])],
+            None,
+            1,
+            usize::MAX,
+            &BatchContext::default(),
+        )
+        .unwrap();
+        assert_eq!((result.context_included, result.context_total), (1, 3));
+        let prompt = backend.prompts_seen.lock().unwrap()[0].clone();
+        assert!(prompt.contains("[clippy-rule]"), "most relevant kept despite low confidence");
+        assert!(!prompt.contains("[uv-rule]"));
+        assert!(prompt.contains("This list is partial"));
+    }
+
+    #[test]
+    fn pattern_tool_usage_is_recorded_and_shown_to_the_analyzer() {
+        let (_tmp, store) = store();
+        let mut s1 = session("s1", &["deploy it", "now tail the logs"]);
+        s1.tools_used = vec!["Bash".to_string(), "Read".to_string()];
+        let create = r#"{"reasoning":"workflow","operations":[
+            {"action":"create_node","node_type":"pattern","scope":"global","content":"Deploy, then tail the logs until healthy.","confidence":0.6}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![create.to_string()]);
+        analyze_sessions(&store, &backend, &[s1], None, 50, usize::MAX, &BatchContext::default()).unwrap();
+        let id = store.load_all().unwrap().nodes[0].1.id.clone();
+
+        let mut s2 = session("s2", &["deploy again", "tail logs"]);
+        s2.tools_used = vec!["Bash".to_string()];
+        let update = format!(
+            r#"{{"reasoning":"again","operations":[
+            {{"action":"update_node","node_id":"{id}","new_confidence":0.8}}
+        ]}}"#
+        );
+        let backend = MockBackend::with_responses(vec![update]);
+        analyze_sessions(&store, &backend, &[s2.clone()], None, 50, usize::MAX, &BatchContext::default()).unwrap();
+        let prompt = backend.prompts_seen.lock().unwrap()[0].clone();
+        assert!(prompt.contains("tools: Bash×1, Read×1 (of 1 sessions)"), "{prompt}");
+        let log = tools::load(store.root());
+        let entry = &log[&tools::key(&Scope::Global, &id)];
+        assert_eq!(tools::compact(entry, 5), "Bash×2, Read×1 (of 2 sessions)");
+
+        let backend = MockBackend::with_responses(vec![r#"{"reasoning":"","operations":[]}"#.into()]);
+        analyze_sessions(&store, &backend, &[s2], None, 50, usize::MAX, &BatchContext::default()).unwrap();
+        let prompt = backend.prompts_seen.lock().unwrap()[0].clone();
+        assert!(prompt.contains("tools: Bash×2, Read×1 (of 2 sessions)"), "{prompt}");
+    }
+
     #[test]
     fn low_signal_sessions_are_filtered_before_any_ai_call() {
         let (_tmp, store) = store();
@@ -502,6 +981,9 @@ mod tests {
             &backend,
             &[session("tiny", &["single message"])],
             None,
+            50,
+            usize::MAX,
+            &BatchContext::default(),
         )
         .unwrap();
         assert_eq!(result.sessions_analyzed, 0);
@@ -522,12 +1004,14 @@ mod tests {
                 created: today,
                 updated: today,
                 invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
                 body: "a very distinctive existing rule body".to_string(),
             })
             .unwrap();
         let response = r#"{"reasoning":"nothing new","operations":[]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
-        analyze_sessions(&store, &backend, &[session("s4", &["a", "b"])], None).unwrap();
+        analyze_sessions(&store, &backend, &[session("s4", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         let prompts = backend.prompts_seen.lock().unwrap();
         assert_eq!(prompts.len(), 1);
         assert!(
@@ -546,7 +1030,7 @@ mod tests {
         ]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s5", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s5", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.ops_skipped, 3, "skipped: {:?}", result.skipped);
         assert_eq!(result.nodes_created, 0);
         assert_eq!(result.nodes_updated, 0);
@@ -573,6 +1057,8 @@ mod tests {
                 created: today,
                 updated: today,
                 invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
                 body: "solo body".to_string(),
             })
             .unwrap();
@@ -581,7 +1067,7 @@ mod tests {
         ]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s6", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s6", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.ops_skipped, 1, "skipped: {:?}", result.skipped);
         assert_eq!(result.nodes_merged, 0);
         let solo = store.get(&Scope::Global, "solo").unwrap().unwrap();
@@ -600,7 +1086,7 @@ mod tests {
         );
         let backend = MockBackend::with_responses(vec![response]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s7", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s7", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.ops_skipped, 2, "skipped: {:?}", result.skipped);
         assert_eq!(result.nodes_created, 0);
         assert!(store.load_all().unwrap().nodes.is_empty());
@@ -620,6 +1106,8 @@ mod tests {
                 created: today,
                 updated: today,
                 invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
                 body: "victim body".to_string(),
             })
             .unwrap();
@@ -628,7 +1116,7 @@ mod tests {
         ]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s8", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s8", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.ops_skipped, 1, "skipped: {:?}", result.skipped);
         assert_eq!(result.nodes_invalidated, 0);
         let victim = store.get(&Scope::Global, "victim").unwrap().unwrap();
@@ -644,8 +1132,9 @@ mod tests {
         ]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s9", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s9", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.ops_skipped, 2, "skipped: {:?}", result.skipped);
+        assert_eq!(result.orphaned_updates, 1);
         assert_eq!(result.nodes_updated, 0);
         assert_eq!(result.nodes_merged, 0);
         assert!(
@@ -663,13 +1152,185 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mangled_update_ids_are_recovered_by_case_and_prefix() {
+        let (_tmp, store) = store();
+        for id in ["use-uv-for-python-installs", "run-clippy-before-push"] {
+            store
+                .write_node(&Node {
+                    id: id.to_string(),
+                    scope: Scope::Global,
+                    node_type: NodeType::Rule,
+                    confidence: 0.5,
+                    sources: vec![],
+                    created: Utc::now().date_naive(),
+                    updated: Utc::now().date_naive(),
+                    invalidated_by: None,
+                    dismissal_reason: None,
+                    section: None,
+                    body: id.replace('-', " "),
+                })
+                .unwrap();
+        }
+        let response = r#"{"reasoning":"r","operations":[
+            {"action":"update_node","node_id":"Use_UV_For_Python_Installs","new_confidence":0.8},
+            {"action":"update_node","node_id":"run-clippy","new_confidence":0.9},
+            {"action":"update_node","node_id":"use","new_confidence":0.9}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let result =
+            analyze_sessions(&store, &backend, &[session("s11", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
+        assert_eq!(result.nodes_updated, 2, "skipped: {:?}", result.skipped);
+        assert_eq!(result.update_ids_recovered, 2);
+        assert_eq!(result.orphaned_updates, 1, "a 3-char fragment is too short to match");
+        let uv = store.get(&Scope::Global, "use-uv-for-python-installs").unwrap().unwrap();
+        assert!((uv.confidence - 0.8).abs() < 1e-9);
+        assert_eq!(uv.sources, vec!["session:s11"]);
+        let clippy = store.get(&Scope::Global, "run-clippy-before-push").unwrap().unwrap();
+        assert!((clippy.confidence - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn orphaned_update_with_content_becomes_a_low_confidence_node() {
+        let (_tmp, store) = store();
+        let response = r#"{"reasoning":"r","operations":[
+            {"action":"update_node","node_id":"3f2a9c1e-hallucinated","new_confidence":0.95,"new_content":"Run migrations before the test suite."}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let result =
+            analyze_sessions(&store, &backend, &[session("s12", &["a", "b"])], Some("my-app"), 50, usize::MAX, &BatchContext::default())
+                .unwrap();
+        assert_eq!(result.orphaned_updates, 1);
+        assert_eq!(result.nodes_created, 1);
+        assert_eq!(result.ops_skipped, 0);
+        let nodes = store.load_all().unwrap().nodes;
+        let (_, node) = &nodes[0];
+        assert_eq!(node.scope, Scope::Project("my-app".to_string()));
+        assert_eq!(node.body, "Run migrations before the test suite.");
+        assert_eq!(node.sources, vec!["session:s12"]);
+        assert!(node.confidence <= ORPHAN_CONFIDENCE, "never projects unreinforced");
+    }
+
+    #[test]
+    fn content_in_another_language_than_output_language_is_flagged_not_stored() {
+        let (_tmp, store) = store();
+        let today = chrono::Utc::now().date_naive();
+        store
+            .write_node(&Node {
+                id: "push-tests".to_string(),
+                scope: Scope::Global,
+                node_type: NodeType::Rule,
+                confidence: 0.7,
+                sources: vec![],
+                created: today,
+                updated: today,
+                invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
+                body: "Run the tests before pushing.".to_string(),
+            })
+            .unwrap();
+        let german = session(
+            "de1",
+            &[
+                "Bitte führe immer die Tests vor dem Push aus",
+                "Nein, nicht mit pip — verwende uv",
+            ],
+        );
+        let response = r#"{"reasoning":"r","operations":[
+            {"action":"create_node","node_type":"rule","scope":"global","content":"Verwende immer uv statt pip für die Installation.","confidence":0.8},
+            {"action":"create_node","node_type":"rule","scope":"global","content":"Use uv instead of pip for installs.","confidence":0.8},
+            {"action":"update_node","node_id":"push-tests","new_content":"Führe die Tests immer vor dem Push aus.","new_confidence":0.9}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let batch = BatchContext {
+            output_language: "en",
+            ..Default::default()
+        };
+        let result =
+            analyze_sessions(&store, &backend, &[german], None, 50, usize::MAX, &batch).unwrap();
+
+        assert!(backend.prompts_seen.lock().unwrap()[0].contains("content in English regardless"));
+        assert_eq!((result.nodes_created, result.nodes_updated), (1, 0));
+        assert_eq!(result.language_mismatches, 2, "{:?}", result.skipped);
+        assert!(
+            result.skipped[0].contains("content is in German, not English"),
+            "{:?}",
+            result.skipped
+        );
+        assert!(result.skipped[1].ends_with("(`push-tests` unchanged)"));
+        let kept = store.get(&Scope::Global, "push-tests").unwrap().unwrap();
+        assert_eq!((kept.body.as_str(), kept.confidence), ("Run the tests before pushing.", 0.7));
+    }
+
+    #[test]
+    fn creates_over_the_cap_are_deferred_and_promoted_when_reobserved() {
+        let (tmp, store) = store();
+        let ops: Vec<String> = (0..6)
+            .map(|i| {
+                format!(
+                    r#"{{"action":"create_node","node_type":"rule","scope":"global","content":"Rule number {i} about distinct topic {i}.","confidence":0.{}}}"#,
+                    i + 3
+                )
+            })
+            .collect();
+        let response = format!(r#"{{"reasoning":"r","operations":[{}]}}"#, ops.join(","));
+        let backend = MockBackend::with_responses(vec![response]);
+        let result =
+            analyze_sessions(&store, &backend, &[session("s13", &["a", "b"])], None, 50, 2, &BatchContext::default())
+                .unwrap();
+        assert_eq!(result.nodes_created, 2);
+        assert_eq!(result.candidates_deferred, 4);
+        let ranked: Vec<&str> = result.deferred.iter().map(|b| &b[12..13]).collect();
+        assert_eq!(ranked, ["3", "2", "1", "0"], "deferred most confident first");
+        let mut kept: Vec<String> = store
+            .load_all()
+            .unwrap()
+            .nodes
+            .into_iter()
+            .map(|(_, n)| n.body)
+            .collect();
+        kept.sort();
+        assert_eq!(
+            kept,
+            vec!["Rule number 4 about distinct topic 4.", "Rule number 5 about distinct topic 5."],
+            "highest confidence kept"
+        );
+        let deferred = deferred::load(tmp.path());
+        assert_eq!(deferred.len(), 4);
+        assert_eq!(deferred[0].body, "Rule number 3 about distinct topic 3.");
+        assert_eq!(deferred[0].sources, vec!["session:s13"]);
+
+        // A later batch re-observes a deferred candidate: it is created with
+        // both batches' evidence and leaves the deferred list.
+        let response = r#"{"reasoning":"r","operations":[
+            {"action":"create_node","node_type":"rule","scope":"global","content":"Rule number 3 about distinct topic 3","confidence":0.5}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let result =
+            analyze_sessions(&store, &backend, &[session("s14", &["a", "b"])], None, 50, 10, &BatchContext::default())
+                .unwrap();
+        assert_eq!(result.candidates_promoted, 1);
+        let promoted = store
+            .load_all()
+            .unwrap()
+            .nodes
+            .into_iter()
+            .map(|(_, n)| n)
+            .find(|n| n.body.starts_with("Rule number 3"))
+            .unwrap();
+        assert_eq!(promoted.sources, vec!["session:s14", "session:s13"]);
+        assert!((promoted.confidence - 0.6).abs() < 1e-9);
+        assert_eq!(deferred::load(tmp.path()).len(), 3);
+    }
+
     #[test]
     fn reasoning_is_populated_from_response() {
         let (_tmp, store) = store();
         let response = r#"{"reasoning":"found two rules","operations":[]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
         let result =
-            analyze_sessions(&store, &backend, &[session("s10", &["a", "b"])], None).unwrap();
+            analyze_sessions(&store, &backend, &[session("s10", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         assert_eq!(result.reasoning, "found two rules");
     }
 
@@ -686,6 +1347,8 @@ mod tests {
             created: today,
             updated: today,
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: format!("body {id}"),
         };
         // 55 filler nodes at 0.5 — the prompt builder caps at 50, so without
@@ -697,7 +1360,7 @@ mod tests {
 
         let response = r#"{"reasoning":"nothing new","operations":[]}"#;
         let backend = MockBackend::with_responses(vec![response.to_string()]);
-        analyze_sessions(&store, &backend, &[session("s11", &["a", "b"])], None).unwrap();
+        analyze_sessions(&store, &backend, &[session("s11", &["a", "b"])], None, 50, usize::MAX, &BatchContext::default()).unwrap();
         let prompts = backend.prompts_seen.lock().unwrap();
         assert!(
             prompts[0].contains("top-priority-rule"),
@@ -0,0 +1,295 @@
+//! `[privacy] anonymize_paths`: absolute project paths and the home
+//! directory never leave the machine. Before a prompt is sent each is
+//! replaced by an alias (`⟦project-3f9a1c07⟧`, `⟦home-…⟧`); the response is
+//! mapped back before anything is stored, so nodes keep real paths. The
+//! mapping lives only in memory for one run.
+//!
+//! Rewriting happens on the prompt text as a whole, so session fields,
+//! existing-node bodies and the rules proposed earlier in the run are all
+//! covered. Only absolute paths are searched for: a bare project slug
+//! (`app`, `core`) is an ordinary word, and rewriting it would corrupt
+//! retro's own instructions. An alias is a keyed hash of what it stands for: stable across
+//! runs and independent of which other projects exist, but not derivable
+//! from a guessed path without the machine-local key
+//! (`RunnerState::pseudonym_key`). The `⟦ ⟧` delimiters don't occur in
+//! transcripts, so mapping back never touches text the model wrote itself.
+
+use std::hash::BuildHasher;
+
+use crate::analysis::backend::{AnalysisBackend, BackendResponse};
+use crate::errors::CoreError;
+
+/// Real text <-> alias table for one run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pseudonyms {
+    /// `(real, alias)`, longest real text first so a nested project is
+    /// rewritten before its parent.
+    entries: Vec<(String, String)>,
+}
+
+/// A fresh random key for [`Pseudonyms::new`].
+pub fn new_key() -> u64 {
+    std::collections::hash_map::RandomState::new().hash_one(std::time::SystemTime::now())
+}
+
+/// FNV-1a over the key, then `real`: 8 hex digits.
+fn keyed_hash(key: u64, real: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in key.to_le_bytes().iter().chain(real.as_bytes()) {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:08x}", (hash >> 32) ^ (hash & 0xffff_ffff))
+}
+
+/// `⟦kind-hash⟧`.
+fn alias(key: u64, kind: &str, real: &str) -> String {
+    format!("⟦{kind}-{}⟧", keyed_hash(key, real))
+}
+
+/// Characters that continue a path component: `/work/app` must not match
+/// inside `/work/apple` or `/work/app-v2`.
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Whether `needle` at `start..end` of `text` stands on its own: not the
+/// tail of a longer path and not the head of a longer name (a trailing `.`
+/// only continues the name when another name character follows it).
+fn is_bounded(text: &str, start: usize, end: usize) -> bool {
+    let before_ok = text[..start]
+        .chars()
+        .next_back()
+        .is_none_or(|c| !is_name_char(c) && c != '/' && c != '.');
+    let mut after = text[end..].chars();
+    let after_ok = match after.next() {
+        None => true,
+        Some('.') => !after.next().is_some_and(is_name_char),
+        Some(c) => !is_name_char(c),
+    };
+    before_ok && after_ok
+}
+
+/// Replace the bounded occurrences of `from` in `text` with `to`.
+fn replace_bounded(text: &str, from: &str, to: &str) -> String {
+    if from.is_empty() {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = 0;
+    for (start, _) in text.match_indices(from) {
+        if start < rest {
+            continue;
+        }
+        let end = start + from.len();
+        if is_bounded(text, start, end) {
+            out.push_str(&text[rest..start]);
+            out.push_str(to);
+            rest = end;
+        }
+    }
+    out.push_str(&text[rest..]);
+    out
+}
+
+/// `s` as it appears inside a JSON string literal.
+fn json_escaped(s: &str) -> String {
+    let quoted = serde_json::to_string(s).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+impl Pseudonyms {
+    /// Aliases under `key` for each project path and for `home`. Trailing
+    /// slashes are ignored; empty paths and `/` are skipped.
+    pub fn new<'a>(
+        key: u64,
+        project_paths: impl IntoIterator<Item = &'a str>,
+        home: Option<&str>,
+    ) -> Self {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        let mut add = |kind: &str, real: &str| {
+            let real = real.trim_end_matches('/');
+            if !real.is_empty() && !entries.iter().any(|(r, _)| r == real) {
+                entries.push((real.to_string(), alias(key, kind, real)));
+            }
+        };
+        for path in project_paths {
+            add("project", path);
+        }
+        if let Some(home) = home {
+            add("home", home);
+        }
+        entries.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        Pseudonyms { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replace every known path in `text` (bare or JSON-escaped)
+    /// with its alias. Paths below a project keep their relative part:
+    /// `/work/app/src/main.rs` becomes `⟦project-…⟧/src/main.rs`.
+    pub fn anonymize(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for (real, alias) in &self.entries {
+            out = replace_bounded(&out, real, alias);
+            let escaped = json_escaped(real);
+            if escaped != *real {
+                out = replace_bounded(&out, &escaped, alias);
+            }
+        }
+        out
+    }
+
+    /// Map aliases in a JSON response back to the real text, JSON-escaped so
+    /// the result still parses (for ordinary paths that is the path itself).
+    pub fn deanonymize(&self, json: &str) -> String {
+        let mut out = json.to_string();
+        for (real, alias) in &self.entries {
+            out = out.replace(alias.as_str(), &json_escaped(real));
+        }
+        out
+    }
+}
+
+/// A backend that only ever sees pseudonyms: prompts are anonymized on the
+/// way out and responses de-anonymized on the way back.
+pub struct Anonymized<'a> {
+    pub inner: &'a dyn AnalysisBackend,
+    pub names: &'a Pseudonyms,
+}
+
+impl AnalysisBackend for Anonymized<'_> {
+    fn execute(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+    ) -> Result<BackendResponse, CoreError> {
+        let mut response = self.inner.execute(&self.names.anonymize(prompt), json_schema)?;
+        response.text = self.names.deanonymize(&response.text);
+        Ok(response)
+    }
+
+    fn execute_with_model(
+        &self,
+        prompt: &str,
+        json_schema: Option<&str>,
+        model: &str,
+    ) -> Result<BackendResponse, CoreError> {
+        let prompt = self.names.anonymize(prompt);
+        let mut response = self.inner.execute_with_model(&prompt, json_schema, model)?;
+        response.text = self.names.deanonymize(&response.text);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::backend::MockBackend;
+
+    const KEY: u64 = 0x5eed;
+
+    fn names() -> Pseudonyms {
+        Pseudonyms::new(
+            KEY,
+            [
+                "/home/alice/work/acme",
+                "/home/alice/work/acme/web/",
+                "/home/alice/side",
+                "",
+                "/home/alice/work/acme",
+            ],
+            Some("/home/alice"),
+        )
+    }
+
+    fn alias_of(n: &Pseudonyms, real: &str) -> String {
+        n.entries.iter().find(|(r, _)| r == real).unwrap().1.clone()
+    }
+
+    #[test]
+    fn aliases_are_keyed_and_stable() {
+        let n = names();
+        let acme = alias_of(&n, "/home/alice/work/acme");
+        assert!(acme.starts_with("⟦project-") && acme.ends_with('⟧'), "{acme}");
+        // Independent of which other projects are known...
+        let alone = Pseudonyms::new(KEY, ["/home/alice/work/acme"], None);
+        assert_eq!(alias_of(&alone, "/home/alice/work/acme"), acme);
+        // ...but not of the key.
+        let other = Pseudonyms::new(KEY + 1, ["/home/alice/work/acme"], None);
+        assert_ne!(alias_of(&other, "/home/alice/work/acme"), acme);
+        assert_eq!(n.entries.len(), 4, "duplicates and empties skipped");
+        assert!(Pseudonyms::new(KEY, ["/"], None).is_empty());
+    }
+
+    #[test]
+    fn nested_paths_and_home_are_replaced() {
+        let n = names();
+        let text = "edit /home/alice/work/acme/web/src/app.ts, then \
+                    /home/alice/work/acme/lib.rs; notes in /home/alice/notes.md";
+        let alias = n.anonymize(text);
+        let web = alias_of(&n, "/home/alice/work/acme/web");
+        let acme = alias_of(&n, "/home/alice/work/acme");
+        let home = alias_of(&n, "/home/alice");
+        assert!(alias.contains(&format!("edit {web}/src/app.ts, then {acme}/lib.rs")));
+        assert!(alias.contains(&format!("notes in {home}/notes.md")), "{alias}");
+        assert!(!alias.contains("alice"), "{alias}");
+    }
+
+    #[test]
+    fn partial_overlaps_are_left_alone() {
+        let n = names();
+        let text = "/home/alice/work/acme-old, /home/alice/work/acmeco, \
+                    /x/home/alice/side, /home/alice/work/acme_2";
+        // Only the home dir matches.
+        let home = alias_of(&n, "/home/alice");
+        assert_eq!(n.anonymize(text), text.replace("/home/alice/work", &format!("{home}/work")));
+    }
+
+    #[test]
+    fn common_word_project_names_leave_the_instructions_alone() {
+        use crate::analysis::{prompts::build_graph_analysis_prompt, v3::BatchContext};
+        let n = Pseudonyms::new(KEY, ["/work/project", "/work/rule", "/work/app"], None);
+        let prompt =
+            build_graph_analysis_prompt(&[], &[], 0, None, &[], &BatchContext::default());
+        assert!(prompt.contains("**project**") && prompt.contains("**rule**"));
+        assert_eq!(n.anonymize(&prompt), prompt);
+        let reply = r#"{"content":"In this project, run the app's rule check."}"#;
+        assert_eq!(n.deanonymize(reply), reply);
+    }
+
+    #[test]
+    fn deanonymize_only_touches_aliases() {
+        let n = names();
+        let text = "cd /home/alice/work/acme/web && cargo test; see /home/alice/side/README.md";
+        let alias = n.anonymize(text);
+        assert_eq!(n.deanonymize(&alias), text);
+        // Text the model wrote itself, however alias-like, stays as written.
+        let own = "project-A and ⟦project-x⟧ and name-acme";
+        assert_eq!(n.deanonymize(own), own);
+    }
+
+    #[test]
+    fn json_escaped_paths_are_covered_both_ways() {
+        let n = Pseudonyms::new(KEY, [r#"/work/say "hi""#], None);
+        let json = serde_json::json!({"p": r#"/work/say "hi"/x"#}).to_string();
+        let alias = n.anonymize(&json);
+        assert_eq!(alias, format!(r#"{{"p":"{}/x"}}"#, alias_of(&n, r#"/work/say "hi""#)));
+        let back: serde_json::Value = serde_json::from_str(&n.deanonymize(&alias)).unwrap();
+        assert_eq!(back["p"], r#"/work/say "hi"/x"#);
+    }
+
+    #[test]
+    fn backend_sees_only_aliases() {
+        let n = names();
+        let acme = alias_of(&n, "/home/alice/work/acme");
+        let reply = format!(r#"{{"content":"run {acme}/check.sh"}}"#);
+        let mock = MockBackend::with_responses(vec![reply]);
+        let backend = Anonymized { inner: &mock, names: &n };
+        let response = backend.execute("sessions in /home/alice/work/acme", None).unwrap();
+        assert_eq!(mock.prompts_seen.lock().unwrap()[0], format!("sessions in {acme}"));
+        assert_eq!(response.text, r#"{"content":"run /home/alice/work/acme/check.sh"}"#);
+    }
+}
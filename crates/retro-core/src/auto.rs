@@ -0,0 +1,353 @@
+//! Entry checks shared by the automatic paths: the SessionEnd/SessionStart
+//! hooks (`retro observe`, `retro brief`) and the `retro run --background`
+//! they spawn. None of them has a terminal or may fail its caller, so each
+//! check that says "not now" becomes a logged skip instead of an error:
+//!
+//! - no store yet (hooks outlived the store, or fired before `retro init`);
+//! - an unwritable store, which can't even record health;
+//! - for the run, a config that doesn't load (hooks fall back to defaults —
+//!   capture must not stop over a typo — but analysis must not run on them);
+//! - for the run, fewer queued sessions than `[analysis]
+//!   min_sessions_for_auto` — a prompt's worth of preamble for one short
+//!   session is mostly waste, so they wait for company;
+//! - for the run, another run holding `run.lock` (reported by the caller,
+//!   since `runner_v3::run_v3` takes the lock itself).
+//!
+//! Skips log under [`logging::RUNNER`] as `skipped <kind>: <reason>`, so one
+//! grep of `hook-stderr.log` covers all three paths. [`trace`] walks the
+//! same checks without acting on them, for `retro run --background
+//! --dry-run`.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::Config;
+use crate::logging;
+
+/// Which automatic path is starting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoKind {
+    Observe,
+    Brief,
+    Run,
+}
+
+impl AutoKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            AutoKind::Observe => "observe",
+            AutoKind::Brief => "brief",
+            AutoKind::Run => "run",
+        }
+    }
+}
+
+/// Why an automatic path did not proceed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Skip {
+    Uninitialized,
+    Unwritable(String),
+    Config(String),
+    BelowMinSessions { queued: usize, min: usize },
+    Locked,
+}
+
+impl std::fmt::Display for Skip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Skip::Uninitialized => write!(f, "retro is not initialized"),
+            Skip::Unwritable(e) | Skip::Config(e) => write!(f, "{e}"),
+            Skip::BelowMinSessions { queued, min } => {
+                write!(f, "{queued} session(s) waiting (will analyze at {min}+)")
+            }
+            Skip::Locked => write!(f, "another retro run holds the lock"),
+        }
+    }
+}
+
+/// What a path that may proceed starts with.
+#[derive(Debug)]
+pub struct AutoContext {
+    pub config: Config,
+}
+
+/// Run the entry checks for `kind` against the store at `dir`.
+pub fn check(dir: &Path, kind: AutoKind) -> Result<AutoContext, Skip> {
+    check_store(dir)?;
+    let config = load_config(dir, kind)?;
+    if kind == AutoKind::Run {
+        check_queue(dir, &config)?;
+    }
+    Ok(AutoContext { config })
+}
+
+fn check_store(dir: &Path) -> Result<(), Skip> {
+    if !dir.join("knowledge").exists() {
+        return Err(Skip::Uninitialized);
+    }
+    crate::util::check_writable_dir(dir).map_err(|e| Skip::Unwritable(e.to_string()))
+}
+
+fn load_config(dir: &Path, kind: AutoKind) -> Result<Config, Skip> {
+    let path = dir.join("config.toml");
+    match kind {
+        AutoKind::Run => Config::load(&path).map_err(|e| Skip::Config(e.to_string())),
+        AutoKind::Observe | AutoKind::Brief => Ok(Config::load_or_default(&path)),
+    }
+}
+
+/// Queued sessions, when there are enough for an automatic run.
+fn check_queue(dir: &Path, config: &Config) -> Result<usize, Skip> {
+    let queued = crate::store::queue::list(dir).map(|q| q.len()).unwrap_or(0);
+    let min = config.analysis.min_sessions_for_auto;
+    // 0 and 1 both mean no minimum: a run without queued sessions still
+    // catches up and projects, as it always has.
+    if min > 1 && queued < min {
+        return Err(Skip::BelowMinSessions { queued, min });
+    }
+    Ok(queued)
+}
+
+/// One step of [`trace`]: what was looked at and what the hook path would
+/// do about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gate {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// The hook path's checks in the order it meets them — the spawn throttle
+/// (for an event in `cwd`), then [`check`]'s, then the lock and the AI
+/// budget the run itself checks — stopping where the path would stop.
+/// Uses the same decision functions and leaves nothing behind: the spawn
+/// marker isn't claimed, the lock is only inspected, and the writability
+/// probe is removed as usual.
+pub fn trace(dir: &Path, cwd: Option<&Path>, now: DateTime<Utc>) -> Vec<Gate> {
+    use crate::lock::LockState;
+    use crate::throttle::{self, SpawnDecision};
+
+    let mut gates = Vec::new();
+    let mut gate = |name, result: Result<String, String>| {
+        let passed = result.is_ok();
+        let detail = result.unwrap_or_else(|e| e);
+        gates.push(Gate { name, passed, detail });
+        passed
+    };
+
+    let spawn = match throttle::spawn_decision(dir, cwd, now) {
+        Ok(SpawnDecision::Spawn) => Ok(format!(
+            "no run spawned in the last {}s → would spawn",
+            throttle::COALESCE_WINDOW_SECS
+        )),
+        Ok(SpawnDecision::Coalesced(age)) => Err(format!(
+            "last spawn {age}s ago < {}s → would coalesce",
+            throttle::COALESCE_WINDOW_SECS
+        )),
+        Ok(SpawnDecision::RepoBusy(repo, op)) => {
+            Err(format!("{op} in progress in {} → would defer", repo.display()))
+        }
+        // claim_spawn's caller spawns anyway
+        Err(e) => Ok(format!("runner state unreadable ({e}) → would spawn anyway")),
+    };
+    if !gate("spawn", spawn) {
+        return gates;
+    }
+    let store = check_store(dir)
+        .map(|()| format!("{} initialized and writable", dir.display()))
+        .map_err(|skip| format!("{skip} → would skip"));
+    if !gate("store", store) {
+        return gates;
+    }
+    let config = match load_config(dir, AutoKind::Run) {
+        Ok(config) => config,
+        Err(skip) => {
+            gate("config", Err(format!("{skip} → would skip")));
+            return gates;
+        }
+    };
+    gate("config", Ok("config.toml loads".to_string()));
+    let min = config.analysis.min_sessions_for_auto;
+    let queue = match check_queue(dir, &config) {
+        Ok(queued) if min <= 1 => Ok(format!("{queued} queued, no minimum → would analyze")),
+        Ok(queued) => Ok(format!("{queued} queued ≥ min {min} → would analyze")),
+        Err(Skip::BelowMinSessions { queued, .. }) => {
+            Err(format!("{queued} queued < min {min} → would wait"))
+        }
+        Err(skip) => Err(format!("{skip} → would skip")),
+    };
+    if !gate("queue", queue) {
+        return gates;
+    }
+    let lock = match crate::lock::inspect(&dir.join("run.lock")) {
+        LockState::Free => Ok("free".to_string()),
+        LockState::Stale(pid) => Ok(format!("stale (PID {pid} is gone) → would take it over")),
+        LockState::Held(pid) => Err(format!("held by PID {pid} → would skip")),
+    };
+    if !gate("lock", lock) {
+        return gates;
+    }
+    let budget = if config.ai.backend == "heuristic" {
+        Ok("heuristic backend, no AI calls".to_string())
+    } else {
+        let state = crate::store::state::RunnerState::load(dir).unwrap_or_default();
+        let today = now.date_naive().to_string();
+        let max = config.runner.max_ai_calls_per_day;
+        match state.budget_remaining(&today, max) {
+            0 => Err(format!(
+                "0/{max} AI call(s) left today → would leave the queue for tomorrow"
+            )),
+            left => Ok(format!("{left}/{max} AI call(s) left today")),
+        }
+    };
+    gate("budget", budget);
+    gates
+}
+
+/// [`check`], logging a skip. `None` means: return quietly.
+pub fn begin(dir: &Path, kind: AutoKind) -> Option<AutoContext> {
+    check(dir, kind).map_err(|skip| log_skip(kind, &skip)).ok()
+}
+
+/// Log `skip` for `kind`. An absent store is the normal state of a machine
+/// without retro set up, and a short queue the normal state between
+/// sessions, so they only show at debug level.
+pub fn log_skip(kind: AutoKind, skip: &Skip) {
+    match skip {
+        Skip::Uninitialized | Skip::BelowMinSessions { .. } | Skip::Locked => {
+            log::debug!(target: logging::RUNNER, "skipped {}: {skip}", kind.name());
+        }
+        Skip::Unwritable(_) | Skip::Config(_) => {
+            log::warn!(target: logging::RUNNER, "skipped {}: {skip}", kind.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("knowledge/global")).unwrap();
+        tmp
+    }
+
+    #[test]
+    fn missing_store_skips_every_kind() {
+        let tmp = TempDir::new().unwrap();
+        for kind in [AutoKind::Observe, AutoKind::Brief, AutoKind::Run] {
+            assert_eq!(check(tmp.path(), kind).unwrap_err(), Skip::Uninitialized);
+        }
+        assert!(begin(tmp.path(), AutoKind::Run).is_none());
+    }
+
+    #[test]
+    fn broken_config_skips_only_the_run() {
+        let tmp = store();
+        std::fs::write(tmp.path().join("config.toml"), "[analysis\nwindow_days = ").unwrap();
+        for kind in [AutoKind::Observe, AutoKind::Brief] {
+            assert!(check(tmp.path(), kind).is_ok(), "{kind:?} falls back to defaults");
+        }
+        assert!(matches!(check(tmp.path(), AutoKind::Run), Err(Skip::Config(_))));
+    }
+
+    #[test]
+    fn initialized_store_proceeds_with_its_config() {
+        let tmp = store();
+        std::fs::write(
+            tmp.path().join("config.toml"),
+            "[runner]\nmax_ai_calls_per_day = 3\n[analysis]\nmin_sessions_for_auto = 0\n",
+        )
+        .unwrap();
+        let ctx = check(tmp.path(), AutoKind::Run).unwrap();
+        assert_eq!(ctx.config.runner.max_ai_calls_per_day, 3);
+    }
+
+    #[test]
+    fn run_waits_for_enough_queued_sessions() {
+        let tmp = store();
+        std::fs::write(tmp.path().join("config.toml"), "[analysis]\nmin_sessions_for_auto = 3\n")
+            .unwrap();
+        let enqueue = |id: &str| {
+            let entry = crate::store::queue::QueueEntry {
+                session_id: id.to_string(),
+                transcript_path: format!("/t/{id}.jsonl"),
+                cwd: None,
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            };
+            crate::store::queue::enqueue(tmp.path(), &entry).unwrap();
+        };
+        enqueue("a");
+        enqueue("b");
+        let skip = check(tmp.path(), AutoKind::Run).unwrap_err();
+        assert_eq!(skip, Skip::BelowMinSessions { queued: 2, min: 3 });
+        assert_eq!(skip.to_string(), "2 session(s) waiting (will analyze at 3+)");
+        assert!(check(tmp.path(), AutoKind::Observe).is_ok(), "capture never waits");
+        enqueue("c");
+        assert!(check(tmp.path(), AutoKind::Run).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unwritable_store_skips() {
+        use std::os::unix::fs::PermissionsExt;
+        // root ignores permission bits — nothing to observe there.
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+        let tmp = store();
+        std::fs::set_permissions(tmp.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+        let result = check(tmp.path(), AutoKind::Observe);
+        std::fs::set_permissions(tmp.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(matches!(result, Err(Skip::Unwritable(_))), "got: {result:?}");
+    }
+
+    #[test]
+    fn trace_reports_each_gate_and_stops_where_the_run_would() {
+        let tmp = store();
+        let now = DateTime::parse_from_rfc3339("2026-07-06T12:00:00Z").unwrap().to_utc();
+        let verdicts = |gates: Vec<Gate>| -> Vec<(&'static str, bool)> {
+            gates.iter().map(|g| (g.name, g.passed)).collect()
+        };
+        std::fs::write(
+            tmp.path().join("config.toml"),
+            "[runner]\nmax_ai_calls_per_day = 2\n[analysis]\nmin_sessions_for_auto = 3\n",
+        )
+        .unwrap();
+        let gates = trace(tmp.path(), None, now);
+        assert_eq!(
+            verdicts(gates.clone()),
+            vec![("spawn", true), ("store", true), ("config", true), ("queue", false)]
+        );
+        assert_eq!(gates[3].detail, "0 queued < min 3 → would wait");
+
+        // The default: no minimum.
+        std::fs::write(tmp.path().join("config.toml"), "[runner]\nmax_ai_calls_per_day = 2\n")
+            .unwrap();
+        let gates = trace(tmp.path(), None, now);
+        let names: Vec<&str> = gates.iter().map(|g| g.name).collect();
+        assert_eq!(names, ["spawn", "store", "config", "queue", "lock", "budget"]);
+        assert!(gates.iter().all(|g| g.passed), "{gates:?}");
+        assert_eq!(gates[3].detail, "0 queued, no minimum → would analyze");
+        assert_eq!(gates[5].detail, "2/2 AI call(s) left today");
+
+        // a recent spawn: the hook would coalesce, and nothing was claimed
+        let mut state = crate::store::state::RunnerState::load(tmp.path()).unwrap();
+        state.last_spawn_at = Some((now - chrono::Duration::seconds(12)).to_rfc3339());
+        state.save(tmp.path()).unwrap();
+        let gates = trace(tmp.path(), None, now);
+        assert_eq!(verdicts(gates.clone()), vec![("spawn", false)]);
+        assert_eq!(gates[0].detail, "last spawn 12s ago < 30s → would coalesce");
+        let after = crate::store::state::RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(after.last_spawn_at, state.last_spawn_at);
+    }
+
+    #[test]
+    fn skip_reasons_read_as_log_lines() {
+        assert_eq!(Skip::Uninitialized.to_string(), "retro is not initialized");
+        assert_eq!(Skip::Locked.to_string(), "another retro run holds the lock");
+    }
+}
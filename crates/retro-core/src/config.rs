@@ -1,6 +1,28 @@
 use crate::errors::CoreError;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Claude Code's own override for its config directory. When set (non-empty)
+/// it wins over `[paths] claude_dir`, matching where Claude Code itself reads
+/// and writes.
+pub const CLAUDE_CONFIG_DIR: &str = "CLAUDE_CONFIG_DIR";
+
+/// `CLAUDE_CONFIG_DIR` as captured by [`capture_env`]. Never read from the
+/// environment directly, so a library caller or `cargo test` run from a shell
+/// that exports it keeps its explicit `claude_dir`.
+static ENV_CLAUDE_DIR: OnceLock<Option<String>> = OnceLock::new();
+
+/// Capture `CLAUDE_CONFIG_DIR` for every later [`Config::load`]. The binary
+/// calls this once at startup; later calls are no-ops.
+pub fn capture_env() {
+    ENV_CLAUDE_DIR.get_or_init(|| {
+        std::env::var(CLAUDE_CONFIG_DIR)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+    });
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -18,6 +40,14 @@ pub struct Config {
     pub knowledge: KnowledgeConfig,
     #[serde(default = "default_ui")]
     pub ui: UiConfig,
+    #[serde(default = "default_ingest")]
+    pub ingest: IngestConfig,
+    #[serde(default = "default_hooks")]
+    pub hooks: HooksConfig,
+    #[serde(default = "default_updates")]
+    pub updates: UpdatesConfig,
+    #[serde(default = "default_log")]
+    pub log: LogConfig,
 }
 
 impl Default for Config {
@@ -30,6 +60,10 @@ impl Default for Config {
             runner: default_runner(),
             knowledge: default_knowledge(),
             ui: default_ui(),
+            ingest: default_ingest(),
+            hooks: default_hooks(),
+            updates: default_updates(),
+            log: default_log(),
         }
     }
 }
@@ -42,6 +76,31 @@ pub struct AnalysisConfig {
     pub confidence_threshold: f64,
     #[serde(default = "default_staleness_days")]
     pub staleness_days: u32,
+    /// Cap on existing nodes listed in each analysis prompt (most relevant
+    /// to the batch first); keeps the prompt from growing with the store.
+    #[serde(default = "default_max_existing_nodes_in_prompt")]
+    pub max_existing_nodes_in_prompt: usize,
+    /// Cap on nodes created per run (highest confidence kept); the rest are
+    /// deferred (`store::deferred`) until re-observed. Updates and merges are
+    /// never capped. 0 disables the cap.
+    #[serde(default = "default_max_new_nodes_per_run")]
+    pub max_new_nodes_per_run: usize,
+    /// Condense sessions too large for the analysis prompt with
+    /// `[ai] summary_model` first (`analysis::summarize`), instead of
+    /// truncating them.
+    #[serde(default)]
+    pub summarize_large_sessions: bool,
+    /// Language node content is written in, whatever the sessions' language
+    /// (`en`, `de`, or a name like `German`). Empty: no instruction, no check
+    /// (`analysis::language`).
+    #[serde(default)]
+    pub output_language: String,
+    /// Queued sessions a hook-spawned `retro run --background` waits for
+    /// before it runs (`auto`); fewer stay queued. `retro run` ignores it.
+    /// 0 or 1 (the default): no minimum, every session is analyzed as it
+    /// ends.
+    #[serde(default = "default_min_sessions_for_auto")]
+    pub min_sessions_for_auto: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,12 +109,41 @@ pub struct AiConfig {
     pub backend: String,
     #[serde(default = "default_model")]
     pub model: String,
+    /// Model for the per-session summaries of
+    /// `[analysis] summarize_large_sessions`: a cheaper one than `model`.
+    #[serde(default = "default_summary_model")]
+    pub summary_model: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathsConfig {
     #[serde(default = "default_claude_dir")]
     pub claude_dir: String,
+    /// `CLAUDE_CONFIG_DIR` override applied at load; never written back to
+    /// config.toml.
+    #[serde(skip)]
+    pub claude_dir_env: Option<String>,
+}
+
+/// Where [`Config::claude_dir`] came from, most specific first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaudeDirSource {
+    /// The `CLAUDE_CONFIG_DIR` environment variable.
+    Env,
+    /// `[paths] claude_dir` in config.toml.
+    Config,
+    /// The built-in `~/.claude`.
+    Default,
+}
+
+impl ClaudeDirSource {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ClaudeDirSource::Env => "from $CLAUDE_CONFIG_DIR",
+            ClaudeDirSource::Config => "from [paths] claude_dir",
+            ClaudeDirSource::Default => "default",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +152,10 @@ pub struct PrivacyConfig {
     pub scrub_secrets: bool,
     #[serde(default)]
     pub exclude_projects: Vec<String>,
+    /// Replace absolute project paths with pseudonyms (`project-A`) in
+    /// analysis prompts, restoring them in the response before storage.
+    #[serde(default)]
+    pub anonymize_paths: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +170,69 @@ pub struct KnowledgeConfig {
     pub confidence_threshold: f64,
     #[serde(default = "default_global_promotion_threshold")]
     pub global_promotion_threshold: f64,
+    /// Make CLAUDE.md lint findings (`projection::claude_md::lint`) block the
+    /// runner's projection of the affected file instead of only warning.
+    #[serde(default)]
+    pub strict_lint: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestConfig {
+    /// Sessions matching any rule are dropped from the queue unanalyzed
+    /// (CI `claude -p` runs, bots). See `ingest::ignore`.
+    #[serde(default)]
+    pub ignore_session_if: Vec<IgnoreRule>,
+    /// Also analyze prompts from Claude Code's `history.jsonl` whose
+    /// transcripts are gone, as one weak-signal pseudo-session per project.
+    /// See `ingest::history`.
+    #[serde(default)]
+    pub include_history: bool,
+}
+
+/// One `[[ingest.ignore_session_if]]` rule. Every matcher that is set must
+/// match; a rule with none set is rejected at load time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IgnoreRule {
+    /// Regex searched in the session's first user message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_message: Option<String>,
+    /// Session working directory prefix (`~` expanded).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd_prefix: Option<String>,
+    /// Model name, exact match (e.g. `claude-haiku-4-5`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Hook behavior on shared machines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Observe sessions in checkouts owned by another user (a shared deploy
+    /// checkout). Off by default: two users' runs would race on the
+    /// checkout's CLAUDE.local.md.
+    #[serde(default)]
+    pub allow_shared_checkout: bool,
+}
+
+/// Release checks. Off by default: retro makes no network calls of its own
+/// unless asked to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatesConfig {
+    /// Ask the GitHub releases API for the newest release, at most once a
+    /// day, and mention it in `retro status` and the session briefing.
+    #[serde(default)]
+    pub check_github: bool,
+}
+
+/// Audit trail of projected files (`retro log`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Keep the full content of every projected file under
+    /// `~/.retro/snapshots/`, so `retro log --show-files` can diff it against
+    /// the file as it is now. Digests and sizes are always recorded.
+    #[serde(default)]
+    pub store_apply_snapshots: bool,
 }
 
 /// v3 dashboard server settings.
@@ -96,6 +251,11 @@ fn default_analysis() -> AnalysisConfig {
         window_days: default_window_days(),
         confidence_threshold: default_confidence_threshold(),
         staleness_days: default_staleness_days(),
+        max_existing_nodes_in_prompt: default_max_existing_nodes_in_prompt(),
+        max_new_nodes_per_run: default_max_new_nodes_per_run(),
+        summarize_large_sessions: false,
+        output_language: String::new(),
+        min_sessions_for_auto: default_min_sessions_for_auto(),
     }
 }
 
@@ -103,12 +263,14 @@ fn default_ai() -> AiConfig {
     AiConfig {
         backend: default_backend(),
         model: default_model(),
+        summary_model: default_summary_model(),
     }
 }
 
 fn default_paths() -> PathsConfig {
     PathsConfig {
         claude_dir: default_claude_dir(),
+        claude_dir_env: None,
     }
 }
 
@@ -116,6 +278,7 @@ fn default_privacy() -> PrivacyConfig {
     PrivacyConfig {
         scrub_secrets: default_scrub_secrets(),
         exclude_projects: Vec::new(),
+        anonymize_paths: false,
     }
 }
 
@@ -128,12 +291,24 @@ fn default_confidence_threshold() -> f64 {
 fn default_staleness_days() -> u32 {
     28
 }
+fn default_max_existing_nodes_in_prompt() -> usize {
+    50
+}
+fn default_max_new_nodes_per_run() -> usize {
+    10
+}
+fn default_min_sessions_for_auto() -> usize {
+    1
+}
 fn default_backend() -> String {
     "claude-cli".to_string()
 }
 fn default_model() -> String {
     "sonnet".to_string()
 }
+fn default_summary_model() -> String {
+    "haiku".to_string()
+}
 fn default_claude_dir() -> String {
     "~/.claude".to_string()
 }
@@ -158,6 +333,32 @@ fn default_knowledge() -> KnowledgeConfig {
     KnowledgeConfig {
         confidence_threshold: default_confidence_threshold(),
         global_promotion_threshold: default_global_promotion_threshold(),
+        strict_lint: false,
+    }
+}
+
+fn default_ingest() -> IngestConfig {
+    IngestConfig {
+        ignore_session_if: Vec::new(),
+        include_history: false,
+    }
+}
+
+fn default_hooks() -> HooksConfig {
+    HooksConfig {
+        allow_shared_checkout: false,
+    }
+}
+
+fn default_updates() -> UpdatesConfig {
+    UpdatesConfig {
+        check_github: false,
+    }
+}
+
+fn default_log() -> LogConfig {
+    LogConfig {
+        store_apply_snapshots: false,
     }
 }
 
@@ -169,17 +370,34 @@ fn default_ui() -> UiConfig {
 
 impl Config {
     /// Load config from the given path, or return defaults if file doesn't exist.
+    /// A captured `CLAUDE_CONFIG_DIR` ([`capture_env`]) is applied on top.
     pub fn load(path: &Path) -> Result<Self, CoreError> {
-        if path.exists() {
+        let config = if path.exists() {
             let contents = std::fs::read_to_string(path)
                 .map_err(|e| CoreError::Io(format!("reading config: {e}")))?;
             let config: Config =
                 toml::from_str(&contents).map_err(|e| CoreError::Config(e.to_string()))?;
-
-            Ok(config)
+            crate::ingest::ignore::validate(&config.ingest.ignore_session_if)?;
+            config
         } else {
-            Ok(Config::default())
-        }
+            Config::default()
+        };
+        Ok(config.with_env_claude_dir(ENV_CLAUDE_DIR.get().cloned().flatten()))
+    }
+
+    /// [`Config::load`], falling back to defaults (still with the
+    /// `CLAUDE_CONFIG_DIR` override) when config.toml is unreadable — for
+    /// hooks that must not fail.
+    pub fn load_or_default(path: &Path) -> Self {
+        Config::load(path).unwrap_or_else(|_| {
+            Config::default().with_env_claude_dir(ENV_CLAUDE_DIR.get().cloned().flatten())
+        })
+    }
+
+    /// Override `claude_dir` as `CLAUDE_CONFIG_DIR` would (`None` clears it).
+    pub fn with_env_claude_dir(mut self, dir: Option<String>) -> Self {
+        self.paths.claude_dir_env = dir;
+        self
     }
 
     /// Write config to the given path.
@@ -195,30 +413,123 @@ impl Config {
         Ok(())
     }
 
-    /// Resolve the claude_dir path, expanding ~ to home directory.
+    /// Resolve the claude_dir path, expanding ~ to home directory:
+    /// `CLAUDE_CONFIG_DIR`, then `[paths] claude_dir`, then `~/.claude`.
+    /// Every reader of Claude Code's files goes through here.
     pub fn claude_dir(&self) -> PathBuf {
-        expand_tilde(&self.paths.claude_dir)
+        match &self.paths.claude_dir_env {
+            Some(dir) => expand_tilde(dir),
+            None => expand_tilde(&self.paths.claude_dir),
+        }
+    }
+
+    /// Which setting [`Config::claude_dir`] resolved from.
+    pub fn claude_dir_source(&self) -> ClaudeDirSource {
+        if self.paths.claude_dir_env.is_some() {
+            ClaudeDirSource::Env
+        } else if self.paths.claude_dir != default_claude_dir() {
+            ClaudeDirSource::Config
+        } else {
+            ClaudeDirSource::Default
+        }
+    }
+
+    /// The behavior-relevant settings as flat `section.key -> value` pairs.
+    /// The runner stores the last one it ran with and records a config
+    /// change when they differ. Config holds no secrets today; anything
+    /// secret added later must stay out of this list.
+    pub fn snapshot(&self) -> BTreeMap<String, String> {
+        [
+            ("ai.backend", self.ai.backend.clone()),
+            ("ai.model", self.ai.model.clone()),
+            ("ai.summary_model", self.ai.summary_model.clone()),
+            ("analysis.window_days", self.analysis.window_days.to_string()),
+            ("analysis.staleness_days", self.analysis.staleness_days.to_string()),
+            (
+                "analysis.max_existing_nodes_in_prompt",
+                self.analysis.max_existing_nodes_in_prompt.to_string(),
+            ),
+            (
+                "analysis.max_new_nodes_per_run",
+                self.analysis.max_new_nodes_per_run.to_string(),
+            ),
+            (
+                "analysis.summarize_large_sessions",
+                self.analysis.summarize_large_sessions.to_string(),
+            ),
+            ("analysis.output_language", self.analysis.output_language.clone()),
+            (
+                "analysis.min_sessions_for_auto",
+                self.analysis.min_sessions_for_auto.to_string(),
+            ),
+            (
+                "knowledge.confidence_threshold",
+                self.knowledge.confidence_threshold.to_string(),
+            ),
+            (
+                "knowledge.global_promotion_threshold",
+                self.knowledge.global_promotion_threshold.to_string(),
+            ),
+            ("runner.max_ai_calls_per_day", self.runner.max_ai_calls_per_day.to_string()),
+            ("knowledge.strict_lint", self.knowledge.strict_lint.to_string()),
+            ("ingest.include_history", self.ingest.include_history.to_string()),
+            (
+                "hooks.allow_shared_checkout",
+                self.hooks.allow_shared_checkout.to_string(),
+            ),
+            ("updates.check_github", self.updates.check_github.to_string()),
+            ("log.store_apply_snapshots", self.log.store_apply_snapshots.to_string()),
+            ("privacy.scrub_secrets", self.privacy.scrub_secrets.to_string()),
+            ("privacy.exclude_projects", self.privacy.exclude_projects.join(", ")),
+            ("privacy.anonymize_paths", self.privacy.anonymize_paths.to_string()),
+            ("paths.claude_dir", self.paths.claude_dir.clone()),
+            (
+                "paths.claude_config_dir_env",
+                self.paths.claude_dir_env.clone().unwrap_or_default(),
+            ),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
     }
 }
 
+/// Field-level diff between two [`Config::snapshot`]s, one line per key in
+/// key order: `key: old → new`, `key: added (new)`, `key: removed (was old)`.
+/// Empty when nothing changed.
+pub fn diff_snapshots(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let keys: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter_map(|k| match (old.get(k), new.get(k)) {
+            (Some(a), Some(b)) if a != b => Some(format!("{k}: {a} → {b}")),
+            (None, Some(b)) => Some(format!("{k}: added ({b})")),
+            (Some(a), None) => Some(format!("{k}: removed (was {a})")),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Get the retro data directory.
-/// Uses `RETRO_HOME` env var if set, otherwise defaults to `~/.retro/`.
+/// Uses `RETRO_HOME` env var if set, otherwise `.retro/` in the effective
+/// user's home — a `$HOME` owned by another user (`sudo`/`su`) gives way to
+/// the passwd entry, so it never lands in their store (see `user::home`).
 pub fn retro_dir() -> PathBuf {
     if let Ok(dir) = std::env::var("RETRO_HOME") {
         return PathBuf::from(dir);
     }
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home).join(".retro")
+    crate::user::home().path.join(".retro")
 }
 
-/// Expand ~ at the start of a path.
+/// Expand ~ at the start of a path to the effective user's home (see
+/// `user::home`), so `~/.claude` under `sudo` is not the caller's.
 pub fn expand_tilde(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/") {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(rest)
+        crate::user::home().path.join(rest)
     } else if path == "~" {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home)
+        crate::user::home().path
     } else {
         PathBuf::from(path)
     }
@@ -284,6 +595,48 @@ enabled = true
         assert_eq!(config.knowledge.confidence_threshold, 0.7);
     }
 
+    #[test]
+    fn test_ignore_rules_load_and_invalid_rules_fail_loudly() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[[ingest.ignore_session_if]]\nfirst_message = \"^Summarize\"\n\n[[ingest.ignore_session_if]]\nmodel = \"claude-haiku-4-5\"\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.ingest.ignore_session_if.len(), 2);
+        assert_eq!(config.ingest.ignore_session_if[1].model.as_deref(), Some("claude-haiku-4-5"));
+
+        std::fs::write(&path, "[[ingest.ignore_session_if]]\nfirst_message = \"(\"\n").unwrap();
+        let err = Config::load(&path).unwrap_err().to_string();
+        assert!(err.contains("rule 1"), "got: {err}");
+        std::fs::write(&path, "[[ingest.ignore_session_if]]\nfirst_mesage = \"typo\"\n").unwrap();
+        assert!(Config::load(&path).is_err(), "unknown matcher keys are rejected");
+    }
+
+    #[test]
+    fn test_diff_snapshots_reports_changed_added_and_removed_keys() {
+        let old = Config::default().snapshot();
+        assert!(diff_snapshots(&old, &Config::default().snapshot()).is_empty());
+
+        let mut config = Config::default();
+        config.ai.model = "opus".to_string();
+        config.knowledge.confidence_threshold = 0.8;
+        let mut new = config.snapshot();
+        new.remove("paths.claude_dir");
+        new.insert("future.key".to_string(), "on".to_string());
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            vec![
+                "ai.model: sonnet → opus".to_string(),
+                "future.key: added (on)".to_string(),
+                "knowledge.confidence_threshold: 0.7 → 0.8".to_string(),
+                "paths.claude_dir: removed (was ~/.claude)".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn test_retro_dir_default() {
         // SAFETY: single-threaded test, no concurrent env access
@@ -308,6 +661,49 @@ enabled = true
         }
     }
 
+    #[test]
+    fn home_lookups_follow_a_sandbox_env_home() {
+        let sandbox = tempfile::TempDir::new().unwrap();
+        let original = std::env::var_os("HOME");
+        // SAFETY: every home lookup goes through user::home, which honors a
+        // $HOME the user owns; restored below.
+        unsafe { std::env::set_var("HOME", sandbox.path()) };
+        let claude = expand_tilde("~/.claude");
+        let home = expand_tilde("~");
+        let shown = crate::util::shorten_path_buf(&sandbox.path().join("x"));
+        // SAFETY: restoring env to original state
+        unsafe {
+            match original {
+                Some(val) => std::env::set_var("HOME", val),
+                None => std::env::remove_var("HOME"),
+            }
+        }
+        assert_eq!(claude, sandbox.path().join(".claude"));
+        assert_eq!(home, sandbox.path());
+        assert_eq!(shown, "~/x");
+    }
+
+    #[test]
+    fn claude_dir_prefers_env_then_config_then_default() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        assert_eq!(Config::default().claude_dir_source(), ClaudeDirSource::Default);
+        std::fs::write(&path, "[paths]\nclaude_dir = \"/srv/claude\"\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.claude_dir(), PathBuf::from("/srv/claude"));
+        assert_eq!(config.claude_dir_source(), ClaudeDirSource::Config);
+
+        let synced = tmp.path().join("synced").display().to_string();
+        let config = config.with_env_claude_dir(Some(synced.clone()));
+        assert_eq!(config.claude_dir(), PathBuf::from(&synced));
+        assert_eq!(config.claude_dir_source(), ClaudeDirSource::Env);
+        assert_eq!(config.snapshot()["paths.claude_config_dir_env"], synced);
+        config.save(&path).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("synced"), "env override never persisted: {saved}");
+        assert!(saved.contains("/srv/claude"));
+    }
+
     #[test]
     fn ui_section_defaults_and_roundtrips() {
         let config = Config::default();
@@ -0,0 +1,317 @@
+//! Weekly digest: a Markdown summary of what retro learned over a window,
+//! grouped by scope. Pure rendering over already-loaded data so the format is
+//! testable without a store; `retro digest` gathers the inputs.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use chrono::NaiveDate;
+
+use crate::store::state::ConfigChange;
+use crate::store::{Node, Scope};
+
+/// Everything the digest renders. Callers load these; nothing here touches disk.
+pub struct DigestInput<'a> {
+    pub since: NaiveDate,
+    pub until: NaiveDate,
+    pub nodes: &'a [Node],
+    /// Store commit subjects within the window (newest first, as `git log` prints).
+    pub commit_subjects: &'a [String],
+    pub queued_sessions: usize,
+    pub health_warnings: &'a [String],
+    /// Recorded config changes (any age; those before `since` are skipped),
+    /// so behavior shifts in the window can be matched to a cause.
+    pub config_changes: &'a [ConfigChange],
+}
+
+#[derive(Default)]
+struct ScopeSection<'a> {
+    new: Vec<&'a Node>,
+    updated: Vec<&'a Node>,
+    invalidated: Vec<&'a Node>,
+}
+
+/// Render the digest. Sections with nothing to report are omitted; a quiet
+/// week still renders the header and summary so piping it somewhere never
+/// produces an empty message.
+pub fn render_markdown(input: &DigestInput) -> String {
+    let mut sections: BTreeMap<Option<&str>, ScopeSection> = BTreeMap::new();
+    for n in input.nodes {
+        if n.updated < input.since {
+            continue;
+        }
+        let key = match &n.scope {
+            Scope::Global => None,
+            Scope::Project(slug) => Some(slug.as_str()),
+        };
+        let section = sections.entry(key).or_default();
+        if !n.is_active() {
+            section.invalidated.push(n);
+        } else if n.created >= input.since {
+            section.new.push(n);
+        } else {
+            section.updated.push(n);
+        }
+    }
+    for section in sections.values_mut() {
+        for list in [
+            &mut section.new,
+            &mut section.updated,
+            &mut section.invalidated,
+        ] {
+            list.sort_by(|a, b| a.id.cmp(&b.id));
+        }
+    }
+
+    let count = |prefix: &str| {
+        input
+            .commit_subjects
+            .iter()
+            .filter(|s| s.starts_with(prefix))
+            .count()
+    };
+    let total = |f: fn(&ScopeSection) -> usize| sections.values().map(f).sum::<usize>();
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# retro digest: {} to {}\n", input.since, input.until);
+    let _ = writeln!(out, "## Summary\n");
+    let _ = writeln!(out, "- Learning runs: {}", count("retro: learn"));
+    let _ = writeln!(out, "- New nodes: {}", total(|s| s.new.len()));
+    let _ = writeln!(out, "- Updated nodes: {}", total(|s| s.updated.len()));
+    let _ = writeln!(
+        out,
+        "- Invalidated nodes: {}",
+        total(|s| s.invalidated.len())
+    );
+    let _ = writeln!(out, "- Manual edits: {}", count("user:"));
+
+    // BTreeMap orders None (global) before every project slug.
+    for (key, section) in &sections {
+        match key {
+            None => {
+                let _ = writeln!(out, "\n## Global");
+            }
+            Some(slug) => {
+                let _ = writeln!(out, "\n## Project: {slug}");
+            }
+        }
+        for (title, list) in [
+            ("New", &section.new),
+            ("Updated", &section.updated),
+            ("Invalidated", &section.invalidated),
+        ] {
+            if list.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n### {title}\n");
+            for n in list.iter() {
+                let _ = writeln!(
+                    out,
+                    "- **{}** ({}, {:.2}): {}",
+                    n.id,
+                    n.node_type.as_str(),
+                    n.confidence,
+                    first_line(&n.body)
+                );
+            }
+        }
+    }
+
+    let changes: Vec<&ConfigChange> = input
+        .config_changes
+        .iter()
+        .filter(|c| change_date(c).is_some_and(|d| d >= input.since))
+        .collect();
+    if !changes.is_empty() {
+        let _ = writeln!(out, "\n## Config changes\n");
+        for c in changes {
+            if let Some(d) = change_date(c) {
+                let _ = writeln!(out, "- {d}: {}", c.changes.join("; "));
+            }
+        }
+    }
+
+    if input.queued_sessions > 0 || !input.health_warnings.is_empty() {
+        let _ = writeln!(out, "\n## Needs attention\n");
+        if input.queued_sessions > 0 {
+            let _ = writeln!(
+                out,
+                "- {} session(s) waiting in the queue",
+                input.queued_sessions
+            );
+        }
+        for w in input.health_warnings {
+            let _ = writeln!(out, "- {w}");
+        }
+    }
+    out
+}
+
+fn change_date(c: &ConfigChange) -> Option<NaiveDate> {
+    chrono::DateTime::parse_from_rfc3339(&c.at)
+        .ok()
+        .map(|dt| dt.date_naive())
+}
+
+fn first_line(body: &str) -> String {
+    let line = body
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    let short = crate::util::truncate_str(line, 120);
+    if short.len() < line.len() {
+        format!("{short}…")
+    } else {
+        short.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::NodeType;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn node(id: &str, scope: Scope, created: &str, updated: &str, body: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            scope,
+            node_type: NodeType::Rule,
+            confidence: 0.8,
+            sources: vec![],
+            created: date(created),
+            updated: date(updated),
+            invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_sections_per_scope() {
+        let mut vetoed = node(
+            "old-rule",
+            Scope::Global,
+            "2026-01-01",
+            "2026-03-04",
+            "Old.",
+        );
+        vetoed.invalidated_by = Some("user".to_string());
+        let nodes = vec![
+            node(
+                "use-uv",
+                Scope::Global,
+                "2026-03-03",
+                "2026-03-03",
+                "Use uv, not pip.\nMore.",
+            ),
+            node(
+                "run-tests",
+                Scope::Project("app".into()),
+                "2026-02-01",
+                "2026-03-05",
+                "Run cargo test.",
+            ),
+            node(
+                "untouched",
+                Scope::Global,
+                "2026-01-01",
+                "2026-01-02",
+                "Outside the window.",
+            ),
+            vetoed,
+        ];
+        let subjects = vec![
+            "retro: learn 1 node(s), update 1".to_string(),
+            "user: edit knowledge".to_string(),
+            "retro: maintenance".to_string(),
+        ];
+        let warnings = vec!["retro push failed at 2026-03-05 10:00 UTC: no network".to_string()];
+        let config_changes = vec![
+            ConfigChange {
+                at: "2026-02-20T09:00:00Z".to_string(),
+                changes: vec!["ai.model: haiku → sonnet".to_string()],
+            },
+            ConfigChange {
+                at: "2026-03-02T09:00:00Z".to_string(),
+                changes: vec!["knowledge.confidence_threshold: 0.7 → 0.8".to_string()],
+            },
+        ];
+        let out = render_markdown(&DigestInput {
+            since: date("2026-03-01"),
+            until: date("2026-03-08"),
+            nodes: &nodes,
+            commit_subjects: &subjects,
+            queued_sessions: 2,
+            health_warnings: &warnings,
+            config_changes: &config_changes,
+        });
+        let expected = "\
+# retro digest: 2026-03-01 to 2026-03-08
+
+## Summary
+
+- Learning runs: 1
+- New nodes: 1
+- Updated nodes: 1
+- Invalidated nodes: 1
+- Manual edits: 1
+
+## Global
+
+### New
+
+- **use-uv** (rule, 0.80): Use uv, not pip.
+
+### Invalidated
+
+- **old-rule** (rule, 0.80): Old.
+
+## Project: app
+
+### Updated
+
+- **run-tests** (rule, 0.80): Run cargo test.
+
+## Config changes
+
+- 2026-03-02: knowledge.confidence_threshold: 0.7 → 0.8
+
+## Needs attention
+
+- 2 session(s) waiting in the queue
+- retro push failed at 2026-03-05 10:00 UTC: no network
+";
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn quiet_week_still_has_summary() {
+        let out = render_markdown(&DigestInput {
+            since: date("2026-03-01"),
+            until: date("2026-03-08"),
+            nodes: &[],
+            commit_subjects: &[],
+            queued_sessions: 0,
+            health_warnings: &[],
+            config_changes: &[],
+        });
+        assert!(out.contains("- New nodes: 0"), "got: {out}");
+        assert!(!out.contains("## Needs attention"));
+        assert!(!out.contains("## Global"));
+    }
+
+    #[test]
+    fn long_bodies_are_truncated_to_one_line() {
+        let long = "x".repeat(300);
+        assert_eq!(
+            first_line(&format!("\n\n{long}\nsecond")).chars().count(),
+            121
+        );
+    }
+}
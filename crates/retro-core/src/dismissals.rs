@@ -0,0 +1,298 @@
+//! What the user throws away. A node the user invalidates (dashboard veto,
+//! `invalidated_by = "user"`) is a dismissed suggestion; aggregated over a
+//! window by type, scope, confidence and project, dismissals show which kinds
+//! of output the analyzer should produce less of. Pure functions over loaded
+//! nodes; `retro stats --dismissals` gathers them.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::store::{Node, Scope};
+
+/// Below this many dismissals, shares are noise: no hints.
+pub const MIN_SAMPLE: usize = 5;
+
+/// Share of dismissals at which one category is called out.
+const DOMINANT_SHARE: f64 = 0.6;
+
+/// Dismissed share of everything learned in the window worth a hint.
+const HIGH_DISMISSAL_RATE: f64 = 0.3;
+
+/// `invalidated_by` value the dashboard writes for a user veto.
+pub const USER: &str = "user";
+
+/// Confidence buckets, lowest first: `(label, exclusive upper bound)`.
+const BUCKETS: [(&str, f64); 4] = [
+    ("< 0.5", 0.5),
+    ("0.5–0.7", 0.7),
+    ("0.7–0.85", 0.85),
+    (">= 0.85", f64::INFINITY),
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DismissalStats {
+    pub dismissed: usize,
+    /// Nodes created in the window and still active (the denominator for the
+    /// dismissal rate, with `dismissed`).
+    pub kept: usize,
+    pub by_type: BTreeMap<String, usize>,
+    /// `global` or `project`.
+    pub by_scope: BTreeMap<String, usize>,
+    /// Bucket label -> count, every bucket present (zeros included); iterate
+    /// [`confidence_buckets`] for display order.
+    pub by_confidence: BTreeMap<String, usize>,
+    /// Project slug -> count (global nodes not included).
+    pub by_project: BTreeMap<String, usize>,
+    /// Dismissed nodes backed by at most one session.
+    pub single_session: usize,
+    /// Dismissed nodes at or above the projection threshold: they reached
+    /// CLAUDE.md before the user removed them.
+    pub projected: usize,
+    /// Reason given at dismiss time -> count (dismissals without one not
+    /// included).
+    pub by_reason: BTreeMap<String, usize>,
+}
+
+/// Bucket labels, lowest confidence first.
+pub fn confidence_buckets() -> impl Iterator<Item = &'static str> {
+    BUCKETS.iter().map(|(label, _)| *label)
+}
+
+fn bucket(confidence: f64) -> &'static str {
+    BUCKETS
+        .iter()
+        .find(|(_, upper)| confidence < *upper)
+        .map_or(BUCKETS[BUCKETS.len() - 1].0, |(label, _)| *label)
+}
+
+fn sessions_backing(node: &Node) -> usize {
+    node.sources.iter().filter(|s| s.starts_with("session:")).count()
+}
+
+/// Aggregate user dismissals dated on or after `since` (a dismissal stamps
+/// the node's `updated`). `threshold` is `[knowledge] confidence_threshold`.
+pub fn aggregate(nodes: &[Node], since: NaiveDate, threshold: f64) -> DismissalStats {
+    let mut s = DismissalStats {
+        by_confidence: confidence_buckets().map(|b| (b.to_string(), 0)).collect(),
+        ..Default::default()
+    };
+    for node in nodes {
+        if node.is_active() {
+            if node.created >= since {
+                s.kept += 1;
+            }
+            continue;
+        }
+        if node.invalidated_by.as_deref() != Some(USER) || node.updated < since {
+            continue;
+        }
+        s.dismissed += 1;
+        *s.by_type.entry(node.node_type.as_str().to_string()).or_default() += 1;
+        let scope = match &node.scope {
+            Scope::Global => "global",
+            Scope::Project(slug) => {
+                *s.by_project.entry(slug.clone()).or_default() += 1;
+                "project"
+            }
+        };
+        *s.by_scope.entry(scope.to_string()).or_default() += 1;
+        *s.by_confidence.entry(bucket(node.confidence).to_string()).or_default() += 1;
+        if sessions_backing(node) <= 1 {
+            s.single_session += 1;
+        }
+        if node.confidence >= threshold {
+            s.projected += 1;
+        }
+        if let Some(reason) = &node.dismissal_reason {
+            *s.by_reason.entry(reason.clone()).or_default() += 1;
+        }
+    }
+    s
+}
+
+fn share(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
+
+fn pct(x: f64) -> u32 {
+    (x * 100.0).round() as u32
+}
+
+/// Most dismissals rest on one session's evidence.
+pub fn hint_single_session(s: &DismissalStats) -> Option<String> {
+    let x = share(s.single_session, s.dismissed);
+    (s.dismissed >= MIN_SAMPLE && x >= DOMINANT_SHARE).then(|| {
+        format!(
+            "{}% of dismissed nodes came from a single session — raise [knowledge] \
+             confidence_threshold so one-off observations wait for a second sighting",
+            pct(x)
+        )
+    })
+}
+
+/// One node type dominates the dismissals.
+pub fn hint_dominant_type(s: &DismissalStats) -> Option<String> {
+    if s.dismissed < MIN_SAMPLE {
+        return None;
+    }
+    let (kind, n) = s.by_type.iter().max_by_key(|(_, n)| **n)?;
+    let x = share(*n, s.dismissed);
+    (x >= DOMINANT_SHARE).then(|| {
+        format!("{}% of dismissals are {kind} nodes — the analyzer over-produces them", pct(x))
+    })
+}
+
+/// Dismissed nodes had already been projected.
+pub fn hint_projected(s: &DismissalStats, threshold: f64) -> Option<String> {
+    let x = share(s.projected, s.dismissed);
+    (s.dismissed >= MIN_SAMPLE && x >= DOMINANT_SHARE).then(|| {
+        format!(
+            "{}% of dismissed nodes were at or above the projection threshold ({threshold}) — \
+             they reached CLAUDE.md first; consider raising confidence_threshold",
+            pct(x)
+        )
+    })
+}
+
+/// One project produces most dismissals.
+pub fn hint_project(s: &DismissalStats) -> Option<String> {
+    if s.dismissed < MIN_SAMPLE {
+        return None;
+    }
+    let (slug, n) = s.by_project.iter().max_by_key(|(_, n)| **n)?;
+    let x = share(*n, s.dismissed);
+    (x >= DOMINANT_SHARE).then(|| {
+        format!(
+            "{}% of dismissals come from `{slug}` — if its sessions aren't representative, \
+             add an [[ingest.ignore_session_if]] rule or privacy.exclude_projects entry",
+            pct(x)
+        )
+    })
+}
+
+/// Dismissals are a large share of the window's output.
+pub fn hint_dismissal_rate(s: &DismissalStats) -> Option<String> {
+    let x = share(s.dismissed, s.dismissed + s.kept);
+    (s.dismissed >= MIN_SAMPLE && x >= HIGH_DISMISSAL_RATE).then(|| {
+        format!(
+            "you dismissed {} node(s) and kept {} new one(s) in this window ({}% dismissed) \
+             — much of what retro proposes isn't landing",
+            s.dismissed,
+            s.kept,
+            pct(x)
+        )
+    })
+}
+
+/// Every hint that applies, broadest first.
+pub fn hints(s: &DismissalStats, threshold: f64) -> Vec<String> {
+    [
+        hint_dismissal_rate(s),
+        hint_single_session(s),
+        hint_projected(s, threshold),
+        hint_dominant_type(s),
+        hint_project(s),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::NodeType;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 7, day).unwrap()
+    }
+
+    fn node(id: &str, scope: Scope, confidence: f64, sessions: usize, day: u32) -> Node {
+        Node {
+            id: id.to_string(),
+            scope,
+            node_type: NodeType::Rule,
+            confidence,
+            sources: (0..sessions).map(|i| format!("session:s{i}")).collect(),
+            created: date(1),
+            updated: date(day),
+            invalidated_by: Some(USER.to_string()),
+            dismissal_reason: None,
+            section: None,
+            body: format!("{id} body"),
+        }
+    }
+
+    fn project(slug: &str) -> Scope {
+        Scope::Project(slug.to_string())
+    }
+
+    #[test]
+    fn aggregates_user_dismissals_in_the_window() {
+        let mut superseded = node("old", Scope::Global, 0.9, 3, 20);
+        superseded.invalidated_by = Some("new-rule".to_string());
+        let mut kept = node("kept", Scope::Global, 0.8, 2, 20);
+        kept.invalidated_by = None;
+        kept.created = date(15);
+        let mut pattern = node("p", project("app"), 0.4, 1, 20);
+        pattern.node_type = NodeType::Pattern;
+        pattern.dismissal_reason = Some("too vague".to_string());
+        let nodes = vec![
+            node("a", project("app"), 0.9, 1, 20),
+            node("b", Scope::Global, 0.75, 2, 12),
+            node("too-old", Scope::Global, 0.6, 1, 2),
+            pattern,
+            superseded,
+            kept,
+        ];
+        let s = aggregate(&nodes, date(10), 0.7);
+        assert_eq!(s.dismissed, 3, "supersession and out-of-window vetoes excluded");
+        assert_eq!(s.kept, 1);
+        assert_eq!(s.by_type["rule"], 2);
+        assert_eq!(s.by_type["pattern"], 1);
+        assert_eq!((s.by_scope["global"], s.by_scope["project"]), (1, 2));
+        assert_eq!(s.by_project["app"], 2);
+        assert_eq!(s.by_confidence["< 0.5"], 1);
+        assert_eq!(s.by_confidence["0.5–0.7"], 0, "empty buckets still listed");
+        assert_eq!(s.by_confidence["0.7–0.85"], 1);
+        assert_eq!(s.by_confidence[">= 0.85"], 1);
+        assert_eq!(s.single_session, 2);
+        assert_eq!(s.projected, 2);
+        assert_eq!(s.by_reason, [("too vague".to_string(), 1)].into());
+    }
+
+    #[test]
+    fn each_hint_fires_on_its_own_threshold() {
+        let base = DismissalStats {
+            dismissed: 10,
+            kept: 40,
+            by_type: [("rule".to_string(), 5), ("pattern".to_string(), 5)].into(),
+            by_project: [("a".to_string(), 3), ("b".to_string(), 3)].into(),
+            single_session: 5,
+            projected: 5,
+            ..Default::default()
+        };
+        assert!(hints(&base, 0.7).is_empty(), "{:?}", hints(&base, 0.7));
+
+        let single = DismissalStats { single_session: 8, ..base.clone() };
+        assert!(hint_single_session(&single).unwrap().starts_with("80% of dismissed"));
+        let typed = DismissalStats {
+            by_type: [("rule".to_string(), 9), ("pattern".to_string(), 1)].into(),
+            ..base.clone()
+        };
+        assert!(hint_dominant_type(&typed).unwrap().contains("90% of dismissals are rule"));
+        let projected = DismissalStats { projected: 7, ..base.clone() };
+        assert!(hint_projected(&projected, 0.7).unwrap().contains("threshold (0.7)"));
+        let one_project = DismissalStats {
+            by_project: [("noisy".to_string(), 6)].into(),
+            ..base.clone()
+        };
+        assert!(hint_project(&one_project).unwrap().contains("`noisy`"));
+        let rate = DismissalStats { kept: 10, ..base.clone() };
+        assert!(hint_dismissal_rate(&rate).unwrap().contains("(50% dismissed)"));
+
+        let tiny = DismissalStats { dismissed: MIN_SAMPLE - 1, single_session: 4, ..base };
+        assert!(hints(&tiny, 0.7).is_empty(), "too few dismissals to generalize");
+    }
+}
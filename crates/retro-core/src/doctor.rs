@@ -59,8 +59,37 @@ mod tests {
         assert!(by_name("store-present").ok);
         assert!(by_name("store-repo").ok);
         assert!(by_name("index").ok);
+        assert!(by_name("sources").ok);
+        assert!(by_name("tool-log").ok);
         assert!(by_name("hooks").ok);
         assert!(by_name("queue").ok);
+        assert!(by_name("ai-model").ok, "unverified model is not a failure");
+    }
+
+    #[test]
+    fn cached_unknown_model_is_reported() {
+        let tmp = TempDir::new().unwrap();
+        let claude = TempDir::new().unwrap();
+        Store::open(tmp.path()).ensure_layout().unwrap();
+        let mut config = config_for(claude.path());
+        config.ai.model = "claude-sonet-4".to_string();
+        crate::analysis::claude_cli::ModelCheck {
+            model: "claude-sonet-4".to_string(),
+            date: "2026-03-01".to_string(),
+            ok: false,
+            detail: "unknown model 'claude-sonet-4'".to_string(),
+            inconclusive: false,
+            at: "2026-03-01T09:00:00Z".to_string(),
+        }
+        .save(tmp.path());
+        let report = run_checks_for_tests(tmp.path(), &config);
+        let check = report.checks.iter().find(|c| c.name == "ai-model").unwrap();
+        assert!(!check.ok);
+        assert!(check.detail.contains("claude-sonet-4"));
+        // a different (fixed) model ignores the stale failure
+        config.ai.model = "sonnet".to_string();
+        let report = run_checks_for_tests(tmp.path(), &config);
+        assert!(report.checks.iter().find(|c| c.name == "ai-model").unwrap().ok);
     }
 
     #[test]
@@ -70,8 +99,18 @@ mod tests {
         let store = Store::open(tmp.path());
         store.ensure_layout().unwrap();
         // no repo, no index, no hooks, stale index after node write
+        std::fs::write(
+            store.knowledge_dir().join("global/use-uv.md"),
+            "---\nid: use-uv\nsources: session:a\n---\nUse uv.\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(tmp.path().join("state")).unwrap();
+        std::fs::write(tmp.path().join("state/pattern_tools.json"), r#"{"global/gone":{}}"#)
+            .unwrap();
         let report = run_checks_for_tests(tmp.path(), &config_for(claude.path()));
         let by_name = |n: &str| report.checks.iter().find(|c| c.name == n).unwrap();
+        assert!(by_name("sources").detail.contains("1 node file(s)"));
+        assert!(by_name("tool-log").detail.contains("1 entr(ies) for deleted nodes (global/gone)"));
         assert!(!by_name("store-repo").ok);
         assert!(!by_name("index").ok);
         assert!(!by_name("hooks").ok);
@@ -161,6 +200,40 @@ fn run_checks_inner(
         detail: index_check.1,
     });
 
+    // Hand-edited `sources:` lines (skipped nodes, unrecognized evidence)
+    let sources_check = match crate::store::repair::scan(&store) {
+        Ok(found) if found.is_empty() => (true, "well-formed".to_string()),
+        Ok(found) => (
+            false,
+            format!(
+                "{} node file(s) with a malformed sources line — run `retro doctor --repair-sources`",
+                found.len()
+            ),
+        ),
+        Err(e) => (false, format!("scan failed: {e}")),
+    };
+    checks.push(Check {
+        name: "sources".to_string(),
+        ok: sources_check.0,
+        detail: sources_check.1,
+    });
+
+    // Tool-log entries for patterns whose node file is gone
+    let orphans = crate::store::tools::orphans(&crate::store::tools::load(store_root), &store);
+    checks.push(Check {
+        name: "tool-log".to_string(),
+        ok: orphans.is_empty(),
+        detail: if orphans.is_empty() {
+            "every entry has its node".to_string()
+        } else {
+            format!(
+                "{} entr(ies) for deleted nodes ({}) — run `retro reindex` to drop them",
+                orphans.len(),
+                orphans.iter().take(3).cloned().collect::<Vec<_>>().join(", ")
+            )
+        },
+    });
+
     // Hooks installed (global settings.json contains retro observe + brief)
     let settings_path = config.claude_dir().join("settings.json");
     let hooks_ok = std::fs::read_to_string(&settings_path)
@@ -204,7 +277,10 @@ fn run_checks_inner(
         Ok(entries) if entries.is_empty() => (true, "empty".to_string()),
         Ok(entries) => {
             let oldest_stale = chrono::DateTime::parse_from_rfc3339(&entries[0].enqueued_at)
-                .map(|t| chrono::Utc::now().signed_duration_since(t) > chrono::Duration::hours(24))
+                .map(|t| {
+                    let t = t.with_timezone(&chrono::Utc);
+                    crate::util::elapsed(t, chrono::Utc::now()) > chrono::Duration::hours(24)
+                })
                 .unwrap_or(false);
             if oldest_stale {
                 (
@@ -282,7 +358,8 @@ fn run_checks_inner(
 
     // v2 runner coexistence (Plan 2 final-review carry-over): both pipelines
     // being live doubles AI spend and double-writes the global managed block.
-    let v2_plist = std::path::PathBuf::from(std::env::var("HOME").unwrap_or_default())
+    let v2_plist = crate::user::home()
+        .path
         .join("Library/LaunchAgents/com.retro.runner.plist");
     if probe_env && v2_plist.exists() {
         checks.push(Check {
@@ -304,6 +381,21 @@ fn run_checks_inner(
         },
     });
 
+    // Configured model: read the cached validation from the last run (no
+    // probe here — validating a model costs a ping, doctor stays free).
+    let model = &config.ai.model;
+    let (ok, detail) = match crate::analysis::claude_cli::ModelCheck::load(store_root) {
+        Some(c) if c.model == *model && !c.ok => (false, c.detail),
+        Some(c) if c.model == *model && c.inconclusive => (true, format!("`{model}` {}", c.detail)),
+        Some(c) if c.model == *model => (true, format!("`{model}` verified {}", c.date)),
+        _ => (true, format!("`{model}` not verified yet (checked on the next run)")),
+    };
+    checks.push(Check {
+        name: "ai-model".to_string(),
+        ok,
+        detail,
+    });
+
     // claude CLI probe (optional)
     if probe_claude {
         let probe = std::process::Command::new("claude")
@@ -46,7 +46,10 @@ impl Health {
         self.stages
             .iter()
             .filter(|(_, s)| !s.ok)
-            .map(|(name, s)| format!("retro {name} failed at {}: {}", s.at, s.detail))
+            .map(|(name, s)| {
+                let at = crate::util::fmt_rfc3339(&s.at, false);
+                format!("retro {name} failed at {at}: {}", s.detail)
+            })
             .collect()
     }
 }
@@ -0,0 +1,140 @@
+//! Correction detection: flags user prompts that push back on the previous
+//! assistant turn ("no, use X instead", "you forgot the tests"). These are
+//! the strongest recurring-mistake evidence in a transcript, so the parser
+//! marks them and the analysis prompt surfaces them explicitly.
+//!
+//! Keyword/regex only — no AI. Deliberately conservative: questions, quoted
+//! text and code blocks never count, and ambiguous openers ("no need",
+//! "no worries") are not cues.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+struct Patterns {
+    /// Cues that only count at the start of a sentence ("No, …", "Undo that").
+    leading: Regex,
+    /// Cues that count anywhere in a sentence.
+    anywhere: Regex,
+    fenced_code: Regex,
+    inline_quoted: Regex,
+}
+
+static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+
+fn patterns() -> &'static Patterns {
+    PATTERNS.get_or_init(|| Patterns {
+        leading: Regex::new(r"(?i)^(?:no[,.!:]|nope\b|wrong\b|undo\b|revert (?:that|this|it)\b)")
+            .unwrap(),
+        anywhere: Regex::new(concat!(
+            r"(?i)\b(?:",
+            r"that'?s (?:wrong|not right|incorrect|not what i (?:asked|wanted|said))",
+            r"|that is (?:wrong|not right|incorrect|not what i (?:asked|wanted|said))",
+            r"|you forgot",
+            r"|again,? you",
+            r"|you (?:did it|did that|broke it|made the same mistake) again",
+            r"|i (?:already )?(?:said|told you)",
+            r"|(?:undo|revert) (?:that|this|your|those)",
+            r"|i didn'?t ask (?:you )?(?:for|to)",
+            r")\b"
+        ))
+        .unwrap(),
+        fenced_code: Regex::new(r"(?s)```.*?(```|$)").unwrap(),
+        inline_quoted: Regex::new(r#"`[^`]*`|"[^"]*"|“[^”]*”"#).unwrap(),
+    })
+}
+
+/// True if `text` reads as a correction of the previous assistant turn.
+/// Callers are responsible for only asking about prompts that actually
+/// follow an assistant message.
+pub fn is_correction(text: &str) -> bool {
+    let p = patterns();
+    let without_code = p.fenced_code.replace_all(text, "\n");
+    for line in without_code.lines() {
+        let line = line.trim();
+        // Pasted quotes ("> no, that's wrong" from a review thread).
+        if line.starts_with('>') {
+            continue;
+        }
+        let line = p.inline_quoted.replace_all(line, "…");
+        for sentence in sentences(&line) {
+            if sentence.ends_with('?') {
+                continue;
+            }
+            if p.leading.is_match(sentence) || p.anywhere.is_match(sentence) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Sentence split that keeps the terminator, so questions stay recognizable.
+/// `.` only ends a sentence before whitespace (keeps `main.rs` intact).
+fn sentences(line: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let ends = match ch {
+            '!' | '?' | ';' => true,
+            '.' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends {
+            let end = i + ch.len_utf8();
+            out.push(line[start..end].trim());
+            start = end;
+        }
+    }
+    out.push(line[start..].trim());
+    out.retain(|s| !s.is_empty());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Labeled examples: (prompt, is a correction).
+    const LABELED: &[(&str, bool)] = &[
+        ("No, use the existing helper instead.", true),
+        ("no. revert to the old signature", true),
+        ("Nope, still failing", true),
+        ("That's wrong — the config lives in ~/.retro", true),
+        ("that is not what I asked for", true),
+        ("Undo that change to main.rs", true),
+        ("Please revert that, it broke the build", true),
+        ("You forgot to update the README.", true),
+        ("Again you used unwrap in library code", true),
+        ("you did it again, tabs instead of spaces", true),
+        ("I told you to use uv, not pip", true),
+        ("I didn't ask for a refactor", true),
+        ("Looks good. But you forgot the tests", true),
+        // questions
+        ("No, why did you change that?", false),
+        ("Is that wrong?", false),
+        ("Did you forget the tests?", false),
+        // quoted / pasted text
+        (
+            "> no, that's wrong\nthe reviewer said this, what do you think",
+            false,
+        ),
+        ("the error says \"that's wrong\" somewhere", false),
+        ("```\n// no, that's wrong\n```\nrun this", false),
+        // ordinary prompts and ambiguous openers
+        ("No need to add tests for this one", false),
+        ("no worries, carry on", false),
+        ("Add a --utc flag to the status command", false),
+        ("Great, now wire it into the runner", false),
+        ("notice the bug in parse_config", false),
+        ("Nobody uses that module anymore", false),
+    ];
+
+    #[test]
+    fn labeled_set() {
+        for (text, expected) in LABELED {
+            assert_eq!(is_correction(text), *expected, "{text:?}");
+        }
+    }
+}
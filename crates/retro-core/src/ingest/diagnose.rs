@@ -0,0 +1,149 @@
+//! Why a run found nothing to do for the current project. "0 sessions
+//! analyzed" is true but unhelpful when the real cause is that Claude Code
+//! has never been used here, or that every session was already analyzed.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::store::state::RunnerState;
+
+/// What Claude Code has on disk for one project, relative to retro's state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSessions {
+    /// `<claude_dir>/projects/<encoded project path>`.
+    pub sessions_dir: PathBuf,
+    pub dir_exists: bool,
+    /// Top-level transcripts (subagent transcripts are parts of sessions).
+    pub sessions: usize,
+    /// Of those, already analyzed (`RunnerState::processed`).
+    pub processed: usize,
+    /// Unix seconds of the newest transcript's mtime.
+    pub newest_unix: Option<u64>,
+    /// Unix seconds of retro's capture watermark (0 before the first capture).
+    pub watermark_unix: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diagnosis {
+    /// No sessions directory: Claude Code was never used at this path.
+    NoSessionsDir,
+    /// The directory exists but holds no transcripts.
+    NoSessions,
+    /// Every transcript was already analyzed.
+    AllProcessed,
+    /// Some transcripts were never captured and predate the watermark
+    /// (sessions from before retro was installed); catch-up won't reach them.
+    BeforeWatermark,
+    /// Unanalyzed sessions exist after the watermark; the next capture or
+    /// catch-up enqueues them.
+    Pending,
+}
+
+impl ProjectSessions {
+    pub fn diagnosis(&self) -> Diagnosis {
+        if !self.dir_exists {
+            Diagnosis::NoSessionsDir
+        } else if self.sessions == 0 {
+            Diagnosis::NoSessions
+        } else if self.processed == self.sessions {
+            Diagnosis::AllProcessed
+        } else if self.newest_unix.is_some_and(|n| n <= self.watermark_unix) {
+            Diagnosis::BeforeWatermark
+        } else {
+            Diagnosis::Pending
+        }
+    }
+}
+
+/// Inspect Claude Code's session directory for `project_path`.
+pub fn project_sessions(
+    claude_dir: &Path,
+    project_path: &str,
+    state: &RunnerState,
+) -> ProjectSessions {
+    let sessions_dir = claude_dir
+        .join("projects")
+        .join(super::encode_project_path(project_path.trim_end_matches('/')));
+    let mut out = ProjectSessions {
+        dir_exists: sessions_dir.is_dir(),
+        sessions_dir,
+        sessions: 0,
+        processed: 0,
+        newest_unix: None,
+        watermark_unix: state.last_observed_unix,
+    };
+    let Ok(entries) = std::fs::read_dir(&out.sessions_dir) else {
+        return out;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        out.sessions += 1;
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        if state.processed.contains_key(stem) {
+            out.processed += 1;
+        }
+        let mtime = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        if let Some(m) = mtime {
+            out.newest_unix = Some(out.newest_unix.map_or(m, |n| n.max(m)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup(sessions: &[&str]) -> (TempDir, String) {
+        let claude = TempDir::new().unwrap();
+        let project = "/work/my-app".to_string();
+        if !sessions.is_empty() {
+            let dir = claude.path().join("projects/-work-my-app");
+            std::fs::create_dir_all(dir.join("s1/subagents")).unwrap();
+            std::fs::write(dir.join("s1/subagents/agent-1.jsonl"), "{}\n").unwrap();
+            for s in sessions {
+                std::fs::write(dir.join(format!("{s}.jsonl")), "{}\n").unwrap();
+            }
+        }
+        (claude, project)
+    }
+
+    #[test]
+    fn missing_sessions_dir() {
+        let (claude, project) = setup(&[]);
+        let found = project_sessions(claude.path(), &project, &RunnerState::default());
+        assert_eq!(found.diagnosis(), Diagnosis::NoSessionsDir);
+        assert!(found.sessions_dir.ends_with("projects/-work-my-app"));
+
+        std::fs::create_dir_all(&found.sessions_dir).unwrap();
+        let found = project_sessions(claude.path(), &project, &RunnerState::default());
+        assert_eq!(found.diagnosis(), Diagnosis::NoSessions);
+    }
+
+    #[test]
+    fn all_processed_before_watermark_and_pending() {
+        let (claude, project) = setup(&["s1", "s2"]);
+        let mut state = RunnerState::default();
+        let found = project_sessions(claude.path(), &project, &state);
+        assert_eq!((found.sessions, found.processed), (2, 0), "subagents not counted");
+        assert_eq!(found.diagnosis(), Diagnosis::Pending);
+
+        state.last_observed_unix = found.newest_unix.unwrap() + 60;
+        let found = project_sessions(claude.path(), &project, &state);
+        assert_eq!(found.diagnosis(), Diagnosis::BeforeWatermark);
+
+        state.record_processed("s1", 0, None);
+        state.record_processed("s2", 0, None);
+        let found = project_sessions(claude.path(), &project, &state);
+        assert_eq!(found.diagnosis(), Diagnosis::AllProcessed);
+    }
+}
@@ -0,0 +1,345 @@
+//! Backfill from Claude Code's flat prompt history (`<claude_dir>/history.jsonl`).
+//! Transcripts under `projects/<encoded>/` get cleaned up; the history file
+//! keeps every prompt. Prompts whose transcript is gone are kept as
+//! history-only records in `state/history.json` (machine-local, like the
+//! queue) and, with `[ingest] include_history` / `retro run
+//! --include-history`, analyzed per project as one pseudo-session of user
+//! prompts — a weak signal: no assistant turns, no tool use, no outcomes.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::CoreError;
+use crate::models::{ParsedUserMessage, Session, SessionMetadata};
+
+/// Pseudo-session ids start with this; never a queue entry or transcript.
+pub const PSEUDO_SESSION_PREFIX: &str = "history-";
+
+/// Prompts per pseudo-session (newest kept); older ones wait for a later run.
+const MAX_PROMPTS_PER_SESSION: usize = 50;
+
+/// One line of `history.jsonl`. Older Claude Code versions omit `sessionId`.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    #[serde(default)]
+    pub display: String,
+    /// Unix milliseconds.
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default, rename = "sessionId")]
+    pub session_id: Option<String>,
+}
+
+/// A history-only prompt: its transcript no longer exists.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryRecord {
+    pub project: String,
+    pub display: String,
+    /// Unix milliseconds.
+    pub timestamp: Option<i64>,
+    /// Included in an analyzed pseudo-session (or dropped as too little signal).
+    #[serde(default)]
+    pub analyzed: bool,
+}
+
+/// Parse `history.jsonl`. Unparseable lines, empty prompts and entries
+/// without a project are skipped; a missing file yields nothing.
+pub fn parse_history(path: &Path) -> Vec<HistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|l| serde_json::from_str::<HistoryEntry>(l).ok())
+        .filter(|e| !e.display.trim().is_empty())
+        .filter(|e| e.project.as_deref().is_some_and(|p| !p.is_empty()))
+        .collect()
+}
+
+/// Entries grouped by project path, each group in file order.
+pub fn group_by_project(entries: &[HistoryEntry]) -> BTreeMap<String, Vec<&HistoryEntry>> {
+    let mut groups: BTreeMap<String, Vec<&HistoryEntry>> = BTreeMap::new();
+    for entry in entries {
+        if let Some(project) = &entry.project {
+            groups.entry(project.clone()).or_default().push(entry);
+        }
+    }
+    groups
+}
+
+/// Entries whose transcript is gone: the session's own file when the entry
+/// names one, else the project's whole sessions directory.
+pub fn history_only(claude_dir: &Path, entries: &[HistoryEntry]) -> Vec<HistoryEntry> {
+    let mut out = Vec::new();
+    for (project, group) in group_by_project(entries) {
+        let dir = sessions_dir(claude_dir, &project);
+        let dir_exists = dir.is_dir();
+        for entry in group {
+            let gone = match &entry.session_id {
+                Some(id) => !dir.join(format!("{id}.jsonl")).exists(),
+                None => !dir_exists,
+            };
+            if gone {
+                out.push(entry.clone());
+            }
+        }
+    }
+    out
+}
+
+fn sessions_dir(claude_dir: &Path, project: &str) -> PathBuf {
+    claude_dir
+        .join("projects")
+        .join(super::encode_project_path(project.trim_end_matches('/')))
+}
+
+fn records_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("history.json")
+}
+
+/// Missing or corrupt file loads empty.
+pub fn load(store_root: &Path) -> Vec<HistoryRecord> {
+    std::fs::read_to_string(records_path(store_root))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(store_root: &Path, records: &[HistoryRecord]) -> Result<(), CoreError> {
+    let path = records_path(store_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CoreError::Io(e.to_string()))?;
+    }
+    let json =
+        serde_json::to_string_pretty(records).map_err(|e| CoreError::Parse(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| CoreError::Io(e.to_string()))
+}
+
+/// Add history-only prompts not yet recorded (same project, prompt and
+/// timestamp). Returns how many were added.
+pub fn merge_new(records: &mut Vec<HistoryRecord>, history_only: &[HistoryEntry]) -> usize {
+    let mut added = 0;
+    for entry in history_only {
+        let project = entry.project.clone().unwrap_or_default();
+        let known = records.iter().any(|r| {
+            r.project == project && r.timestamp == entry.timestamp && r.display == entry.display
+        });
+        if !known {
+            records.push(HistoryRecord {
+                project,
+                display: entry.display.clone(),
+                timestamp: entry.timestamp,
+                analyzed: false,
+            });
+            added += 1;
+        }
+    }
+    added
+}
+
+/// Re-point records of a moved project from `old` to `new`. Returns how
+/// many records moved.
+pub fn relocate(
+    store_root: &Path,
+    old: &str,
+    new: &str,
+    dry_run: bool,
+) -> Result<usize, CoreError> {
+    let mut records = load(store_root);
+    let mut moved = 0;
+    for r in records.iter_mut() {
+        if let Some(rebased) = crate::store::projects::rebase_path(&r.project, old, new) {
+            r.project = rebased;
+            moved += 1;
+        }
+    }
+    if moved > 0 && !dry_run {
+        save(store_root, &records)?;
+    }
+    Ok(moved)
+}
+
+/// One pseudo-session per project from its unanalyzed records (newest
+/// [`MAX_PROMPTS_PER_SESSION`]), oldest prompt first.
+pub fn pending_sessions(records: &[HistoryRecord]) -> Vec<Session> {
+    let mut by_project: BTreeMap<&str, Vec<&HistoryRecord>> = BTreeMap::new();
+    for r in records.iter().filter(|r| !r.analyzed) {
+        by_project.entry(r.project.as_str()).or_default().push(r);
+    }
+    by_project
+        .into_iter()
+        .map(|(project, mut prompts)| {
+            prompts.sort_by_key(|r| r.timestamp);
+            let start = prompts.len().saturating_sub(MAX_PROMPTS_PER_SESSION);
+            Session {
+                session_id: pseudo_session_id(project),
+                project: project.to_string(),
+                session_path: String::new(),
+                user_messages: prompts[start..]
+                    .iter()
+                    .map(|r| ParsedUserMessage {
+                        text: r.display.clone(),
+                        timestamp: r
+                            .timestamp
+                            .and_then(chrono::DateTime::from_timestamp_millis)
+                            .map(|t| t.to_rfc3339()),
+                        is_correction: false,
+                    })
+                    .collect(),
+                assistant_messages: Vec::new(),
+                summaries: vec![
+                    "History-only prompts (transcripts no longer exist): user prompts \
+                     only, no responses or outcomes — weak evidence."
+                        .to_string(),
+                ],
+                tools_used: Vec::new(),
+                errors: Vec::new(),
+                metadata: SessionMetadata {
+                    cwd: Some(project.to_string()),
+                    version: None,
+                    git_branch: None,
+                    model: None,
+                    git_branches: Vec::new(),
+                },
+                unknown_entry_types: Default::default(),
+            }
+        })
+        .collect()
+}
+
+/// Stable, queue-safe pseudo-session id for a project path.
+pub fn pseudo_session_id(project: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in project.as_bytes() {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{PSEUDO_SESSION_PREFIX}{hash:016x}")
+}
+
+/// Mark the prompts of `session` (a [`pending_sessions`] result) analyzed.
+pub fn mark_analyzed(records: &mut [HistoryRecord], session: &Session) {
+    for r in records.iter_mut().filter(|r| r.project == session.project) {
+        if session.user_messages.iter().any(|m| m.text == r.display) {
+            r.analyzed = true;
+        }
+    }
+}
+
+/// Counts for `retro status`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryCounts {
+    /// History-only prompts in `history.jsonl` right now.
+    pub prompts: usize,
+    pub projects: usize,
+    /// Of the recorded prompts, already analyzed.
+    pub analyzed: usize,
+}
+
+pub fn counts(store_root: &Path, claude_dir: &Path) -> HistoryCounts {
+    let entries = parse_history(&claude_dir.join("history.jsonl"));
+    let only = history_only(claude_dir, &entries);
+    HistoryCounts {
+        prompts: only.len(),
+        projects: group_by_project(&only).len(),
+        analyzed: load(store_root).iter().filter(|r| r.analyzed).count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn line(display: &str, project: &str, ts: i64, session: Option<&str>) -> String {
+        let mut v = serde_json::json!({
+            "display": display,
+            "pastedContents": {},
+            "timestamp": ts,
+            "project": project,
+        });
+        if let Some(s) = session {
+            v["sessionId"] = serde_json::json!(s);
+        }
+        v.to_string()
+    }
+
+    #[test]
+    fn parses_and_groups_skipping_junk() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("history.jsonl");
+        let content = [
+            line("use uv, not pip", "/work/a", 1, None),
+            "not json".to_string(),
+            line("   ", "/work/a", 2, None),
+            r#"{"display":"no project","timestamp":3}"#.to_string(),
+            line("run clippy first", "/work/b", 4, Some("s-b")),
+            line("always use uv", "/work/a", 5, None),
+        ]
+        .join("\n");
+        std::fs::write(&path, content).unwrap();
+        let entries = parse_history(&path);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].session_id.as_deref(), Some("s-b"));
+        let groups = group_by_project(&entries);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["/work/a"].len(), 2);
+        assert!(parse_history(&tmp.path().join("missing.jsonl")).is_empty());
+    }
+
+    #[test]
+    fn only_entries_without_transcripts_are_backfilled_once() {
+        let claude = TempDir::new().unwrap();
+        let kept = claude.path().join("projects/-work-kept");
+        std::fs::create_dir_all(&kept).unwrap();
+        std::fs::write(kept.join("live.jsonl"), "{}\n").unwrap();
+        let entries = vec![
+            serde_json::from_str(&line("kept", "/work/kept", 1, Some("live"))).unwrap(),
+            serde_json::from_str(&line("cleaned", "/work/kept", 2, Some("gone"))).unwrap(),
+            serde_json::from_str(&line("old dir kept", "/work/kept", 3, None)).unwrap(),
+            serde_json::from_str(&line("dir gone", "/work/gone", 4, None)).unwrap(),
+        ];
+        let only = history_only(claude.path(), &entries);
+        let shown: Vec<&str> = only.iter().map(|e| e.display.as_str()).collect();
+        assert_eq!(shown, vec!["dir gone", "cleaned"], "grouped by project");
+
+        let mut records = Vec::new();
+        assert_eq!(merge_new(&mut records, &only), 2);
+        assert_eq!(merge_new(&mut records, &only), 0, "idempotent");
+    }
+
+    #[test]
+    fn pending_sessions_group_per_project_and_mark_analyzed() {
+        let tmp = TempDir::new().unwrap();
+        let record = |project: &str, display: &str, ts: i64| HistoryRecord {
+            project: project.to_string(),
+            display: display.to_string(),
+            timestamp: Some(ts),
+            analyzed: false,
+        };
+        let mut records = vec![
+            record("/work/a", "second", 20),
+            record("/work/b", "only", 5),
+            record("/work/a", "first", 10),
+        ];
+        let sessions = pending_sessions(&records);
+        assert_eq!(sessions.len(), 2);
+        let a = &sessions[0];
+        assert_eq!(a.project, "/work/a");
+        assert!(a.session_id.starts_with(PSEUDO_SESSION_PREFIX));
+        assert_eq!(a.session_id, pseudo_session_id("/work/a"));
+        let texts: Vec<&str> = a.user_messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["first", "second"]);
+        assert_eq!(a.metadata.cwd.as_deref(), Some("/work/a"));
+
+        mark_analyzed(&mut records, a);
+        save(tmp.path(), &records).unwrap();
+        let reloaded = load(tmp.path());
+        assert_eq!(reloaded.iter().filter(|r| r.analyzed).count(), 2);
+        assert_eq!(pending_sessions(&reloaded).len(), 1);
+    }
+}
@@ -0,0 +1,145 @@
+//! `[[ingest.ignore_session_if]]` filters: sessions from automation (CI
+//! `claude -p` summaries, bots) that should never be analyzed. Matching
+//! sessions are dropped from the queue and recorded as processed, so
+//! catch-up never re-enqueues them.
+//!
+//! Config errors are loud: `Config::load` calls [`validate`], so a bad regex
+//! fails every interactive command instead of silently ignoring nothing.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::config::{IgnoreRule, expand_tilde};
+use crate::errors::CoreError;
+use crate::models::Session;
+
+/// Reject rules that can't be evaluated: no matcher set, an empty matcher,
+/// or an invalid `first_message` regex. Errors name the rule (1-based).
+pub fn validate(rules: &[IgnoreRule]) -> Result<(), CoreError> {
+    for (i, rule) in rules.iter().enumerate() {
+        let at = |msg: String| {
+            CoreError::Config(format!("[[ingest.ignore_session_if]] rule {}: {msg}", i + 1))
+        };
+        let matchers = [&rule.first_message, &rule.cwd_prefix, &rule.model];
+        if matchers.iter().all(|m| m.is_none()) {
+            return Err(at(
+                "matches every session — set first_message, cwd_prefix, or model".to_string(),
+            ));
+        }
+        if matchers.iter().any(|m| m.as_deref().is_some_and(|v| v.trim().is_empty())) {
+            return Err(at("empty matcher".to_string()));
+        }
+        if let Some(pattern) = &rule.first_message {
+            Regex::new(pattern).map_err(|e| at(format!("invalid first_message regex: {e}")))?;
+        }
+    }
+    Ok(())
+}
+
+/// Index of the first rule `session` (run in `cwd`) matches, if any.
+/// Rules are assumed validated; an uncompilable regex never matches.
+pub fn first_match(rules: &[IgnoreRule], session: &Session, cwd: &str) -> Option<usize> {
+    rules.iter().position(|r| matches(r, session, cwd))
+}
+
+fn matches(rule: &IgnoreRule, session: &Session, cwd: &str) -> bool {
+    if let Some(pattern) = &rule.first_message {
+        let first = session.user_messages.first().map(|m| m.text.as_str());
+        let hit = Regex::new(pattern)
+            .ok()
+            .zip(first)
+            .is_some_and(|(re, text)| re.is_match(text));
+        if !hit {
+            return false;
+        }
+    }
+    let cwd_ok = rule
+        .cwd_prefix
+        .as_ref()
+        .is_none_or(|prefix| Path::new(cwd).starts_with(expand_tilde(prefix)));
+    let model_ok = rule
+        .model
+        .as_ref()
+        .is_none_or(|model| session.metadata.model.as_deref() == Some(model.as_str()));
+    cwd_ok && model_ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ParsedUserMessage, SessionMetadata};
+
+    fn session(first: &str, model: Option<&str>) -> Session {
+        Session {
+            session_id: "s".to_string(),
+            project: String::new(),
+            session_path: String::new(),
+            user_messages: vec![ParsedUserMessage {
+                text: first.to_string(),
+                timestamp: None,
+                is_correction: false,
+            }],
+            assistant_messages: vec![],
+            summaries: vec![],
+            tools_used: vec![],
+            errors: vec![],
+            metadata: SessionMetadata {
+                cwd: None,
+                version: None,
+                git_branch: None,
+                model: model.map(str::to_string),
+                git_branches: vec![],
+            },
+            unknown_entry_types: Default::default(),
+        }
+    }
+
+    fn rule(first_message: Option<&str>, cwd_prefix: Option<&str>, model: Option<&str>) -> IgnoreRule {
+        IgnoreRule {
+            first_message: first_message.map(str::to_string),
+            cwd_prefix: cwd_prefix.map(str::to_string),
+            model: model.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn each_matcher_type() {
+        let ci = session("Summarize this pull request: #123", Some("claude-haiku-4-5"));
+        let human = session("help me fix the flaky test", Some("claude-sonnet-4-5"));
+
+        let by_message = [rule(Some(r"^Summarize this pull request"), None, None)];
+        assert_eq!(first_match(&by_message, &ci, "/work/app"), Some(0));
+        assert_eq!(first_match(&by_message, &human, "/work/app"), None);
+
+        let by_cwd = [rule(None, Some("/ci/runner"), None)];
+        assert_eq!(first_match(&by_cwd, &human, "/ci/runner/_work/app"), Some(0));
+        assert_eq!(first_match(&by_cwd, &human, "/ci/runner-other"), None, "path prefix, not string prefix");
+
+        let by_model = [rule(None, None, Some("claude-haiku-4-5"))];
+        assert_eq!(first_match(&by_model, &ci, "/work/app"), Some(0));
+        assert_eq!(first_match(&by_model, &human, "/work/app"), None);
+    }
+
+    #[test]
+    fn all_set_matchers_must_match_and_first_rule_wins() {
+        let ci = session("Summarize this pull request", Some("claude-haiku-4-5"));
+        let rules = [
+            rule(Some("^Summarize"), Some("/ci"), None),
+            rule(None, None, Some("claude-haiku-4-5")),
+        ];
+        assert_eq!(first_match(&rules, &ci, "/work/app"), Some(1));
+        assert_eq!(first_match(&rules, &ci, "/ci/app"), Some(0));
+    }
+
+    #[test]
+    fn validation_is_loud() {
+        assert!(validate(&[rule(Some("^ok"), None, None)]).is_ok());
+        let err = validate(&[rule(Some("^ok"), None, None), rule(Some("(unclosed"), None, None)])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("rule 2") && err.contains("regex"), "got: {err}");
+        assert!(validate(&[rule(None, None, None)]).unwrap_err().to_string().contains("every session"));
+        assert!(validate(&[rule(None, Some(" "), None)]).is_err());
+    }
+}
@@ -1,5 +1,42 @@
+pub mod corrections;
+pub mod diagnose;
+pub mod history;
+pub mod ignore;
 pub mod session;
 
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bytes sampled from each end of a transcript for its content fingerprint.
+const FINGERPRINT_SAMPLE: u64 = 64 * 1024;
+
+/// Cheap content fingerprint for a session transcript: `<len>:<hash>` where
+/// the hash (FNV-1a, stable across builds) covers the first and last 64KB.
+/// Transcripts are append-only, so any real change moves the length or the
+/// tail; an mtime-only touch (backup tools, sync clients) leaves it intact.
+pub fn content_fingerprint(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    let mut buf = Vec::with_capacity(FINGERPRINT_SAMPLE as usize);
+    (&mut file).take(FINGERPRINT_SAMPLE).read_to_end(&mut buf)?;
+    feed(&buf);
+    if len > FINGERPRINT_SAMPLE {
+        let tail_start = len.saturating_sub(FINGERPRINT_SAMPLE).max(FINGERPRINT_SAMPLE);
+        file.seek(SeekFrom::Start(tail_start))?;
+        buf.clear();
+        file.take(FINGERPRINT_SAMPLE).read_to_end(&mut buf)?;
+        feed(&buf);
+    }
+    Ok(format!("{len}:{hash:016x}"))
+}
+
 /// Encode a project path for use as a directory name.
 /// /home/user/project → -home-user-project
 pub fn encode_project_path(path: &str) -> String {
@@ -10,6 +47,32 @@ pub fn encode_project_path(path: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_content_fingerprint_ignores_mtime_but_sees_appends() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        let body = "{\"type\":\"user\"}\n".repeat(10_000); // > 2 samples
+        std::fs::write(&path, &body).unwrap();
+        let before = content_fingerprint(&path).unwrap();
+        // rewrite identical content (what a sync client's touch looks like)
+        std::fs::write(&path, &body).unwrap();
+        assert_eq!(content_fingerprint(&path).unwrap(), before);
+        std::fs::write(&path, format!("{body}{{\"type\":\"summary\"}}\n")).unwrap();
+        assert_ne!(content_fingerprint(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn test_content_fingerprint_sees_same_length_tail_edit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        let mut body = "a".repeat(200_000);
+        std::fs::write(&path, &body).unwrap();
+        let before = content_fingerprint(&path).unwrap();
+        body.replace_range(199_990.., "bbbbbbbbbb");
+        std::fs::write(&path, &body).unwrap();
+        assert_ne!(content_fingerprint(&path).unwrap(), before);
+    }
+
     #[test]
     fn test_encode_project_path() {
         assert_eq!(
@@ -1,6 +1,8 @@
 use crate::errors::CoreError;
+use crate::logging;
 use crate::models::*;
 use crate::util::log_parse_warning;
+use std::collections::BTreeMap;
 use std::io::BufRead;
 use std::path::Path;
 
@@ -10,8 +12,17 @@ pub fn parse_session_file(
     session_id: &str,
     project: &str,
 ) -> Result<Session, CoreError> {
-    let entries = parse_jsonl_entries(path)?;
-    build_session(entries, session_id, project, path)
+    let (entries, unknown) = parse_jsonl_entries(path)?;
+    let mut session = build_session(entries, session_id, project, path)?;
+    log::debug!(
+        target: logging::INGEST,
+        "parsed {session_id}: {} user / {} assistant message(s), {} unknown entry type(s)",
+        session.user_messages.len(),
+        session.assistant_messages.len(),
+        unknown.len()
+    );
+    session.unknown_entry_types = unknown;
+    Ok(session)
 }
 
 /// Parse all subagent JSONL files in a directory.
@@ -56,13 +67,21 @@ const KNOWN_TYPES: &[&str] = &["user", "assistant", "summary"];
 /// Entry types we intentionally skip.
 const SKIP_TYPES: &[&str] = &["file-history-snapshot", "progress"];
 
-/// Parse JSONL entries from a file, skipping unparseable lines.
-fn parse_jsonl_entries(path: &Path) -> Result<Vec<SessionEntry>, CoreError> {
+/// Longest unknown type name kept for reporting (a corrupt line could
+/// otherwise put an arbitrary string into state.json).
+const MAX_TYPE_NAME: usize = 40;
+
+/// Parse JSONL entries from a file, skipping unparseable lines. Lines with
+/// an unrecognized `type` are skipped too, and counted per type name.
+fn parse_jsonl_entries(
+    path: &Path,
+) -> Result<(Vec<SessionEntry>, BTreeMap<String, usize>), CoreError> {
     let file =
         std::fs::File::open(path).map_err(|e| CoreError::Io(format!("opening {}: {e}", path.display())))?;
     let reader = std::io::BufReader::new(file);
 
     let mut entries = Vec::new();
+    let mut unknown: BTreeMap<String, usize> = BTreeMap::new();
 
     for (line_num, line) in reader.lines().enumerate() {
         let line = match line {
@@ -89,7 +108,11 @@ fn parse_jsonl_entries(path: &Path) -> Result<Vec<SessionEntry>, CoreError> {
 
         match &entry_type {
             Some(t) if SKIP_TYPES.contains(&t.as_str()) => continue,
-            Some(t) if !KNOWN_TYPES.contains(&t.as_str()) => continue, // unknown future type
+            Some(t) if !KNOWN_TYPES.contains(&t.as_str()) => {
+                // unknown future type
+                *unknown.entry(truncate(t, MAX_TYPE_NAME)).or_default() += 1;
+                continue;
+            }
             _ => {}
         }
 
@@ -109,7 +132,7 @@ fn parse_jsonl_entries(path: &Path) -> Result<Vec<SessionEntry>, CoreError> {
         }
     }
 
-    Ok(entries)
+    Ok((entries, unknown))
 }
 
 /// Build a Session from parsed entries.
@@ -124,11 +147,14 @@ fn build_session(
     let mut summaries = Vec::new();
     let mut tools_used = Vec::new();
     let mut errors = Vec::new();
+    // A prompt can only correct the assistant if the assistant spoke last.
+    let mut after_assistant = false;
     let mut metadata = SessionMetadata {
         cwd: None,
         version: None,
         git_branch: None,
         model: None,
+        git_branches: Vec::new(),
     };
 
     for entry in entries {
@@ -140,15 +166,20 @@ fn build_session(
                     metadata.version = user.version.clone();
                     metadata.git_branch = user.git_branch.clone();
                 }
+                note_branch(&mut metadata, &user.git_branch);
 
                 // Only include actual user prompts, not tool results
                 if !user.message.content.is_tool_result() {
                     let text = user.message.content.as_text();
                     if !text.is_empty() {
+                        let is_correction =
+                            after_assistant && super::corrections::is_correction(&text);
                         user_messages.push(ParsedUserMessage {
                             text,
                             timestamp: user.timestamp.clone(),
+                            is_correction,
                         });
+                        after_assistant = false;
                     }
                 }
             }
@@ -191,6 +222,7 @@ fn build_session(
                 if metadata.model.is_none() {
                     metadata.model = assistant.message.model.clone();
                 }
+                note_branch(&mut metadata, &assistant.git_branch);
 
                 let text = text_parts.join("\n");
                 if !text.is_empty() || !msg_tools.is_empty() || thinking_summary.is_some() {
@@ -200,6 +232,7 @@ fn build_session(
                         tools: msg_tools,
                         timestamp: assistant.timestamp.clone(),
                     });
+                    after_assistant = true;
                 }
             }
             SessionEntry::Summary(summary) => {
@@ -231,9 +264,20 @@ fn build_session(
         tools_used,
         errors,
         metadata,
+        unknown_entry_types: BTreeMap::new(),
     })
 }
 
+/// Record a branch in first-seen order (entries repeat it on every line).
+fn note_branch(metadata: &mut SessionMetadata, branch: &Option<String>) {
+    let new = branch
+        .as_deref()
+        .filter(|b| !b.is_empty() && !metadata.git_branches.iter().any(|seen| seen == b));
+    if let Some(b) = new {
+        metadata.git_branches.push(b.to_string());
+    }
+}
+
 /// Summarize a thinking block: first 500 chars + keyword-extracted segments.
 /// Thinking blocks can be 32K+ tokens, so we must bound the output.
 fn summarize_thinking(thinking: &str) -> String {
@@ -285,3 +329,79 @@ fn truncate(s: &str, max: usize) -> String {
         format!("{}...", &s[..boundary])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrections_only_count_after_an_assistant_turn() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        let user = |uuid: &str, text: &str| {
+            format!(
+                r#"{{"type":"user","uuid":"{uuid}","message":{{"role":"user","content":"{text}"}}}}"#
+            )
+        };
+        let assistant = r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"text","text":"Done, I used pip."}]}}"#;
+        let lines = [
+            user("u1", "no, start with the tests"), // opener: nothing to correct yet
+            assistant.to_string(),
+            user("u2", "No, use uv instead."),
+            user("u3", "you forgot the lockfile"), // follows a user turn
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let session = parse_session_file(&path, "s", "/p").unwrap();
+        let flags: Vec<bool> = session
+            .user_messages
+            .iter()
+            .map(|m| m.is_correction)
+            .collect();
+        assert_eq!(flags, vec![false, true, false]);
+    }
+
+    #[test]
+    fn branch_switches_are_recorded_in_order() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        let user = |uuid: &str, branch: &str| {
+            format!(r#"{{"type":"user","uuid":"{uuid}","cwd":"/p","gitBranch":"{branch}","version":"2.1.0","message":{{"role":"user","content":"next"}}}}"#)
+        };
+        let lines = [
+            user("u1", "main"),
+            r#"{"type":"assistant","uuid":"a1","gitBranch":"release/2.x","message":{"role":"assistant","content":[{"type":"text","text":"switched"}]}}"#.to_string(),
+            user("u2", "release/2.x"),
+            user("u3", "main"),
+            user("u4", ""),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let session = parse_session_file(&path, "s", "/p").unwrap();
+        assert_eq!(session.metadata.git_branch.as_deref(), Some("main"));
+        assert_eq!(session.metadata.version.as_deref(), Some("2.1.0"));
+        assert_eq!(session.metadata.git_branches, vec!["main", "release/2.x"]);
+    }
+
+    #[test]
+    fn unknown_entry_types_are_skipped_and_counted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"u1","cwd":"/p","message":{"role":"user","content":"add a flag"}}"#,
+            r#"{"type":"checkpoint","id":1,"payload":{"files":[]}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"text","text":"ok"}]}}"#,
+            r#"{"type":"checkpoint","id":2}"#,
+            r#"{"type":"telemetry-v2","data":"x"}"#,
+            r#"{"type":"user","uuid":"u2","message":{"role":"user","content":"thanks"}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let session = parse_session_file(&path, "s", "/p").unwrap();
+        assert_eq!(session.user_messages.len(), 2);
+        assert_eq!(session.assistant_messages.len(), 1);
+        assert_eq!(session.metadata.cwd.as_deref(), Some("/p"));
+        let unknown: Vec<_> = session.unknown_entry_types.into_iter().collect();
+        assert_eq!(
+            unknown,
+            vec![("checkpoint".to_string(), 2), ("telemetry-v2".to_string(), 1)]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 pub mod analysis;
+pub(crate) mod anonymize;
+pub mod auto;
 pub mod briefing;
 pub mod claude_settings;
 pub mod config;
+pub mod digest;
+pub mod dismissals;
 pub mod doctor;
 pub mod errors;
 pub mod health;
@@ -9,11 +13,20 @@ pub mod hook_event;
 pub mod ingest;
 pub mod lint;
 pub mod lock;
+pub mod logging;
 pub mod migrate;
 pub mod models;
 pub mod observer;
+pub mod prelude;
+pub mod profile;
 pub mod projection;
 pub mod runner_v3;
-pub mod scrub;
+pub(crate) mod scrub;
+#[cfg(any(test, feature = "dev-tools", feature = "test-support"))]
+pub mod seed;
+pub mod status;
 pub mod store;
+pub mod throttle;
+pub mod user;
 pub mod util;
+pub mod version;
@@ -1,16 +1,22 @@
-//! Store-wide lint: free (no-AI) checks for near-duplicate active nodes and
-//! stale low-confidence candidates. Findings are data; `retro lint` renders
+//! Store-wide lint: free (no-AI) checks for near-duplicate active nodes,
+//! stale low-confidence candidates, and rules already written by hand in the
+//! file they would project into. Findings are data; `retro lint` renders
 //! them and (non-dry-run) records them as briefing notifications.
+//! [`lint_projections`] runs the CLAUDE.md checks over what projection would
+//! write; [`retire_external`] acts on the hand-written duplicates.
+
+use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 
 use crate::config::Config;
 use crate::errors::CoreError;
-use crate::store::Store;
+use crate::projection::{claude_md, local_md};
+use crate::store::{Node, Scope, Store, projects};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct LintFinding {
-    pub kind: String, // "near-duplicate" | "stale-candidate"
+    pub kind: String, // "near-duplicate" | "stale-candidate" | "in-repo"
     pub node_ids: Vec<String>,
     pub detail: String,
 }
@@ -21,6 +27,20 @@ pub struct LintReport {
     pub nodes_scanned: usize,
 }
 
+/// `invalidated_by` value for a node retired because its rule is already
+/// written by hand in the file it would project into.
+pub const EXTERNAL: &str = "external";
+
+/// An active node whose rule already appears outside the managed block of
+/// `path`: the project's committed CLAUDE.md (a teammate wrote it down), or
+/// the global CLAUDE.md's hand-written part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalMatch {
+    pub scope: Scope,
+    pub node_id: String,
+    pub path: PathBuf,
+}
+
 /// Free lint pass: no AI calls, no writes. Compares ACTIVE nodes only.
 pub fn run_lint(store: &Store, config: &Config) -> Result<LintReport, CoreError> {
     let loaded = store.load_all()?;
@@ -50,9 +70,9 @@ pub fn run_lint(store: &Store, config: &Config) -> Result<LintReport, CoreError>
             }
             if crate::util::normalized_similarity(&a.body, &b.body) > 0.8 {
                 let cross_type = if a.node_type == b.node_type {
-                    "consider merging (invalidate one)"
+                    format!("consider `retro merge {} {}`", a.id, b.id)
                 } else {
-                    "similar content across node types — review whether both are needed"
+                    "similar content across node types — review whether both are needed".to_string()
                 };
                 report.findings.push(LintFinding {
                     kind: "near-duplicate".to_string(),
@@ -67,8 +87,10 @@ pub fn run_lint(store: &Store, config: &Config) -> Result<LintReport, CoreError>
     }
 
     // Stale candidates: sub-threshold confidence that never matured.
-    let staleness = chrono::Duration::days(config.analysis.staleness_days as i64);
-    let cutoff = chrono::Utc::now().date_naive() - staleness;
+    let cutoff = crate::util::days_before(
+        chrono::Utc::now().date_naive(),
+        config.analysis.staleness_days as i64,
+    );
     for n in &active {
         if n.confidence < config.knowledge.confidence_threshold && n.updated < cutoff {
             report.findings.push(LintFinding {
@@ -81,9 +103,106 @@ pub fn run_lint(store: &Store, config: &Config) -> Result<LintReport, CoreError>
             });
         }
     }
+
+    for m in external_duplicates(store, config)? {
+        report.findings.push(LintFinding {
+            kind: "in-repo".to_string(),
+            detail: format!(
+                "`{}` is already written in {} — `retro lint --reconcile` retires it",
+                m.node_id,
+                crate::util::shorten_path_buf(&m.path)
+            ),
+            node_ids: vec![m.node_id],
+        });
+    }
     Ok(report)
 }
 
+/// Active nodes whose every projected bullet already appears (normalized:
+/// case, whitespace, trailing punctuation) among the hand-written bullets of
+/// the file they would project next to. Project nodes are checked against
+/// the project's own CLAUDE.md — the shared, committed file, not retro's
+/// CLAUDE.local.md.
+pub fn external_duplicates(
+    store: &Store,
+    config: &Config,
+) -> Result<Vec<ExternalMatch>, CoreError> {
+    let mut files: Vec<(Scope, PathBuf)> =
+        vec![(Scope::Global, config.claude_dir().join("CLAUDE.md"))];
+    for (slug, path) in projects::PathMap::load(store.root())?.paths {
+        files.push((Scope::Project(slug), Path::new(&path).join("CLAUDE.md")));
+    }
+    let loaded = store.load_all()?;
+    let mut out = Vec::new();
+    for (scope, path) in files {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let written = claude_md::user_bullets(&content);
+        if written.is_empty() {
+            continue;
+        }
+        let matches = loaded
+            .nodes
+            .iter()
+            .map(|(_, n)| n)
+            .filter(|n| n.is_active() && n.scope == scope)
+            .filter(|n| already_written(n, &written));
+        for n in matches {
+            out.push(ExternalMatch {
+                scope: scope.clone(),
+                node_id: n.id.clone(),
+                path: path.clone(),
+            });
+        }
+    }
+    Ok(out)
+}
+
+fn already_written(node: &Node, written: &std::collections::HashSet<String>) -> bool {
+    let bullets = local_md::projected_bullets(&node.body);
+    !bullets.is_empty()
+        && bullets
+            .iter()
+            .all(|b| written.contains(&claude_md::normalize_bullet(b)))
+}
+
+/// Invalidate [`external_duplicates`] matches as [`EXTERNAL`], so they stop
+/// projecting. The caller commits and reprojects.
+pub fn retire_external(store: &Store, matches: &[ExternalMatch]) -> Result<(), CoreError> {
+    for m in matches {
+        store.invalidate(&m.scope, &m.node_id, EXTERNAL)?;
+    }
+    Ok(())
+}
+
+/// Lint the content projection would leave in the global CLAUDE.md and each
+/// registered project's CLAUDE.local.md (previewed, never written). Files
+/// without findings are omitted.
+pub fn lint_projections(
+    store: &Store,
+    config: &Config,
+) -> Result<Vec<(PathBuf, Vec<claude_md::LintFinding>)>, CoreError> {
+    let threshold = config.knowledge.confidence_threshold;
+    let global = config.claude_dir().join("CLAUDE.md");
+    let mut previews = vec![(
+        global.clone(),
+        local_md::preview_global_md(store, &global, threshold)?,
+    )];
+    for (slug, path) in projects::PathMap::load(store.root())?.paths {
+        let root = Path::new(&path);
+        if root.is_dir() {
+            let content = local_md::preview_local_md(store, &slug, root, threshold)?;
+            previews.push((root.join("CLAUDE.local.md"), content));
+        }
+    }
+    Ok(previews
+        .into_iter()
+        .map(|(path, content)| (path, claude_md::lint(&content)))
+        .filter(|(_, findings)| !findings.is_empty())
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +221,8 @@ mod tests {
             created: date,
             updated: date,
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: body.to_string(),
         }
     }
@@ -216,4 +337,97 @@ mod tests {
         assert!(report.findings.is_empty());
         assert_eq!(report.nodes_scanned, 1);
     }
+
+    #[test]
+    fn lint_projections_checks_previewed_content_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let claude = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let mut config = Config::default();
+        config.paths.claude_dir = claude.path().display().to_string();
+        let md = claude.path().join("CLAUDE.md");
+        std::fs::write(&md, "- Use uv for Python installs\n").unwrap();
+        assert!(lint_projections(&store, &config).unwrap().is_empty());
+
+        store
+            .write_node(&node("uv", Scope::Global, 0.9, 1, "Use uv for Python installs."))
+            .unwrap();
+        let found = lint_projections(&store, &config).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, md);
+        assert_eq!(found[0].1[0].kind, "duplicate-bullet");
+        assert_eq!(std::fs::read_to_string(&md).unwrap(), "- Use uv for Python installs\n");
+    }
+
+    #[test]
+    fn rules_already_written_by_hand_are_found_and_retired() {
+        let tmp = TempDir::new().unwrap();
+        let claude = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let mut config = Config::default();
+        config.paths.claude_dir = claude.path().display().to_string();
+        let mut map = projects::PathMap::default();
+        map.paths.insert("app".to_string(), repo.path().display().to_string());
+        map.save(tmp.path()).unwrap();
+        // A teammate's rule in the committed CLAUDE.md; the managed block of
+        // the global file is retro's own output and never counts.
+        std::fs::write(
+            repo.path().join("CLAUDE.md"),
+            "# App\n\n## Testing\n- run   `cargo test -p app` before pushing!\n",
+        )
+        .unwrap();
+        std::fs::write(
+            claude.path().join("CLAUDE.md"),
+            claude_md::update_claude_md_content("", &["Use uv for Python".to_string()]),
+        )
+        .unwrap();
+        let project = Scope::Project("app".to_string());
+        store
+            .write_node(&node(
+                "push",
+                project.clone(),
+                0.9,
+                1,
+                "Run `cargo test -p app` before pushing.",
+            ))
+            .unwrap();
+        store
+            .write_node(&node("fmt", project.clone(), 0.9, 1, "Run cargo fmt before committing."))
+            .unwrap();
+        store
+            .write_node(&node("uv", Scope::Global, 0.9, 1, "Use uv for Python"))
+            .unwrap();
+        // same text, wrong scope: the global file doesn't hold it
+        store
+            .write_node(&node(
+                "push",
+                Scope::Global,
+                0.9,
+                1,
+                "Run `cargo test -p app` before pushing.",
+            ))
+            .unwrap();
+
+        let found = external_duplicates(&store, &config).unwrap();
+        assert_eq!(
+            found,
+            vec![ExternalMatch {
+                scope: project.clone(),
+                node_id: "push".to_string(),
+                path: repo.path().join("CLAUDE.md"),
+            }]
+        );
+        let report = run_lint(&store, &config).unwrap();
+        assert!(report.findings.iter().any(|f| f.kind == "in-repo" && f.node_ids == ["push"]));
+
+        retire_external(&store, &found).unwrap();
+        let retired = store.get(&project, "push").unwrap().unwrap();
+        assert_eq!(retired.invalidated_by.as_deref(), Some(EXTERNAL));
+        assert!(store.get(&project, "fmt").unwrap().unwrap().is_active());
+        assert!(store.get(&Scope::Global, "push").unwrap().unwrap().is_active());
+        assert!(external_duplicates(&store, &config).unwrap().is_empty());
+    }
 }
@@ -17,7 +17,8 @@ impl LockFile {
             if let Ok(pid) = contents.trim().parse::<i32>() {
                 if is_process_alive(pid) {
                     return Err(CoreError::Lock(format!(
-                        "another retro process is running (PID {pid})"
+                        "another retro process is running ({})",
+                        holder(path).unwrap_or_else(|| format!("PID {pid}"))
                     )));
                 }
             }
@@ -50,9 +51,66 @@ impl Drop for LockFile {
     }
 }
 
+/// Who holds a lockfile, without trying to take it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockState {
+    Free,
+    Held(i32),
+    /// The holding process is gone; the next `acquire` removes the file.
+    Stale(i32),
+}
+
+/// Inspect a lockfile. An unparseable file counts as stale (PID 0), which
+/// is also how `acquire` treats it.
+pub fn inspect(path: &Path) -> LockState {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return LockState::Free;
+    };
+    match contents.trim().parse::<i32>() {
+        Ok(pid) if is_process_alive(pid) => LockState::Held(pid),
+        Ok(pid) => LockState::Stale(pid),
+        Err(_) => LockState::Stale(0),
+    }
+}
+
+/// `PID 123, user alice` for a held lockfile: the PID it records and the
+/// user who created it (the file's owner — on a shared machine, whose run
+/// is in the way). `None` when the lock is free or stale.
+pub fn holder(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let LockState::Held(pid) = inspect(path) else {
+        return None;
+    };
+    Some(match fs::metadata(path) {
+        Ok(meta) => format!("PID {pid}, user {}", crate::user::name_of(meta.uid())),
+        Err(_) => format!("PID {pid}"),
+    })
+}
+
 /// Check if a process is alive using kill(pid, 0) — portable across Linux and macOS.
 fn is_process_alive(pid: i32) -> bool {
     // kill with signal 0 checks process existence without sending a signal.
     // Returns 0 if process exists, -1 with ESRCH if it doesn't.
     unsafe { libc::kill(pid, 0) == 0 }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holder_names_the_pid_and_the_lock_owner() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("run.lock");
+        assert_eq!(holder(&path), None, "free");
+        let lock = LockFile::acquire(&path).unwrap();
+        let held = holder(&path).unwrap();
+        assert!(held.starts_with(&format!("PID {}, user ", std::process::id())), "{held}");
+        let err = LockFile::acquire(&path).err().unwrap().to_string();
+        assert!(err.contains(&held), "{err}");
+        drop(lock);
+        fs::write(&path, "not a pid").unwrap();
+        assert_eq!(holder(&path), None, "stale");
+    }
+}
@@ -0,0 +1,240 @@
+//! Diagnostic logging over the `log` facade. Core code logs under a few
+//! stable targets (below) rather than its module paths, so a filter like
+//! `RETRO_LOG=retro_core::git=trace` keeps working across refactors. The
+//! binary installs a [`Logger`] once at startup; a library embedder can
+//! install any `log` implementation instead, or none (logging is then free).
+//!
+//! Filter syntax (`RETRO_LOG`): comma-separated `target=level` directives
+//! plus an optional bare default level, e.g. `warn,retro_core::git=trace`.
+//! A directive applies to its target and everything below it (`a` covers
+//! `a::b`); the longest matching directive wins.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Transcript discovery and parsing.
+pub const INGEST: &str = "retro_core::ingest";
+/// Backend calls and analysis results.
+pub const ANALYSIS: &str = "retro_core::analysis";
+/// Every git invocation against the store.
+pub const GIT: &str = "retro_core::git";
+/// CLAUDE.md / CLAUDE.local.md writes.
+pub const PROJECTION: &str = "retro_core::projection";
+/// Pipeline stages.
+pub const RUNNER: &str = "retro_core::runner";
+/// The `retro` binary's own messages (hook warnings, the briefing).
+pub const CLI: &str = "retro";
+/// The dashboard server.
+pub const UI: &str = "retro::ui";
+
+/// Environment variable holding the filter spec.
+pub const ENV_VAR: &str = "RETRO_LOG";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    default: LevelFilter,
+    /// Sorted longest target first, so the first prefix match is the most specific.
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    pub fn new(default: LevelFilter) -> Self {
+        Filter {
+            default,
+            directives: Vec::new(),
+        }
+    }
+
+    /// Parse a filter spec on top of `default`. Unparseable directives are
+    /// ignored (a typo in an env var must not stop a hook).
+    pub fn parse(spec: &str, default: LevelFilter) -> Self {
+        let mut filter = Filter::new(default);
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                None => {
+                    if let Ok(level) = part.parse() {
+                        filter.default = level;
+                    }
+                }
+                Some((target, level)) => {
+                    if let Ok(level) = level.trim().parse() {
+                        filter.directives.push((target.trim().to_string(), level));
+                    }
+                }
+            }
+        }
+        filter.directives.sort_by_key(|(t, _)| std::cmp::Reverse(t.len()));
+        filter
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(t, _)| {
+                target == t
+                    || (target.starts_with(t.as_str()) && target[t.len()..].starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// The most verbose level any target can log at.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, l)| *l)
+            .fold(self.default, std::cmp::max)
+    }
+}
+
+/// Size past which [`open_log_file`] rotates a log file.
+pub const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Open `path` for appending. A file already past `max_bytes` is first
+/// renamed to `<name>.1` (replacing the previous one), so a log that hooks
+/// append to on every session stays bounded at about twice `max_bytes`.
+pub fn open_log_file(path: &Path, max_bytes: u64) -> std::io::Result<File> {
+    let full = std::fs::metadata(path).is_ok_and(|m| m.len() > max_bytes);
+    if full {
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(".1");
+        // Best-effort: a failed rename (a racing hook got there first)
+        // still leaves a file to append to.
+        let _ = std::fs::rename(path, rotated);
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Compact line-per-record logger: `LEVEL target: message`.
+pub struct Logger {
+    filter: Filter,
+    writer: Mutex<Box<dyn Write + Send>>,
+    timestamps: bool,
+}
+
+impl Logger {
+    pub fn new(filter: Filter, writer: Box<dyn Write + Send>) -> Self {
+        Logger {
+            filter,
+            writer: Mutex::new(writer),
+            timestamps: false,
+        }
+    }
+
+    /// Prefix each line with the UTC time (for log files read after the fact).
+    pub fn timestamped(mut self) -> Self {
+        self.timestamps = true;
+        self
+    }
+
+    /// Install as the global logger. Fails if one is already installed.
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        log::set_max_level(self.filter.max_level());
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(mut w) = self.writer.lock() {
+            if self.timestamps {
+                let _ = write!(w, "{} ", chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"));
+            }
+            let _ = writeln!(w, "{:<5} {}: {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut w) = self.writer.lock() {
+            let _ = w.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+    use std::sync::Arc;
+
+    /// Shared buffer the test keeps a handle to after boxing the logger's copy.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn emit(logger: &Logger, target: &str, level: Level, msg: &str) {
+        logger.log(
+            &Record::builder()
+                .target(target)
+                .level(level)
+                .args(format_args!("{msg}"))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn filtered_target_logs_and_others_stay_quiet() {
+        let capture = Capture::default();
+        let filter = Filter::parse("warn,retro_core::git=trace", LevelFilter::Off);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        let logger = Logger::new(filter, Box::new(capture.clone()));
+
+        emit(&logger, GIT, Level::Trace, "git -C store status");
+        emit(&logger, INGEST, Level::Debug, "parsed 40 entries");
+        emit(&logger, ANALYSIS, Level::Info, "1 call");
+        emit(&logger, PROJECTION, Level::Warn, "over the size limit");
+
+        let out = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(out.contains("TRACE retro_core::git: git -C store status"), "{out}");
+        assert!(!out.contains("parsed 40 entries"), "{out}");
+        assert!(!out.contains("1 call"), "{out}");
+        assert!(out.contains("WARN  retro_core::projection: over the size limit"), "{out}");
+    }
+
+    #[test]
+    fn log_file_rotates_once_past_the_cap() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("hook-stderr.log");
+        let rotated = tmp.path().join("hook-stderr.log.1");
+        open_log_file(&path, 10).unwrap().write_all(b"0123456789").unwrap();
+        open_log_file(&path, 10).unwrap().write_all(b"x").unwrap();
+        assert!(!rotated.exists(), "at the cap is not past it");
+
+        let mut file = open_log_file(&path, 10).unwrap();
+        file.write_all(b"fresh").unwrap();
+        assert_eq!(std::fs::read_to_string(&rotated).unwrap(), "0123456789x");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fresh");
+    }
+
+    #[test]
+    fn longest_directive_wins_on_module_boundaries() {
+        let filter = Filter::parse(
+            "retro_core=info, retro_core::git=off,bogus=loud,debug",
+            LevelFilter::Warn,
+        );
+        assert_eq!(filter.level_for("retro_core::git"), LevelFilter::Off);
+        assert_eq!(filter.level_for("retro_core::git::push"), LevelFilter::Off);
+        assert_eq!(filter.level_for("retro_core::gitx"), LevelFilter::Info);
+        assert_eq!(filter.level_for("retro_core::ingest"), LevelFilter::Info);
+        assert_eq!(filter.level_for("tiny_http"), LevelFilter::Debug, "bare level is the default");
+        assert_eq!(Filter::parse("", LevelFilter::Warn), Filter::new(LevelFilter::Warn));
+    }
+}
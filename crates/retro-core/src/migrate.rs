@@ -167,6 +167,8 @@ pub fn migrate_knowledge(
                 created: date_of(&v2.created_at),
                 updated: date_of(&v2.updated_at),
                 invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
                 body: v2.content.clone(),
             };
             store.write_node(&node)?;
@@ -200,7 +202,13 @@ pub fn safety_import(
         .nodes
         .iter()
         .filter(|(_, n)| n.scope == *scope)
-        .map(|(_, n)| n.body.clone())
+        .flat_map(|(_, n)| {
+            // A multi-rule body projects as several bullets — each must
+            // dedup against it, or re-running init re-imports them.
+            let mut forms = crate::projection::local_md::projected_bullets(&n.body);
+            forms.push(n.body.clone());
+            forms
+        })
         .collect();
     // Seed with bodies a preceding (possibly dry-run) knowledge import
     // accepted, so dry-run previews report the same count a real run would.
@@ -232,6 +240,8 @@ pub fn safety_import(
                 created: today,
                 updated: today,
                 invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
                 body: rule,
             })?;
         }
@@ -331,10 +341,9 @@ pub fn remove_v2_launchd() -> bool {
     let _ = std::process::Command::new("launchctl")
         .args(["bootout", &format!("gui/{uid}/com.retro.runner")])
         .output();
-    let Ok(home) = std::env::var("HOME") else {
-        return false;
-    };
-    let plist = Path::new(&home).join("Library/LaunchAgents/com.retro.runner.plist");
+    let plist = crate::user::home()
+        .path
+        .join("Library/LaunchAgents/com.retro.runner.plist");
     std::fs::remove_file(&plist).is_ok()
 }
 
@@ -675,6 +684,37 @@ mod tests {
         assert_eq!(nodes[0].1.sources, vec!["managed-import".to_string()]);
     }
 
+    #[test]
+    fn safety_import_recognizes_split_bullets_of_a_multi_rule_node() {
+        let tmp = TempDir::new().unwrap();
+        let claude = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let today = chrono::Utc::now().date_naive();
+        store
+            .write_node(&Node {
+                id: "python-tooling".to_string(),
+                scope: Scope::Global,
+                node_type: NodeType::Rule,
+                confidence: 0.9,
+                sources: vec![],
+                created: today,
+                updated: today,
+                invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
+                body: "- Use uv for Python installs.\n- Run ruff before committing.".to_string(),
+            })
+            .unwrap();
+        let path = claude.path().join("CLAUDE.md");
+        crate::projection::local_md::project_global_md(&store, &path, 0.7, None).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("- Run ruff before committing."));
+        assert_eq!(
+            safety_import(&store, &path, &Scope::Global, &[], false).unwrap(),
+            0
+        );
+    }
+
     #[test]
     fn safety_import_noop_without_managed_section() {
         let tmp = TempDir::new().unwrap();
@@ -215,12 +215,21 @@ pub struct Session {
     pub tools_used: Vec<String>,
     pub errors: Vec<String>,
     pub metadata: SessionMetadata,
+    /// Entry `type`s the parser doesn't know (newer Claude Code) -> line
+    /// count. Those lines are skipped, never fatal; the runner aggregates
+    /// these so `retro status` can say when retro may need an update.
+    #[serde(default)]
+    pub unknown_entry_types: std::collections::BTreeMap<String, usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedUserMessage {
     pub text: String,
     pub timestamp: Option<String>,
+    /// Follows an assistant turn and pushes back on it
+    /// (`ingest::corrections::is_correction`).
+    #[serde(default)]
+    pub is_correction: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -237,6 +246,10 @@ pub struct SessionMetadata {
     pub version: Option<String>,
     pub git_branch: Option<String>,
     pub model: Option<String>,
+    /// Every branch the session ran on, in first-seen order (a mid-session
+    /// `git checkout` yields more than one). `git_branch` is the first.
+    #[serde(default)]
+    pub git_branches: Vec<String>,
 }
 
 /// Claude CLI --output-format json wrapper.
@@ -299,6 +312,23 @@ pub struct CompactSession {
     pub errors: Vec<String>,
     pub thinking_highlights: Vec<String>,
     pub summaries: Vec<String>,
+    /// Branch(es) the session ran on — instructions on a release branch
+    /// may not hold on main.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub git_branches: Vec<String>,
+    /// Claude Code version that wrote the transcript.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claude_version: Option<String>,
+    /// User prompts classified as corrections of the assistant; `corrections`
+    /// holds the first few excerpts, the count covers all of them.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub corrections_count: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub corrections: Vec<String>,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -420,6 +450,10 @@ pub struct KnowledgeNode {
     pub scope: NodeScope,
     pub content: String,
     pub confidence: f64,
+    /// Pattern nodes: compact tool histogram of the source sessions
+    /// (`store::tools::compact`); empty otherwise.
+    #[serde(default)]
+    pub tools: String,
 }
 
 /// Operations the analyzer produces to mutate the knowledge graph.
@@ -431,6 +465,8 @@ pub enum GraphOperation {
         project_id: Option<String>,
         content: String,
         confidence: f64,
+        /// Heading of the project's CLAUDE.md the rule belongs under.
+        section: Option<String>,
     },
     CreateEdge {
         source_id: String,
@@ -471,6 +507,8 @@ pub struct GraphOperationResponse {
     #[serde(default)]
     pub confidence: Option<f64>,
     #[serde(default)]
+    pub section: Option<String>,
+    #[serde(default)]
     pub node_id: Option<String>,
     #[serde(default)]
     pub new_confidence: Option<f64>,
@@ -531,6 +569,7 @@ mod tests {
             scope: NodeScope::Project,
             content: "Always run tests".to_string(),
             confidence: 0.85,
+            tools: String::new(),
         };
         assert_eq!(node.node_type, NodeType::Rule);
         assert_eq!(node.scope, NodeScope::Project);
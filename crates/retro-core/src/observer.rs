@@ -1,6 +1,11 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use serde::{Deserialize, Serialize};
+
+use crate::errors::CoreError;
+
 /// A session file that has been modified since last check.
 #[derive(Debug, Clone)]
 pub struct ModifiedSession {
@@ -66,6 +71,144 @@ pub fn find_modified_sessions(
     results
 }
 
+/// Per-directory listings from the last catch-up scan, so an unchanged
+/// project directory isn't listed again (`state/scan_cache.json`,
+/// machine-local). Directory mtime moves when an entry is added, removed or
+/// renamed — NOT when an existing transcript is appended to, so cached files
+/// are still stat'ed every scan. A listing taken within [`RACY_NANOS`] of
+/// the directory's mtime is never reused (an entry added in the same coarse
+/// mtime tick would leave the mtime unchanged — git's "racy" rule).
+/// Caveat: tools that set a directory's mtime back (some sync and restore
+/// tools) hide new entries until the directory changes again, or until
+/// `retro brief --full-scan`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScanCache {
+    #[serde(default)]
+    pub dirs: BTreeMap<String, CachedDir>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CachedDir {
+    /// Directory mtime, nanoseconds since the epoch.
+    pub mtime_nanos: u64,
+    /// When the listing was taken, nanoseconds since the epoch.
+    pub listed_nanos: u64,
+    /// Top-level `*.jsonl` file names.
+    pub files: Vec<String>,
+}
+
+/// What a cached scan did (for the brief's health detail).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScanStats {
+    pub dirs: usize,
+    /// Directories listed because they changed (or were new, or `full_scan`).
+    pub dirs_listed: usize,
+    pub files: usize,
+}
+
+/// Coarsest directory mtime granularity worth covering (FAT: 2s).
+pub const RACY_NANOS: u64 = 2_000_000_000;
+
+fn scan_cache_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("scan_cache.json")
+}
+
+impl ScanCache {
+    /// Missing or corrupt file loads empty (the next scan lists everything).
+    pub fn load(store_root: &Path) -> Self {
+        std::fs::read_to_string(scan_cache_path(store_root))
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, store_root: &Path) -> Result<(), CoreError> {
+        let io = |e: std::io::Error| CoreError::Io(e.to_string());
+        let path = scan_cache_path(store_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io)?;
+        }
+        let json = serde_json::to_string(self).map_err(|e| CoreError::Parse(e.to_string()))?;
+        // Concurrent briefs: last writer wins, a torn file never lands.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(io)?;
+        std::fs::rename(&tmp, &path).map_err(io)
+    }
+}
+
+fn nanos(t: SystemTime) -> Option<u64> {
+    let since = t.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    u64::try_from(since.as_nanos()).ok()
+}
+
+/// [`find_modified_sessions`] over every project directory, reusing
+/// `cache`'s listings of directories whose mtime is unchanged (see
+/// [`ScanCache`]; `full_scan` lists everything). Only top-level transcripts are
+/// returned: subagent transcripts (`<session>/subagents/`) are parts of
+/// their parent session. Directories gone from disk drop out of `cache`.
+pub fn find_modified_sessions_cached(
+    claude_dir: &Path,
+    since: Option<SystemTime>,
+    cache: &mut ScanCache,
+    full_scan: bool,
+) -> (Vec<ModifiedSession>, ScanStats) {
+    let mut stats = ScanStats::default();
+    let mut results = Vec::new();
+    let Ok(projects) = std::fs::read_dir(claude_dir.join("projects")) else {
+        cache.dirs.clear();
+        return (results, stats);
+    };
+    let mut seen = BTreeMap::new();
+    for dir in projects.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let key = dir.display().to_string();
+        let Some(mtime) = std::fs::metadata(&dir).and_then(|m| m.modified()).ok().and_then(nanos)
+        else {
+            continue;
+        };
+        stats.dirs += 1;
+        let cached = cache.dirs.remove(&key).filter(|c| {
+            !full_scan
+                && c.mtime_nanos == mtime
+                && c.listed_nanos.saturating_sub(c.mtime_nanos) > RACY_NANOS
+        });
+        let listing = match cached {
+            Some(c) => c,
+            None => {
+                let _list = crate::profile::span("list");
+                stats.dirs_listed += 1;
+                let listed_nanos = nanos(SystemTime::now()).unwrap_or(0);
+                let files = std::fs::read_dir(&dir)
+                    .map(|r| {
+                        r.flatten()
+                            .filter_map(|e| e.file_name().into_string().ok())
+                            .filter(|n| n.ends_with(".jsonl"))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                CachedDir {
+                    mtime_nanos: mtime,
+                    listed_nanos,
+                    files,
+                }
+            }
+        };
+        let _stat = crate::profile::span("stat");
+        for name in &listing.files {
+            let path = dir.join(name);
+            let Some(mtime) = std::fs::metadata(&path).and_then(|m| m.modified()).ok() else {
+                continue;
+            };
+            stats.files += 1;
+            if since.is_none_or(|s| mtime > s) {
+                results.push(ModifiedSession { path, mtime });
+            }
+        }
+        seen.insert(key, listing);
+    }
+    cache.dirs = seen;
+    (results, stats)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,4 +295,77 @@ mod tests {
         );
         assert_eq!(modified.len(), 0);
     }
+
+    fn backdate(path: &Path, secs: u64) {
+        let t = SystemTime::now() - std::time::Duration::from_secs(secs);
+        fs::File::open(path).unwrap().set_modified(t).unwrap();
+    }
+
+    #[test]
+    fn cached_scan_skips_listing_unchanged_dirs_but_sees_appends() {
+        let claude = TempDir::new().unwrap();
+        let proj = claude.path().join("projects").join("-tmp-app");
+        fs::create_dir_all(proj.join("s1").join("subagents")).unwrap();
+        fs::write(proj.join("s1.jsonl"), "{}\n").unwrap();
+        fs::write(proj.join("s1").join("subagents").join("agent-a.jsonl"), "{}\n").unwrap();
+        backdate(&proj.join("s1.jsonl"), 60);
+        backdate(&proj, 60);
+
+        let mut cache = ScanCache::default();
+        let (found, stats) = find_modified_sessions_cached(claude.path(), None, &mut cache, false);
+        assert_eq!(found.len(), 1, "subagent transcripts are not sessions");
+        assert_eq!((stats.dirs, stats.dirs_listed), (1, 1));
+
+        // unchanged: served from the cache
+        let since = SystemTime::now() - std::time::Duration::from_secs(30);
+        let (found, stats) =
+            find_modified_sessions_cached(claude.path(), Some(since), &mut cache, false);
+        assert!(found.is_empty());
+        assert_eq!((stats.dirs_listed, stats.files), (0, 1));
+
+        // an append moves the file's mtime, not the directory's
+        fs::write(proj.join("s1.jsonl"), "{}\n{}\n").unwrap();
+        let (found, stats) =
+            find_modified_sessions_cached(claude.path(), Some(since), &mut cache, false);
+        assert_eq!(found.len(), 1);
+        assert_eq!(stats.dirs_listed, 0);
+
+        // full scan bypasses the cache
+        let (_, stats) = find_modified_sessions_cached(claude.path(), Some(since), &mut cache, true);
+        assert_eq!(stats.dirs_listed, 1);
+    }
+
+    #[test]
+    fn cached_scan_relists_changed_new_and_racy_dirs() {
+        let claude = TempDir::new().unwrap();
+        let proj = claude.path().join("projects").join("-tmp-app");
+        fs::create_dir_all(&proj).unwrap();
+        fs::write(proj.join("s1.jsonl"), "{}\n").unwrap();
+        let mut cache = ScanCache::default();
+        find_modified_sessions_cached(claude.path(), None, &mut cache, false);
+
+        // listed within the racy window of its mtime: never trusted
+        let (_, stats) = find_modified_sessions_cached(claude.path(), None, &mut cache, false);
+        assert_eq!(stats.dirs_listed, 1);
+
+        backdate(&proj, 60);
+        find_modified_sessions_cached(claude.path(), None, &mut cache, false);
+        // a new session file changes the directory's mtime
+        fs::write(proj.join("s2.jsonl"), "{}\n").unwrap();
+        let other = claude.path().join("projects").join("-tmp-other");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("s3.jsonl"), "{}\n").unwrap();
+        let (found, stats) = find_modified_sessions_cached(claude.path(), None, &mut cache, false);
+        assert_eq!(found.len(), 3);
+        assert_eq!((stats.dirs, stats.dirs_listed), (2, 2));
+
+        // the cache survives a save/load; removed directories drop out
+        let store = TempDir::new().unwrap();
+        cache.save(store.path()).unwrap();
+        let mut cache = ScanCache::load(store.path());
+        assert_eq!(cache.dirs.len(), 2);
+        fs::remove_dir_all(&other).unwrap();
+        find_modified_sessions_cached(claude.path(), None, &mut cache, false);
+        assert_eq!(cache.dirs.len(), 1);
+    }
 }
@@ -0,0 +1,29 @@
+//! The supported library surface of retro-core: everything a tool built on
+//! the v3 store needs, re-exported from one place. Items reachable only
+//! through their full module paths are implementation details and may change
+//! in any release; what is listed here follows semver, and its enums and
+//! structs are `#[non_exhaustive]` so new variants and fields are not
+//! breaking (build a [`Node`] with [`Node::new`]). Test doubles such as
+//! `analysis::backend::MockBackend` are behind the `test-support` feature.
+//!
+//! ```no_run
+//! use retro_core::prelude::*;
+//!
+//! let config = Config::load(&retro_dir().join("config.toml"))?;
+//! let store = Store::open(&retro_dir());
+//! let rules = projectable_rules(&store, &Scope::Global, config.knowledge.confidence_threshold)?;
+//! # Ok::<(), CoreError>(())
+//! ```
+
+pub use crate::analysis::backend::{AnalysisBackend, BackendResponse};
+pub use crate::config::{Config, retro_dir};
+pub use crate::errors::CoreError;
+pub use crate::health::Health;
+pub use crate::lint::{LintReport, run_lint};
+pub use crate::projection::local_md::{
+    preview_global_md, preview_local_md, project_global_md, project_local_md, projectable_rules,
+};
+pub use crate::runner_v3::{ProjectRunSummary, RunV3Summary, run_v3};
+pub use crate::store::merge::{MergeOptions, MergeOutcome, merge_nodes};
+pub use crate::store::queue::{QueueEntry, enqueue};
+pub use crate::store::{LoadResult, Node, NodeType, Scope, Store};
@@ -0,0 +1,170 @@
+//! Opt-in timing spans for performance debugging (`--profile` or
+//! `RETRO_PROFILE=1`). Disabled by default: `span()` then checks one atomic
+//! and returns an inert guard — no clock read, no allocation.
+//!
+//! Spans nest per thread: a span opened while another is live records under
+//! `parent/child`, so the report shows where a slow stage spends its time.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static TIMINGS: RefCell<Timings> = RefCell::new(Timings::default());
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Start a named span on this thread's collector; it ends when the guard drops.
+pub fn span(name: &'static str) -> Span {
+    if !is_enabled() {
+        return Span { active: false };
+    }
+    TIMINGS.with(|t| t.borrow_mut().enter(name));
+    Span { active: true }
+}
+
+#[must_use = "the span ends when this guard is dropped"]
+pub struct Span {
+    active: bool,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.active {
+            TIMINGS.with(|t| t.borrow_mut().exit());
+        }
+    }
+}
+
+/// Take this thread's finished spans, leaving the collector empty.
+pub fn take() -> Timings {
+    TIMINGS.with(|t| std::mem::take(&mut *t.borrow_mut()))
+}
+
+/// One finished span: its `/`-joined path from the outermost span.
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    pub path: String,
+    pub duration: Duration,
+}
+
+/// Span collector. `span()` drives a thread-local instance; tests drive
+/// one directly.
+#[derive(Debug, Default)]
+pub struct Timings {
+    stack: Vec<(&'static str, Instant)>,
+    pub records: Vec<SpanRecord>,
+}
+
+impl Timings {
+    pub fn enter(&mut self, name: &'static str) {
+        self.stack.push((name, Instant::now()));
+    }
+
+    pub fn exit(&mut self) {
+        self.exit_at(Instant::now());
+    }
+
+    fn exit_at(&mut self, now: Instant) {
+        let Some((_, start)) = self.stack.last().copied() else {
+            return;
+        };
+        let path = self
+            .stack
+            .iter()
+            .map(|(n, _)| *n)
+            .collect::<Vec<_>>()
+            .join("/");
+        self.stack.pop();
+        self.records.push(SpanRecord {
+            path,
+            duration: now.saturating_duration_since(start),
+        });
+    }
+
+    /// Per-path (calls, total), in path order so children follow parents.
+    pub fn aggregate(&self) -> BTreeMap<String, (usize, Duration)> {
+        let mut out: BTreeMap<String, (usize, Duration)> = BTreeMap::new();
+        for r in &self.records {
+            let e = out.entry(r.path.clone()).or_default();
+            e.0 += 1;
+            e.1 += r.duration;
+        }
+        out
+    }
+
+    /// Plain-text table: one row per span path, indented by depth.
+    pub fn render(&self) -> String {
+        let mut out = String::from("profile (total ms, calls, span):\n");
+        for (path, (calls, total)) in self.aggregate() {
+            let depth = path.matches('/').count();
+            let name = path.rsplit('/').next().unwrap_or(&path);
+            let _ = writeln!(
+                out,
+                "  {:>9.1}  {calls:>5}  {}{name}",
+                total.as_secs_f64() * 1000.0,
+                "  ".repeat(depth)
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_spans_record_paths_and_children_sum_within_parent() {
+        let mut t = Timings::default();
+        let base = Instant::now();
+        t.stack.push(("run", base));
+        t.stack.push(("analyze", base + Duration::from_millis(10)));
+        t.exit_at(base + Duration::from_millis(40));
+        t.stack.push(("analyze", base + Duration::from_millis(40)));
+        t.exit_at(base + Duration::from_millis(60));
+        t.exit_at(base + Duration::from_millis(100));
+
+        let agg = t.aggregate();
+        assert_eq!(agg["run"], (1, Duration::from_millis(100)));
+        assert_eq!(agg["run/analyze"], (2, Duration::from_millis(50)));
+        assert!(agg["run/analyze"].1 <= agg["run"].1);
+
+        let report = t.render();
+        let run_line = report.lines().position(|l| l.ends_with(" run")).unwrap();
+        let child_line = report
+            .lines()
+            .position(|l| l.ends_with("  analyze"))
+            .unwrap();
+        assert!(
+            child_line > run_line,
+            "child listed under parent:\n{report}"
+        );
+    }
+
+    #[test]
+    fn exit_without_enter_is_ignored() {
+        let mut t = Timings::default();
+        t.exit();
+        assert!(t.records.is_empty());
+    }
+
+    #[test]
+    fn disabled_spans_record_nothing() {
+        // ENABLED is process-wide and no test enables it.
+        let _s = span("noop");
+        drop(_s);
+        assert!(take().records.is_empty());
+    }
+}
@@ -59,6 +59,12 @@ pub fn read_managed_section(content: &str) -> Option<Vec<String>> {
     }
 }
 
+/// The managed section as written, start and end markers included.
+pub fn managed_section(content: &str) -> Option<String> {
+    let (_, inner, _) = split_managed(content)?;
+    Some(format!("{MANAGED_START}{inner}{MANAGED_END}"))
+}
+
 /// Split content into (before_start_marker, between_markers, after_end_marker).
 fn split_managed(content: &str) -> Option<(String, String, String)> {
     let start_idx = content.find(MANAGED_START)?;
@@ -110,10 +116,218 @@ pub fn strip_managed_section(content: &str) -> String {
     s
 }
 
+/// Markdown heading texts of the user's content, in file order: the managed
+/// block and fenced code (where `# ...` is a shell comment) are skipped.
+pub fn headings(content: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_fence = false;
+    for line in strip_managed_section(content).lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if !(1..=6).contains(&level) {
+            continue;
+        }
+        let rest = &trimmed[level..];
+        if !rest.starts_with(' ') {
+            continue;
+        }
+        let text = rest.trim().trim_end_matches('#').trim();
+        if !text.is_empty() {
+            out.push(text.to_string());
+        }
+    }
+    out
+}
+
+/// Bullets longer than this read as essays, not rules.
+pub const MAX_RULE_CHARS: usize = 300;
+/// Claude Code warns about (and pays for) CLAUDE.md files beyond this size.
+pub const MAX_FILE_CHARS: usize = 40_000;
+
+/// A quality problem in a CLAUDE.md-style file. Advisory: projection writes
+/// regardless unless `[knowledge] strict_lint` is set.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct LintFinding {
+    /// "duplicate-bullet" | "long-rule" | "contradiction" | "file-length"
+    pub kind: String,
+    pub detail: String,
+}
+
+/// Lint a whole file — user content included, not just the managed block:
+/// a projected rule that duplicates or contradicts a hand-written bullet is
+/// exactly what this is for. Pure; line numbers are 1-based.
+pub fn lint(content: &str) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    let finding = |kind: &str, detail: String| LintFinding {
+        kind: kind.to_string(),
+        detail,
+    };
+    let bullets = bullets(content);
+
+    for (i, (la, a)) in bullets.iter().enumerate() {
+        let na = normalize_bullet(a);
+        for (lb, b) in bullets.iter().skip(i + 1) {
+            let nb = normalize_bullet(b);
+            let (ca, cb) = (na.chars().count(), nb.chars().count());
+            let max_len = ca.max(cb);
+            // Same length pre-filter as store lint: >10% apart can't reach 0.9.
+            if max_len > 0
+                && (ca.abs_diff(cb) as f64) / (max_len as f64) <= 0.1
+                && crate::util::normalized_similarity(&na, &nb) >= 0.9
+            {
+                findings.push(finding(
+                    "duplicate-bullet",
+                    format!("lines {la} and {lb} say the same thing: \"{}\"", preview(a)),
+                ));
+            } else if let Some(why) = contradiction(&na, &nb) {
+                findings.push(finding(
+                    "contradiction",
+                    format!("lines {la} and {lb} contradict ({why}): \"{}\" vs \"{}\"", preview(a), preview(b)),
+                ));
+            }
+        }
+    }
+    for (line, b) in &bullets {
+        let len = b.chars().count();
+        if len > MAX_RULE_CHARS {
+            findings.push(finding(
+                "long-rule",
+                format!("line {line} is {len} characters (> {MAX_RULE_CHARS}): \"{}\"", preview(b)),
+            ));
+        }
+    }
+    let total = content.chars().count();
+    if total > MAX_FILE_CHARS {
+        findings.push(finding(
+            "file-length",
+            format!("file is {total} characters (> {MAX_FILE_CHARS}) — every session pays for it"),
+        ));
+    }
+    findings
+}
+
+/// `- ` / `* ` bullet texts with their 1-based line numbers.
+fn bullets(content: &str) -> Vec<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let t = line.trim_start();
+            t.strip_prefix("- ")
+                .or_else(|| t.strip_prefix("* "))
+                .map(|rest| (i + 1, rest.trim()))
+        })
+        .filter(|(_, b)| !b.is_empty())
+        .collect()
+}
+
+/// The user's bullets (managed block excluded), normalized for matching:
+/// lowercased, whitespace collapsed, trailing `.`/`!`/`;` dropped.
+pub fn user_bullets(content: &str) -> std::collections::HashSet<String> {
+    bullets(&strip_managed_section(content))
+        .into_iter()
+        .map(|(_, b)| normalize_bullet(b))
+        .collect()
+}
+
+pub(crate) fn normalize_bullet(b: &str) -> String {
+    b.to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', '!', ';'])
+        .to_string()
+}
+
+/// `always X` vs `never X` / `don't X` / `do not X` on the same topic.
+fn contradiction(a: &str, b: &str) -> Option<&'static str> {
+    fn polarity(s: &str) -> Option<(bool, &str)> {
+        if let Some(rest) = s.strip_prefix("always ") {
+            return Some((true, rest));
+        }
+        ["never ", "don't ", "don’t ", "do not "]
+            .iter()
+            .find_map(|p| s.strip_prefix(p))
+            .map(|rest| (false, rest))
+    }
+    let (pa, ta) = polarity(a)?;
+    let (pb, tb) = polarity(b)?;
+    if pa == pb {
+        return None;
+    }
+    (ta == tb || crate::util::normalized_similarity(ta, tb) >= 0.85).then_some("always vs never")
+}
+
+fn preview(s: &str) -> String {
+    let short = crate::util::truncate_str(s, 60);
+    if short.len() < s.len() {
+        format!("{short}…")
+    } else {
+        short.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn kinds(content: &str) -> Vec<String> {
+        lint(content).into_iter().map(|f| f.kind).collect()
+    }
+
+    #[test]
+    fn headings_skip_the_managed_block_and_code() {
+        let content = "# Project\n\n## Build ##\n```sh\n# not a heading\n```\n#hashtag\n\
+                       ### Testing\n<!-- retro:managed:start -->\n## Retro\n<!-- retro:managed:end -->\n";
+        assert_eq!(headings(content), vec!["Project", "Build", "Testing"]);
+        assert!(headings("").is_empty());
+    }
+
+    #[test]
+    fn lint_clean_file_has_no_findings() {
+        let content = "# Me\n\n- Use uv for Python.\n- Always run clippy before pushing.\n\n<!-- retro:managed:start -->\n- Never commit to main.\n<!-- retro:managed:end -->\n";
+        assert!(lint(content).is_empty(), "{:?}", lint(content));
+        assert!(lint("").is_empty());
+    }
+
+    #[test]
+    fn lint_finds_duplicates_across_user_and_managed_content() {
+        let managed = update_claude_md_content("- Use uv for Python installs\n", &["use uv for python installs.".to_string()]);
+        let findings = lint(&managed);
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert_eq!(findings[0].kind, "duplicate-bullet");
+        assert!(findings[0].detail.starts_with("lines 1 and 6"), "{}", findings[0].detail);
+        // similar-but-different rules are not duplicates
+        assert!(kinds("- Use uv for Python installs\n- Use pip for Python installs\n").is_empty());
+    }
+
+    #[test]
+    fn lint_finds_always_never_contradictions() {
+        assert_eq!(
+            kinds("- Always use tabs for indentation\n- Never use tabs for indentation.\n"),
+            vec!["contradiction"]
+        );
+        assert_eq!(kinds("* Always squash merge\n* Don't squash merge\n"), vec!["contradiction"]);
+        // different topics, or the same polarity, are fine
+        assert!(kinds("- Always run tests\n- Never push to main\n").is_empty());
+        assert!(kinds("- Never use unwrap\n- Never use expect\n").is_empty());
+    }
+
+    #[test]
+    fn lint_flags_long_rules_and_long_files() {
+        let long_rule = format!("- {}\n", "word ".repeat(70));
+        assert_eq!(kinds(&long_rule), vec!["long-rule"]);
+        let big = "plain prose line, not a bullet\n".repeat(MAX_FILE_CHARS / 20);
+        assert_eq!(kinds(&big), vec!["file-length"]);
+    }
+
     #[test]
     fn test_build_managed_section() {
         let rules = vec![
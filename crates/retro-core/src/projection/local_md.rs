@@ -5,7 +5,11 @@
 use std::path::{Path, PathBuf};
 
 use crate::errors::CoreError;
+use crate::logging;
 use crate::projection::claude_md::{read_managed_section, update_claude_md_content};
+use crate::projection::integrity;
+use crate::projection::writes;
+use crate::projection::split::split_rules;
 use crate::store::{LoadResult, Node, NodeType, Scope, Store};
 
 #[cfg(test)]
@@ -26,6 +30,8 @@ mod tests {
             created: today,
             updated: today,
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: body.to_string(),
         }
     }
@@ -106,6 +112,51 @@ mod tests {
         assert_eq!(exclude.matches("CLAUDE.local.md").count(), 1);
     }
 
+    #[test]
+    fn section_tags_only_name_headings_the_project_has() {
+        let store_tmp = TempDir::new().unwrap();
+        let store = Store::open(store_tmp.path());
+        store.ensure_layout().unwrap();
+        let p = Scope::Project("p".to_string());
+        let mut tagged = node("a-tagged", p.clone(), NodeType::Rule, 0.9, "Run the smoke tests");
+        tagged.section = Some("testing".to_string());
+        let mut stale = node("b-stale", p.clone(), NodeType::Rule, 0.9, "Use the staging db");
+        stale.section = Some("Deployment".to_string());
+        store.write_node(&tagged).unwrap();
+        store.write_node(&stale).unwrap();
+
+        let proj = TempDir::new().unwrap();
+        std::fs::write(proj.path().join("CLAUDE.md"), "# App\n\n## Testing\n\n- cargo test\n")
+            .unwrap();
+        assert_eq!(project_headings(proj.path()), vec!["App", "Testing"]);
+        project_local_md(&store, "p", proj.path(), 0.7).unwrap();
+        let content = std::fs::read_to_string(proj.path().join("CLAUDE.local.md")).unwrap();
+        assert!(content.contains("- [Testing] Run the smoke tests\n"), "{content}");
+        assert!(content.contains("- Use the staging db\n"), "unknown section: untagged");
+        assert_eq!(preview_local_md(&store, "p", proj.path(), 0.7).unwrap(), content);
+
+        // The heading goes away: the tag goes with it on the next projection.
+        std::fs::write(proj.path().join("CLAUDE.md"), "# App\n").unwrap();
+        project_local_md(&store, "p", proj.path(), 0.7).unwrap();
+        let content = std::fs::read_to_string(proj.path().join("CLAUDE.local.md")).unwrap();
+        assert!(content.contains("- Run the smoke tests\n"), "{content}");
+    }
+
+    #[test]
+    fn project_local_md_refuses_an_opted_out_repo() {
+        let store_tmp = TempDir::new().unwrap();
+        let store = Store::open(store_tmp.path());
+        store.ensure_layout().unwrap();
+        let p = Scope::Project("p".to_string());
+        store.write_node(&node("r", p, NodeType::Rule, 0.9, "the rule")).unwrap();
+        let proj = TempDir::new().unwrap();
+        std::fs::write(proj.path().join(".retro.toml"), "[retro]\nenabled = false\n").unwrap();
+
+        let err = project_local_md(&store, "p", proj.path(), 0.7).unwrap_err().to_string();
+        assert!(err.contains(".retro.toml opts the repository out of retro"), "{err}");
+        assert!(!proj.path().join("CLAUDE.local.md").exists());
+    }
+
     #[test]
     fn project_local_md_with_no_rules_removes_managed_content() {
         let store_tmp = TempDir::new().unwrap();
@@ -199,6 +250,10 @@ mod tests {
             std::fs::metadata(&md).unwrap().modified().unwrap(),
             mtime_after_first
         );
+        let written = writes::load(store_tmp.path());
+        assert_eq!(written.len(), 1, "only the real write is logged");
+        assert_eq!(written[0].path, md.display().to_string());
+        assert_eq!(written[0].bytes, std::fs::read_to_string(&md).unwrap().len());
     }
 
     #[test]
@@ -228,6 +283,48 @@ mod tests {
         assert!(content.contains("- Always do X. **Why:** because Y. **How to apply:** do Z."));
     }
 
+    #[test]
+    fn multi_rule_body_projects_one_bullet_per_rule() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        for (id, body) in [
+            ("python-tooling", "- Use uv for Python installs.\n- Run ruff before committing."),
+            ("single", "Run clippy and fix every warning before pushing."),
+        ] {
+            store
+                .write_node(&node(id, Scope::Global, NodeType::Rule, 0.9, body))
+                .unwrap();
+        }
+        let rules = projectable_rules(&store, &Scope::Global, 0.7).unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                "Use uv for Python installs.".to_string(),
+                "Run ruff before committing.".to_string(),
+                "Run clippy and fix every warning before pushing.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn preview_matches_what_projection_writes() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let claude_tmp = TempDir::new().unwrap();
+        let md = claude_tmp.path().join("CLAUDE.md");
+        assert_eq!(preview_global_md(&store, &md, 0.7).unwrap(), "", "nothing to create");
+        std::fs::write(&md, "# Mine\n\n- Use uv for Python.\n").unwrap();
+        store
+            .write_node(&node("r", Scope::Global, NodeType::Rule, 0.9, "Run clippy before pushing."))
+            .unwrap();
+        let previewed = preview_global_md(&store, &md, 0.7).unwrap();
+        assert_eq!(std::fs::read_to_string(&md).unwrap(), "# Mine\n\n- Use uv for Python.\n");
+        project_global_md(&store, &md, 0.7, None).unwrap();
+        assert_eq!(std::fs::read_to_string(&md).unwrap(), previewed);
+    }
+
     #[test]
     fn git_exclude_works_in_worktrees() {
         let store_tmp = TempDir::new().unwrap();
@@ -325,19 +422,28 @@ mod tests {
 }
 
 /// Bodies of projectable nodes for a scope: active, non-memory, confidence >= threshold.
-/// Ordered by node id for stable output (idempotent regeneration).
+/// Ordered by node id for stable output (idempotent regeneration). Untagged:
+/// `[Section]` tags depend on the project's files, see [`project_local_md`].
 pub fn projectable_rules(
     store: &Store,
     scope: &Scope,
     threshold: f64,
 ) -> Result<Vec<String>, CoreError> {
     let loaded = store.load_all()?;
-    Ok(projectable_from(&loaded.nodes, scope, threshold))
+    Ok(projectable_from(&loaded.nodes, scope, threshold, &[]))
 }
 
 /// Pure filter over an already-loaded node set, so callers that also need the
-/// full `LoadResult` (for the empty-wipe guard) don't load twice.
-fn projectable_from(nodes: &[(PathBuf, Node)], scope: &Scope, threshold: f64) -> Vec<String> {
+/// full `LoadResult` (for the empty-wipe guard) don't load twice. `headings`
+/// are the target project's current headings ([`project_headings`]): a node
+/// whose `section` names one projects as `[Heading] rule`. Sections the files
+/// no longer have are ignored, so a renamed heading just drops the tag.
+fn projectable_from(
+    nodes: &[(PathBuf, Node)],
+    scope: &Scope,
+    threshold: f64,
+    headings: &[String],
+) -> Vec<String> {
     let mut ns: Vec<&Node> = nodes
         .iter()
         .map(|(_, n)| n)
@@ -347,7 +453,47 @@ fn projectable_from(nodes: &[(PathBuf, Node)], scope: &Scope, threshold: f64) ->
         .filter(|n| &n.scope == scope)
         .collect();
     ns.sort_by(|a, b| a.id.cmp(&b.id));
-    ns.into_iter().map(|n| flatten_body(&n.body)).collect()
+    ns.into_iter()
+        .flat_map(|n| {
+            let tag = n.section.as_deref().and_then(|s| matching_heading(s, headings));
+            projected_bullets(&n.body).into_iter().map(move |b| match tag {
+                Some(h) => format!("[{h}] {b}"),
+                None => b,
+            })
+        })
+        .collect()
+}
+
+/// The heading `section` refers to, as the file spells it (case and
+/// surrounding whitespace don't matter).
+fn matching_heading<'a>(section: &str, headings: &'a [String]) -> Option<&'a str> {
+    let section = section.trim();
+    headings
+        .iter()
+        .find(|h| h.eq_ignore_ascii_case(section))
+        .map(String::as_str)
+}
+
+/// Headings of a project's hand-written CLAUDE.md and CLAUDE.local.md (the
+/// managed block excluded), deduplicated in file order: what a project rule's
+/// `section` may name.
+pub fn project_headings(project_root: &Path) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for file in ["CLAUDE.md", "CLAUDE.local.md"] {
+        let content = std::fs::read_to_string(project_root.join(file)).unwrap_or_default();
+        for h in crate::projection::claude_md::headings(&content) {
+            if !out.iter().any(|o| o.eq_ignore_ascii_case(&h)) {
+                out.push(h);
+            }
+        }
+    }
+    out
+}
+
+/// The managed-block bullet(s) a node body projects as: one per rule when
+/// the body holds several (see [`split_rules`]), each flattened to one line.
+pub fn projected_bullets(body: &str) -> Vec<String> {
+    split_rules(body).iter().map(|r| flatten_body(r)).collect()
 }
 
 /// Managed-block bullets are single-line (the v2-compatible, renderer-safe
@@ -370,7 +516,7 @@ pub fn project_global_md(
     backup_dir: Option<&Path>,
 ) -> Result<usize, CoreError> {
     let loaded = store.load_all()?;
-    let rules = projectable_from(&loaded.nodes, &Scope::Global, threshold);
+    let rules = projectable_from(&loaded.nodes, &Scope::Global, threshold, &[]);
     if rules.is_empty() {
         // Parity with project_local_md: never create an empty shell on a
         // machine that has no CLAUDE.md and no rules yet.
@@ -379,7 +525,14 @@ pub fn project_global_md(
         }
         guard_against_empty_wipe(&loaded, claude_md_path)?;
     }
-    write_managed(claude_md_path, &rules, backup_dir)?;
+    write_managed(store.root(), claude_md_path, &rules, backup_dir)?;
+    log::debug!(
+        target: logging::PROJECTION,
+        "{} rule(s) -> {}",
+        rules.len(),
+        claude_md_path.display()
+    );
+    integrity::record(store.root(), claude_md_path, &rules)?;
     Ok(rules.len())
 }
 
@@ -405,14 +558,24 @@ fn guard_against_empty_wipe(loaded: &LoadResult, path: &Path) -> Result<(), Core
 
 /// Regenerate <project>/CLAUDE.local.md and ensure it is ignored via
 /// .git/info/exclude (personal ignore file — the team's .gitignore is never touched).
+/// Refuses a repo that opted out of retro (`.retro-ignore`, see
+/// [`crate::store::projects::opt_out_marker`]).
 pub fn project_local_md(
     store: &Store,
     slug: &str,
     project_root: &Path,
     threshold: f64,
 ) -> Result<usize, CoreError> {
+    if let Some(marker) = crate::store::projects::opt_out_marker(&project_root.to_string_lossy()) {
+        return Err(CoreError::Config(format!(
+            "{slug}: not projected — {} opts the repository out of retro",
+            marker.display()
+        )));
+    }
     let loaded = store.load_all()?;
-    let rules = projectable_from(&loaded.nodes, &Scope::Project(slug.to_string()), threshold);
+    let headings = project_headings(project_root);
+    let scope = Scope::Project(slug.to_string());
+    let rules = projectable_from(&loaded.nodes, &scope, threshold, &headings);
     let path = project_root.join("CLAUDE.local.md");
     if rules.is_empty() {
         // No rules and no existing file: don't create an empty shell.
@@ -421,19 +584,63 @@ pub fn project_local_md(
         }
         guard_against_empty_wipe(&loaded, &path)?;
     }
-    write_managed(&path, &rules, None)?;
+    write_managed(store.root(), &path, &rules, None)?;
+    log::debug!(target: logging::PROJECTION, "{slug}: {} rule(s) -> {}", rules.len(), path.display());
+    integrity::record(store.root(), &path, &rules)?;
     ensure_git_exclude(project_root)?;
     Ok(rules.len())
 }
 
+/// The content `project_global_md` would leave in `claude_md_path`, without
+/// writing anything (empty when it would not create the file). Same
+/// regeneration as the writer, so a preview can't diverge from the write.
+pub fn preview_global_md(
+    store: &Store,
+    claude_md_path: &Path,
+    threshold: f64,
+) -> Result<String, CoreError> {
+    let loaded = store.load_all()?;
+    let rules = projectable_from(&loaded.nodes, &Scope::Global, threshold, &[]);
+    Ok(preview(claude_md_path, &rules))
+}
+
+/// [`preview_global_md`] for a project's CLAUDE.local.md.
+pub fn preview_local_md(
+    store: &Store,
+    slug: &str,
+    project_root: &Path,
+    threshold: f64,
+) -> Result<String, CoreError> {
+    let loaded = store.load_all()?;
+    let headings = project_headings(project_root);
+    let scope = Scope::Project(slug.to_string());
+    let rules = projectable_from(&loaded.nodes, &scope, threshold, &headings);
+    Ok(preview(&project_root.join("CLAUDE.local.md"), &rules))
+}
+
+fn preview(path: &Path, rules: &[String]) -> String {
+    if rules.is_empty() && !path.exists() {
+        return String::new();
+    }
+    regenerate(path, rules).1
+}
+
+/// (current content, regenerated content) — the single regeneration step
+/// shared by the writer and the previews.
+fn regenerate(path: &Path, rules: &[String]) -> (String, String) {
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let updated = update_claude_md_content(&existing, rules);
+    (existing, updated)
+}
+
 fn write_managed(
+    store_root: &Path,
     path: &Path,
     rules: &[String],
     backup_dir: Option<&Path>,
 ) -> Result<(), CoreError> {
     let io = |e: std::io::Error| CoreError::Io(e.to_string());
-    let existing = std::fs::read_to_string(path).unwrap_or_default();
-    let updated = update_claude_md_content(&existing, rules);
+    let (existing, updated) = regenerate(path, rules);
     // Idempotent regeneration: unchanged content means no write, no backup —
     // hook-triggered runs must not churn the user's files.
     if updated == existing {
@@ -446,8 +653,10 @@ fn write_managed(
     }
     // Atomic swap: Claude Code may read this file mid-run.
     let tmp = path.with_extension("md.retro-tmp");
-    std::fs::write(&tmp, updated).map_err(io)?;
-    std::fs::rename(&tmp, path).map_err(io)
+    std::fs::write(&tmp, &updated).map_err(io)?;
+    std::fs::rename(&tmp, path).map_err(io)?;
+    writes::record(store_root, path, &updated, writes::snapshots_enabled(), chrono::Utc::now())?;
+    Ok(())
 }
 
 /// Append CLAUDE.local.md to the repo's personal ignore file
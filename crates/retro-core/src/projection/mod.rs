@@ -1,2 +1,6 @@
 pub mod claude_md;
+pub mod integrity;
 pub mod local_md;
+pub mod reproject;
+pub mod split;
+pub mod writes;
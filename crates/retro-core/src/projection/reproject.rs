@@ -0,0 +1,188 @@
+//! Reprojection after a write outside the runner (`retro merge`, dashboard
+//! edits, threshold changes, migrate). Each scope is regenerated at its own
+//! location: global at `<claude_dir>/CLAUDE.md`, a project at the path
+//! registered for that project's slug — never relative to the caller's cwd.
+//! A project whose registered path is gone (repo moved or deleted) is
+//! reported and skipped, so one stale path can't block the others; so is a
+//! project whose repo opted out (`.retro-ignore`).
+
+use std::path::{Path, PathBuf};
+
+use super::local_md;
+use crate::config::Config;
+use crate::errors::CoreError;
+use crate::store::{Scope, Store, projects, projects::PathMap};
+
+#[derive(Debug, Default)]
+pub struct ReprojectReport {
+    /// Rules written to the global CLAUDE.md (None when global wasn't in scope).
+    pub global_rules: Option<usize>,
+    /// `(slug, project root, rules written)` per projected project.
+    pub projects: Vec<(String, PathBuf, usize)>,
+    /// `(slug, registered path)` for projects whose path no longer exists.
+    pub missing: Vec<(String, String)>,
+    /// Project slugs with no registered path on this machine.
+    pub unregistered: Vec<String>,
+    /// `(slug, marker)` for projects whose repo opted out of retro.
+    pub opted_out: Vec<(String, PathBuf)>,
+}
+
+impl ReprojectReport {
+    /// One line per skipped project, for CLI output and health records.
+    pub fn skipped(&self) -> Vec<String> {
+        let missing = self
+            .missing
+            .iter()
+            .map(|(slug, path)| {
+                format!(
+                    "{slug}: {path} no longer exists — skipped (moved? `retro relocate {slug} <new-path>`)"
+                )
+            });
+        let unregistered = self
+            .unregistered
+            .iter()
+            .map(|slug| format!("{slug}: no path registered on this machine — skipped"));
+        let opted_out = self.opted_out.iter().map(|(slug, marker)| {
+            format!("{slug}: {} opts the repository out of retro — skipped", marker.display())
+        });
+        missing.chain(unregistered).chain(opted_out).collect()
+    }
+}
+
+/// Reproject `scopes` (every registered project plus global when None).
+/// Global projection backs up into `backups` like the runner does.
+pub fn reproject(
+    store: &Store,
+    config: &Config,
+    scopes: Option<&[Scope]>,
+    backups: Option<&Path>,
+) -> Result<ReprojectReport, CoreError> {
+    let threshold = config.knowledge.confidence_threshold;
+    super::writes::set_snapshots(config.log.store_apply_snapshots);
+    let map = PathMap::load(store.root())?;
+    let scopes: Vec<Scope> = match scopes {
+        Some(s) => s.to_vec(),
+        None => std::iter::once(Scope::Global)
+            .chain(map.paths.keys().map(|slug| Scope::Project(slug.clone())))
+            .collect(),
+    };
+    let mut report = ReprojectReport::default();
+    for scope in scopes {
+        match scope {
+            Scope::Global => {
+                let md = config.claude_dir().join("CLAUDE.md");
+                report.global_rules =
+                    Some(local_md::project_global_md(store, &md, threshold, backups)?);
+            }
+            Scope::Project(slug) => match map.paths.get(&slug) {
+                None => report.unregistered.push(slug),
+                Some(path) if !Path::new(path).is_dir() => {
+                    report.missing.push((slug, path.clone()));
+                }
+                Some(path) => match projects::opt_out_marker(path) {
+                    Some(marker) => report.opted_out.push((slug, marker)),
+                    None => {
+                        let root = PathBuf::from(path);
+                        let n = local_md::project_local_md(store, &slug, &root, threshold)?;
+                        report.projects.push((slug, root, n));
+                    }
+                },
+            },
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{Node, NodeType};
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn rule(id: &str, scope: Scope, body: &str) -> Node {
+        let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        Node {
+            id: id.to_string(),
+            scope,
+            node_type: NodeType::Rule,
+            confidence: 0.9,
+            sources: vec![],
+            created: date,
+            updated: date,
+            invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn each_project_projects_to_its_own_path_and_missing_paths_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let claude = TempDir::new().unwrap();
+        let (alpha, beta) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let mut map = PathMap::default();
+        for (slug, dir) in [("alpha", &alpha), ("beta", &beta)] {
+            let scope = Scope::Project(slug.to_string());
+            store.write_node(&rule("r", scope, &format!("Rule for {slug}."))).unwrap();
+            map.paths.insert(slug.to_string(), dir.path().display().to_string());
+        }
+        store.write_node(&rule("r", Scope::Project("gone".into()), "Orphan.")).unwrap();
+        map.paths.insert("gone".to_string(), tmp.path().join("moved").display().to_string());
+        map.save(tmp.path()).unwrap();
+        let mut config = Config::default();
+        config.paths.claude_dir = claude.path().display().to_string();
+
+        let scopes = [
+            Scope::Project("beta".into()),
+            Scope::Project("gone".into()),
+            Scope::Project("unknown".into()),
+            Scope::Project("alpha".into()),
+        ];
+        let report = reproject(&store, &config, Some(&scopes), None).unwrap();
+        assert_eq!(report.global_rules, None);
+        assert_eq!(report.projects.len(), 2);
+        for (slug, dir) in [("alpha", &alpha), ("beta", &beta)] {
+            let md = std::fs::read_to_string(dir.path().join("CLAUDE.local.md")).unwrap();
+            assert!(md.contains(&format!("Rule for {slug}.")), "{slug}: {md}");
+            let other = if slug == "alpha" { "beta" } else { "alpha" };
+            assert!(!md.contains(&format!("Rule for {other}.")), "{slug} got {other}'s rule");
+        }
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.unregistered, vec!["unknown".to_string()]);
+        assert_eq!(report.skipped().len(), 2);
+        assert!(report.skipped()[0].contains("`retro relocate gone <new-path>`"));
+        assert!(!tmp.path().join("moved").exists(), "never recreates a missing root");
+
+        let all = reproject(&store, &config, None, None).unwrap();
+        assert_eq!(all.global_rules, Some(0));
+        assert_eq!(all.projects.len(), 2);
+        assert_eq!(all.missing.len(), 1);
+    }
+
+    #[test]
+    fn opted_out_repos_are_skipped_with_the_marker_named() {
+        let tmp = TempDir::new().unwrap();
+        let claude = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        store.write_node(&rule("r", Scope::Project("oss".into()), "Rule.")).unwrap();
+        let mut map = PathMap::default();
+        map.paths.insert("oss".to_string(), repo.path().display().to_string());
+        map.save(tmp.path()).unwrap();
+        std::fs::write(repo.path().join(projects::OPT_OUT_FILE), "").unwrap();
+        let mut config = Config::default();
+        config.paths.claude_dir = claude.path().display().to_string();
+
+        let report = reproject(&store, &config, None, None).unwrap();
+        assert!(report.projects.is_empty());
+        assert!(!repo.path().join("CLAUDE.local.md").exists());
+        let skipped = report.skipped();
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].contains(".retro-ignore opts the repository out"), "{skipped:?}");
+    }
+}
@@ -0,0 +1,180 @@
+//! Multi-rule body splitting for projection: a node whose body is really two
+//! or three rules ("- Use uv.\n- Run ruff.", "Always X. Never Y.") projects
+//! as one managed bullet per rule instead of one run-on bullet.
+//!
+//! Conservative on purpose — splitting a single rule mangles it, leaving a
+//! compound rule whole only reads a little worse. A body is split only when
+//! EVERY piece reads as its own imperative rule; "and" is never a boundary,
+//! and annotated bodies (`**Why:**`, `**How to apply:**`) stay whole because
+//! the annotation belongs to the rule as a whole.
+
+/// Leading words that mark a piece as an imperative rule.
+const IMPERATIVES: &[&str] = &[
+    "always", "never", "avoid", "prefer", "use", "don't", "dont", "do", "run", "keep", "write",
+    "add", "check", "make", "put", "ensure", "include", "remove", "call", "commit", "test",
+    "read", "stop", "wrap", "return", "pass", "set", "place", "name", "follow", "skip",
+];
+
+/// A piece shorter than this is a fragment, not a rule ("Do it.").
+const MIN_WORDS: usize = 3;
+
+/// Split `body` into the rules it contains. Returns `vec![body]` unless it
+/// clearly holds several: a list of bullets with no lead-in line, one rule
+/// per line, or one rule per sentence — each piece imperative.
+pub fn split_rules(body: &str) -> Vec<String> {
+    let whole = vec![body.to_string()];
+    let lines: Vec<&str> = body.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    if lines
+        .iter()
+        .any(|l| l.contains("**Why:**") || l.contains("**How to apply:**"))
+    {
+        return whole;
+    }
+
+    let pieces = if lines.iter().any(|l| strip_bullet(l).is_some()) {
+        match bullet_items(&lines) {
+            Some(items) => items,
+            None => return whole,
+        }
+    } else if lines.len() > 1 {
+        lines.iter().map(|l| l.to_string()).collect()
+    } else {
+        sentences(lines.first().copied().unwrap_or_default())
+    };
+
+    if pieces.len() > 1 && pieces.iter().all(|p| is_imperative_rule(p)) {
+        pieces
+    } else {
+        whole
+    }
+}
+
+/// Group bullet lines (continuation lines join the preceding bullet). None
+/// when the body has a lead-in line — "When writing Python:" scopes every
+/// bullet below it, so the items can't stand alone.
+fn bullet_items(lines: &[&str]) -> Option<Vec<String>> {
+    let mut items: Vec<String> = Vec::new();
+    for line in lines {
+        match strip_bullet(line) {
+            Some(item) => items.push(item.to_string()),
+            None => {
+                let last = items.last_mut()?;
+                last.push('\n');
+                last.push_str(line);
+            }
+        }
+    }
+    Some(items)
+}
+
+/// `- x`, `* x`, `+ x`, `1. x`, `1) x` → `x`.
+fn strip_bullet(line: &str) -> Option<&str> {
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return Some(rest.trim_start());
+        }
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let rest = &line[digits..];
+    rest.strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))
+        .map(str::trim_start)
+}
+
+/// Sentence split on `.`/`!` followed by whitespace (keeps `main.rs` and
+/// `foo.bar()` intact). Terminators stay with their sentence.
+fn sentences(line: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        if (ch == '.' || ch == '!') && chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+            let end = i + ch.len_utf8();
+            out.push(line[start..end].trim().to_string());
+            start = end;
+        }
+    }
+    out.push(line[start..].trim().to_string());
+    out.retain(|s| !s.is_empty());
+    out
+}
+
+fn is_imperative_rule(piece: &str) -> bool {
+    let words: Vec<&str> = piece.split_whitespace().collect();
+    if words.len() < MIN_WORDS {
+        return false;
+    }
+    let first = words[0]
+        .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’')
+        .replace('’', "'")
+        .to_lowercase();
+    IMPERATIVES.contains(&first.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Labeled corpus: (body, expected pieces).
+    const SPLIT: &[(&str, &[&str])] = &[
+        (
+            "- Use uv for Python installs.\n- Run ruff before committing.",
+            &["Use uv for Python installs.", "Run ruff before committing."],
+        ),
+        (
+            "1. Always run clippy before pushing\n2. Never commit directly to main",
+            &["Always run clippy before pushing", "Never commit directly to main"],
+        ),
+        (
+            "Always run clippy before pushing.\nNever commit directly to main.",
+            &["Always run clippy before pushing.", "Never commit directly to main."],
+        ),
+        (
+            "Always run clippy before pushing. Never use unwrap in library code.",
+            &["Always run clippy before pushing.", "Never use unwrap in library code."],
+        ),
+        (
+            "Use uv for installs. Prefer ruff over flake8. Don’t install packages globally.",
+            &[
+                "Use uv for installs.",
+                "Prefer ruff over flake8.",
+                "Don’t install packages globally.",
+            ],
+        ),
+        (
+            "- Use uv for installs\n  (never plain pip)\n- Run tests with pytest -q",
+            &["Use uv for installs\n(never plain pip)", "Run tests with pytest -q"],
+        ),
+    ];
+
+    /// Bodies that must project as a single bullet.
+    const WHOLE: &[&str] = &[
+        "Run clippy and fix every warning before pushing.",
+        "Use uv and ruff for all Python work, and pin versions in pyproject.toml.",
+        "Use uv. It is much faster than pip.",
+        "Always do X.\n\n**Why:** because Y.\n**How to apply:** do Z.",
+        "When writing Python:\n- use uv for installs\n- use ruff for linting",
+        "Prefer small commits, e.g. one logical change per commit.",
+        "Call `config.save()` after editing main.rs settings.",
+        "- Use uv for Python installs.",
+        "Never push. Ask first.",
+        "The build uses cargo-nextest.\nRun it with `cargo nextest run`.",
+    ];
+
+    #[test]
+    fn splits_multi_rule_bodies() {
+        for (body, expected) in SPLIT {
+            assert_eq!(split_rules(body), *expected, "{body:?}");
+        }
+    }
+
+    #[test]
+    fn keeps_single_rules_whole() {
+        for body in WHOLE {
+            assert_eq!(split_rules(body), vec![body.to_string()], "{body:?}");
+        }
+    }
+}
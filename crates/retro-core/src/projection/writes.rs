@@ -0,0 +1,373 @@
+//! Write log for projected files at `<store>/state/projection_writes.jsonl`:
+//! one record per file retro actually rewrote, with the content's digest
+//! and size. When a CLAUDE.md looks wrong, `retro log` tells what retro
+//! wrote and when, as opposed to what a later edit changed.
+//!
+//! With `[log] store_apply_snapshots`, the full written content is also kept
+//! under `<store>/snapshots/<digest>.md` (content-addressed: identical
+//! renders share one file), so `retro log --show-files` can diff it against
+//! the file as it is now. Records past retention are swept on the next
+//! write, together with snapshots no remaining record references.
+//!
+//! Each record also keeps the git state it was written against — the store
+//! commit the rules came from and, for a file inside a repository, its
+//! branch and HEAD — so a rule that vanished can be traced to the run and
+//! checkout that dropped it. Collecting it is best-effort: a field git
+//! can't answer is left out, never an error.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::CoreError;
+
+/// Records older than this are swept on the next write.
+const RETENTION_DAYS: i64 = 30;
+
+/// Process-wide `[log] store_apply_snapshots`, set by whoever loaded the
+/// config before projecting (the runner, reprojection).
+static SNAPSHOTS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_snapshots(enabled: bool) {
+    SNAPSHOTS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn snapshots_enabled() -> bool {
+    SNAPSHOTS.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WriteRecord {
+    /// RFC3339 (UTC) time of the write.
+    pub at: String,
+    pub path: String,
+    /// FNV-1a of the written content (also the snapshot's file name).
+    pub digest: String,
+    pub bytes: usize,
+    /// A snapshot of the content was stored.
+    #[serde(default)]
+    pub snapshot: bool,
+    /// Absent in records written before it was collected.
+    #[serde(default, skip_serializing_if = "GitContext::is_empty")]
+    pub git: GitContext,
+}
+
+/// Git state a write happened against.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GitContext {
+    /// Store HEAD: the commit whose nodes were projected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_commit: Option<String>,
+    /// Branch checked out in the written file's repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// HEAD of the written file's repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+}
+
+impl GitContext {
+    /// Collect what git knows for a write of `path` from the store at
+    /// `store_root`.
+    pub fn collect(store_root: &Path, path: &Path) -> Self {
+        use crate::store::git::{current_branch, head_commit};
+
+        let dir = path.parent().filter(|d| d.is_dir());
+        GitContext {
+            store_commit: head_commit(store_root),
+            branch: dir.and_then(current_branch),
+            head: dir.and_then(head_commit),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == GitContext::default()
+    }
+}
+
+fn log_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("projection_writes.jsonl")
+}
+
+fn snapshots_dir(store_root: &Path) -> PathBuf {
+    store_root.join("snapshots")
+}
+
+/// Where the snapshot for `digest` lives.
+pub fn snapshot_path(store_root: &Path, digest: &str) -> PathBuf {
+    snapshots_dir(store_root).join(format!("{digest}.md"))
+}
+
+/// All records, oldest first. Unreadable lines are skipped.
+pub fn load(store_root: &Path) -> Vec<WriteRecord> {
+    std::fs::read_to_string(log_path(store_root))
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|l| serde_json::from_str(l).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Record that `content` was written to `path`, storing a snapshot when
+/// `snapshot` is set. Sweeps records past retention and unreferenced
+/// snapshots.
+pub fn record(
+    store_root: &Path,
+    path: &Path,
+    content: &str,
+    snapshot: bool,
+    now: DateTime<Utc>,
+) -> Result<WriteRecord, CoreError> {
+    let io = |e: std::io::Error| CoreError::Io(e.to_string());
+    let digest = super::integrity::checksum(content);
+    if snapshot {
+        let file = snapshot_path(store_root, &digest);
+        if !file.exists() {
+            std::fs::create_dir_all(snapshots_dir(store_root)).map_err(io)?;
+            std::fs::write(&file, content).map_err(io)?;
+        }
+    }
+    let entry = WriteRecord {
+        at: now.to_rfc3339(),
+        path: path.display().to_string(),
+        digest,
+        bytes: content.len(),
+        snapshot,
+        git: GitContext::collect(store_root, path),
+    };
+    let cutoff = now - chrono::Duration::days(RETENTION_DAYS);
+    let mut records: Vec<WriteRecord> = load(store_root)
+        .into_iter()
+        .filter(|r| parse_at(r).is_some_and(|at| at >= cutoff))
+        .collect();
+    records.push(entry.clone());
+    let mut out = String::new();
+    for r in &records {
+        out.push_str(&serde_json::to_string(r).map_err(|e| CoreError::Parse(e.to_string()))?);
+        out.push('\n');
+    }
+    let file = log_path(store_root);
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent).map_err(io)?;
+    }
+    let tmp = file.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, out).map_err(io)?;
+    std::fs::rename(&tmp, &file).map_err(io)?;
+    sweep_snapshots(store_root, &records);
+    Ok(entry)
+}
+
+/// Delete snapshot files no record references. Best-effort.
+fn sweep_snapshots(store_root: &Path, records: &[WriteRecord]) {
+    let keep: BTreeSet<String> = records
+        .iter()
+        .filter(|r| r.snapshot)
+        .map(|r| format!("{}.md", r.digest))
+        .collect();
+    let Ok(entries) = std::fs::read_dir(snapshots_dir(store_root)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !keep.contains(&entry.file_name().to_string_lossy().into_owned()) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn parse_at(record: &WriteRecord) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&record.at)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// The stored content of `record`, when it has a snapshot.
+pub fn snapshot(store_root: &Path, record: &WriteRecord) -> Option<String> {
+    if !record.snapshot {
+        return None;
+    }
+    std::fs::read_to_string(snapshot_path(store_root, &record.digest)).ok()
+}
+
+/// How a recorded file compares to what is on disk now.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileState {
+    /// Byte-for-byte what retro wrote.
+    Unchanged,
+    /// Rewritten since: by a later projection or by hand.
+    Changed { digest: String, bytes: usize },
+    Missing,
+}
+
+pub fn current_state(record: &WriteRecord) -> FileState {
+    match std::fs::read_to_string(&record.path) {
+        Err(_) => FileState::Missing,
+        Ok(content) => {
+            let digest = super::integrity::checksum(&content);
+            if digest == record.digest {
+                FileState::Unchanged
+            } else {
+                FileState::Changed {
+                    digest,
+                    bytes: content.len(),
+                }
+            }
+        }
+    }
+}
+
+/// One line of a [`line_diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff from `old` to `new` (longest common subsequence). Managed files
+/// are short, so the quadratic table is fine.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    // lcs[i][j]: common lines of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn records_digest_and_size_and_compares_with_disk() {
+        let store = TempDir::new().unwrap();
+        let md = store.path().join("CLAUDE.md");
+        std::fs::write(&md, "- rule\n").unwrap();
+        let rec = record(store.path(), &md, "- rule\n", false, Utc::now()).unwrap();
+        assert_eq!(rec.bytes, 7);
+        assert_eq!(rec.digest, crate::projection::integrity::checksum("- rule\n"));
+        assert_eq!(load(store.path()), vec![rec.clone()]);
+        assert_eq!(snapshot(store.path(), &rec), None, "snapshots off");
+        assert!(!store.path().join("snapshots").exists());
+
+        assert_eq!(current_state(&rec), FileState::Unchanged);
+        std::fs::write(&md, "- rule\n- edited\n").unwrap();
+        assert!(matches!(current_state(&rec), FileState::Changed { bytes: 16, .. }));
+        std::fs::remove_file(&md).unwrap();
+        assert_eq!(current_state(&rec), FileState::Missing);
+    }
+
+    #[test]
+    fn snapshots_are_content_addressed_and_swept_with_their_records() {
+        let store = TempDir::new().unwrap();
+        let (a, b) = (store.path().join("a.md"), store.path().join("b.md"));
+        let old = record(store.path(), &a, "same\n", true, at("2026-06-01T00:00:00Z")).unwrap();
+        let path = snapshot_path(store.path(), &old.digest);
+        assert_eq!(path, store.path().join(format!("snapshots/{}.md", old.digest)));
+        assert_eq!(snapshot(store.path(), &old).as_deref(), Some("same\n"));
+
+        // Same content elsewhere: one file. The June record ages out, but
+        // the snapshot stays while the new record needs it.
+        let now = at("2026-07-06T00:00:00Z");
+        let shared = record(store.path(), &b, "same\n", true, now).unwrap();
+        assert_eq!(shared.digest, old.digest);
+        assert_eq!(load(store.path()), vec![shared.clone()]);
+        assert!(path.exists());
+
+        record(store.path(), &b, "other\n", false, at("2026-08-10T00:00:00Z")).unwrap();
+        assert!(!path.exists(), "unreferenced snapshot swept");
+        assert_eq!(std::fs::read_dir(store.path().join("snapshots")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn line_diff_marks_added_and_removed_lines() {
+        let diff = line_diff("# Mine\n- a\n- b\n", "# Mine\n- b\n- c\n");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("# Mine".to_string()),
+                DiffLine::Removed("- a".to_string()),
+                DiffLine::Same("- b".to_string()),
+                DiffLine::Added("- c".to_string()),
+            ]
+        );
+        assert_eq!(line_diff("", "x"), vec![DiffLine::Added("x".to_string())]);
+        assert!(line_diff("x\ny", "x\ny").iter().all(|l| matches!(l, DiffLine::Same(_))));
+    }
+
+    #[test]
+    fn records_the_git_state_written_against() {
+        use crate::store::git as store_git;
+        let store = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        store_git::ensure_repo(store.path()).unwrap();
+        store_git::ensure_repo(project.path()).unwrap();
+        let checkout = std::process::Command::new("git")
+            .arg("-C")
+            .arg(project.path())
+            .args(["checkout", "-q", "-b", "feature"])
+            .status()
+            .unwrap();
+        assert!(checkout.success());
+        let git = |dir: &Path| {
+            let out = std::process::Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(["rev-parse", "HEAD"])
+                .output()
+                .unwrap();
+            String::from_utf8(out.stdout).unwrap().trim().to_string()
+        };
+
+        let md = project.path().join("CLAUDE.local.md");
+        let rec = record(store.path(), &md, "- rule\n", false, Utc::now()).unwrap();
+        assert_eq!(rec.git.store_commit, Some(git(store.path())));
+        assert_eq!(rec.git.head, Some(git(project.path())));
+        assert_eq!(rec.git.branch.as_deref(), Some("feature"));
+        assert_eq!(load(store.path()), vec![rec]);
+
+        // outside any repository: nothing to say, and nothing written for it
+        let plain = TempDir::new().unwrap();
+        let rec = record(plain.path(), &plain.path().join("CLAUDE.md"), "x", false, Utc::now())
+            .unwrap();
+        assert!(rec.git.is_empty());
+        let line = std::fs::read_to_string(plain.path().join("state/projection_writes.jsonl"))
+            .unwrap();
+        assert!(!line.contains("\"git\""), "{line}");
+    }
+}
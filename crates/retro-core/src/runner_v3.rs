@@ -4,12 +4,16 @@
 use std::path::{Path, PathBuf};
 
 use crate::analysis::backend::AnalysisBackend;
+use crate::analysis::heuristic as analysis_heuristic;
+use crate::analysis::summarize;
 use crate::analysis::v3 as analysis_v3;
 use crate::config::Config;
 use crate::errors::CoreError;
 use crate::health;
+use crate::ingest::history;
 use crate::ingest::session::parse_session_file;
 use crate::lock::LockFile;
+use crate::logging;
 use crate::models::Session;
 use crate::projection::local_md;
 use crate::scrub;
@@ -17,6 +21,7 @@ use crate::store::state::RunnerState;
 use crate::store::{Store, git as store_git, index, projects, queue};
 
 #[derive(Debug, Default)]
+#[non_exhaustive]
 pub struct RunV3Summary {
     pub sessions_processed: usize,
     pub sessions_pending: usize,
@@ -35,8 +40,39 @@ pub struct RunV3Summary {
     /// reasons per group are pushed to state notifications so the user sees
     /// them in the next briefing.
     pub ops_skipped: usize,
+    /// `update_node` ops naming an id no node has (after case/prefix
+    /// recovery) — kept as low-confidence nodes when they carried content,
+    /// otherwise also counted in `ops_skipped`.
+    pub orphaned_updates: usize,
+    /// Creates over `[analysis] max_new_nodes_per_run`, deferred until
+    /// re-observed (`store::deferred`).
+    pub candidates_deferred: usize,
+    /// Their bodies, per group in the order the cap ranked them (most
+    /// confident first; ties keep the analyzer's order).
+    pub deferred: Vec<String>,
+    /// Sessions matching an `[[ingest.ignore_session_if]]` rule (subset of
+    /// `sessions_skipped`): dropped unanalyzed and recorded as processed.
+    pub sessions_ignored: usize,
     pub rules_projected_global: usize,
     pub pushed: bool,
+    /// One entry per project group the analysis stage attempted, in queue
+    /// order — including failed ones, whose sessions stay queued and are
+    /// retried by the next run.
+    pub projects: Vec<ProjectRunSummary>,
+}
+
+/// Analysis outcome for one project group.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct ProjectRunSummary {
+    pub slug: String,
+    pub sessions: usize,
+    pub nodes_created: usize,
+    pub nodes_updated: usize,
+    /// Input + output tokens (0 for the heuristic backend).
+    pub tokens: u64,
+    /// Analysis error; None when the group completed.
+    pub error: Option<String>,
 }
 
 /// Run the v3 pipeline once. Returns Ok(None) if another run holds the lock
@@ -47,12 +83,42 @@ pub fn run_v3(
     config: &Config,
     backend: &dyn AnalysisBackend,
     dry_run: bool,
+) -> Result<Option<RunV3Summary>, CoreError> {
+    run_v3_with_base(store_root, config, config, backend, dry_run)
+}
+
+/// [`run_v3`] where `config` carries one-off overrides (`retro run
+/// --heuristic`, `--limit`, ...) on top of `base`, the config as loaded from
+/// config.toml. Only `base` is tracked for config changes, so an override
+/// isn't recorded as the user changing their config (twice: on and off).
+pub fn run_v3_with_base(
+    store_root: &Path,
+    config: &Config,
+    base: &Config,
+    backend: &dyn AnalysisBackend,
+    dry_run: bool,
 ) -> Result<Option<RunV3Summary>, CoreError> {
     let Some(_lock) = LockFile::try_acquire(&store_root.join("run.lock")) else {
         return Ok(None);
     };
+    let _run_span = crate::profile::span("run");
+    // A store written in a newer format (another machine, a downgrade)
+    // must not be rewritten by a binary that can't parse all of it.
+    if let Err(e) = crate::store::meta::check_compatible(store_root) {
+        if !dry_run {
+            health::record(store_root, "run", false, &e.to_string())?;
+        }
+        return Err(e);
+    }
     let mut summary = RunV3Summary::default();
     let store = Store::open(store_root);
+    crate::projection::writes::set_snapshots(config.log.store_apply_snapshots);
+    log::info!(
+        target: logging::RUNNER,
+        "claude dir: {} ({})",
+        config.claude_dir().display(),
+        config.claude_dir_source().describe()
+    );
     // Layout creation (knowledge/ dirs, .gitignore) is itself a write — dry_run
     // must touch nothing, so this is deferred to the real-run path alongside
     // ensure_repo(). load_all()/parse_session_file() below tolerate a
@@ -80,6 +146,23 @@ pub fn run_v3(
         }
     }
 
+    // Stage: config tracking — a changed effective config (model,
+    // thresholds, budget) is recorded so a behavior change can be traced to
+    // it later (`retro status`, `retro digest`).
+    if !dry_run {
+        let mut st = RunnerState::load(store_root)?;
+        let changes = st.note_config(base.snapshot(), &chrono::Utc::now().to_rfc3339());
+        st.save(store_root)?;
+        if !changes.is_empty() {
+            health::record(
+                store_root,
+                "config",
+                true,
+                &format!("config changed: {}", changes.join("; ")),
+            )?;
+        }
+    }
+
     // Stage: exclusion sweep — a project excluded AFTER registration gets its
     // knowledge deleted (recoverable via store git history) and its
     // CLAUDE.local.md removed. Spec §5: exclusion = removal.
@@ -146,9 +229,18 @@ pub fn run_v3(
     }
 
     // Stage: load + parse queue into per-project groups.
+    let parse_span = crate::profile::span("parse-queue");
     let entries = queue::list(store_root)?;
     // (slug, project_path, [(session_id, transcript_mtime_unix, session)])
     let mut groups: Vec<(String, String, Vec<(String, u64, Session)>)> = Vec::new();
+    // session_id -> content fingerprint, taken alongside the mtime so catch-up
+    // can recognize an mtime-only touch of an already-processed transcript.
+    let mut fingerprints: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
+    // Entry types newer than this binary, across every parsed transcript.
+    let mut unknown_types: std::collections::BTreeMap<String, u64> =
+        std::collections::BTreeMap::new();
+    let mut parsed_any = false;
     for entry in &entries {
         let path = PathBuf::from(&entry.transcript_path);
         let mtime_unix = std::fs::metadata(&path)
@@ -157,6 +249,9 @@ pub fn run_v3(
             .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
             .map(|d| d.as_secs())
             .unwrap_or(0);
+        if let Ok(fp) = crate::ingest::content_fingerprint(&path) {
+            fingerprints.insert(entry.session_id.clone(), fp);
+        }
         let cwd_hint = entry.cwd.clone().unwrap_or_default();
         let mut session = match parse_session_file(&path, &entry.session_id, &cwd_hint) {
             Ok(s) => s,
@@ -178,6 +273,10 @@ pub fn run_v3(
                 continue;
             }
         };
+        parsed_any = true;
+        for (t, n) in &session.unknown_entry_types {
+            *unknown_types.entry(t.clone()).or_default() += *n as u64;
+        }
         let cwd = session
             .metadata
             .cwd
@@ -193,10 +292,31 @@ pub fn run_v3(
         }
         if projects::is_excluded(&cwd, &config.privacy.exclude_projects)
             || projects::is_store_dir(store_root, &cwd)
+            || projects::opt_out_marker(&cwd).is_some()
+        {
+            if !dry_run {
+                queue::remove(store_root, &entry.session_id)?;
+            }
+            summary.sessions_skipped += 1;
+            continue;
+        }
+        if let Some(rule) =
+            crate::ingest::ignore::first_match(&config.ingest.ignore_session_if, &session, &cwd)
         {
+            // Automation (CI `claude -p`, bots): processed, never analyzed.
+            // Recorded as processed so catch-up doesn't re-enqueue it.
             if !dry_run {
                 queue::remove(store_root, &entry.session_id)?;
+                let mut state = RunnerState::load(store_root)?;
+                state.record_processed(
+                    &entry.session_id,
+                    mtime_unix,
+                    fingerprints.get(&entry.session_id).map(String::as_str),
+                );
+                *state.ignored_sessions.entry(rule + 1).or_default() += 1;
+                state.save(store_root)?;
             }
+            summary.sessions_ignored += 1;
             summary.sessions_skipped += 1;
             continue;
         }
@@ -211,26 +331,7 @@ pub fn run_v3(
         if config.privacy.scrub_secrets {
             scrub::scrub_session(&mut session);
         }
-        let slug = if dry_run {
-            // dry-run must not write project.toml; use a path-derived label
-            crate::store::slugify(
-                Path::new(&cwd)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("project"),
-            )
-        } else {
-            let reg = projects::register(&store, &cwd)?;
-            if reg.newly_registered {
-                let mut state = RunnerState::load(store_root)?;
-                state.notifications.push(format!(
-                    "retro is now watching `{}` — exclude via privacy.exclude_projects in ~/.retro/config.toml",
-                    reg.slug
-                ));
-                state.save(store_root)?;
-            }
-            reg.slug
-        };
+        let slug = project_slug(&store, &cwd, dry_run)?;
         match groups.iter_mut().find(|(s, _, _)| s == &slug) {
             Some((_, _, sessions)) => {
                 sessions.push((entry.session_id.clone(), mtime_unix, session))
@@ -243,6 +344,61 @@ pub fn run_v3(
         }
     }
 
+    drop(parse_span);
+    log::info!(
+        target: logging::RUNNER,
+        "{} queued session(s) -> {} project group(s), {} skipped",
+        entries.len(),
+        groups.len(),
+        summary.sessions_skipped
+    );
+
+    // Opt-in: prompts from history.jsonl whose transcripts are gone, one
+    // weak-signal pseudo-session per project, analyzed with that project's
+    // queued sessions.
+    let mut history_records = Vec::new();
+    if config.ingest.include_history {
+        history_records = history::load(store_root);
+        let claude_dir = config.claude_dir();
+        let entries = history::parse_history(&claude_dir.join("history.jsonl"));
+        let mut changed =
+            history::merge_new(&mut history_records, &history::history_only(&claude_dir, &entries))
+                > 0;
+        for mut session in history::pending_sessions(&history_records) {
+            let cwd = session.project.clone();
+            if projects::is_excluded(&cwd, &config.privacy.exclude_projects)
+                || projects::is_store_dir(store_root, &cwd)
+                || projects::opt_out_marker(&cwd).is_some()
+                || session.user_messages.len() < 2
+            {
+                // same as queued sessions: processed, never analyzed
+                history::mark_analyzed(&mut history_records, &session);
+                changed = true;
+                continue;
+            }
+            if config.privacy.scrub_secrets {
+                scrub::scrub_session(&mut session);
+            }
+            let slug = project_slug(&store, &cwd, dry_run)?;
+            let item = (session.session_id.clone(), 0, session);
+            match groups.iter_mut().find(|(s, _, _)| s == &slug) {
+                Some((_, _, sessions)) => sessions.push(item),
+                None => groups.push((slug, cwd, vec![item])),
+            }
+        }
+        if changed && !dry_run {
+            history::save(store_root, &history_records)?;
+        }
+    }
+
+    if parsed_any && !dry_run {
+        let mut state = RunnerState::load(store_root)?;
+        if state.unknown_entry_types != unknown_types {
+            state.unknown_entry_types = unknown_types;
+            state.save(store_root)?;
+        }
+    }
+
     if dry_run {
         summary.sessions_pending = groups.iter().map(|(_, _, s)| s.len()).sum();
         return Ok(Some(summary));
@@ -253,11 +409,49 @@ pub fn run_v3(
     // call, so concurrent hook writes (observe/brief) aren't clobbered by a
     // stale save.
     let today = chrono::Utc::now().date_naive().to_string();
+    // `[ai] backend = "heuristic"` (or `retro run --heuristic`): deterministic
+    // directive extraction, no backend calls, so no budget gate either.
+    let heuristic = config.ai.backend == "heuristic";
+    // `<store>/prompts/` overrides: a placeholder typo stops the run here,
+    // before any call is made.
+    let prompt_overrides = crate::analysis::overrides::load(store_root)?;
+    // `[privacy] anonymize_paths`: the backend sees every known project path,
+    // and the home dir, as an alias; responses are mapped back before
+    // anything is stored.
+    let pseudonyms = if config.privacy.anonymize_paths {
+        let known = projects::PathMap::load(store_root)?;
+        let mut st = RunnerState::load(store_root)?;
+        let key = st.pseudonym_key();
+        st.save(store_root)?;
+        let home = crate::user::home().path;
+        crate::anonymize::Pseudonyms::new(
+            key,
+            known
+                .paths
+                .values()
+                .map(String::as_str)
+                .chain(groups.iter().map(|(_, path, _)| path.as_str())),
+            home.to_str().filter(|h| *h != "."),
+        )
+    } else {
+        crate::anonymize::Pseudonyms::default()
+    };
+    let anonymized = crate::anonymize::Anonymized {
+        inner: backend,
+        names: &pseudonyms,
+    };
+    let backend: &dyn AnalysisBackend = if pseudonyms.is_empty() { backend } else { &anonymized };
     let mut touched: Vec<(String, String)> = Vec::new(); // (slug, path) that got/changed nodes
     let mut learned: Vec<String> = Vec::new();
+    // Each group's reasoning, for the learn commit's body: `git log` on a
+    // node file then says why retro wrote it.
+    let mut why: Vec<String> = Vec::new();
+    // Global rules proposed by earlier groups: not projected until the end of
+    // the run, and possibly capped out of a later group's context.
+    let mut proposed: Vec<String> = Vec::new();
     for (slug, project_path, group) in &groups {
         let state = RunnerState::load(store_root)?;
-        if state.budget_remaining(&today, config.runner.max_ai_calls_per_day) == 0 {
+        if !heuristic && state.budget_remaining(&today, config.runner.max_ai_calls_per_day) == 0 {
             let waiting: usize =
                 groups.iter().map(|(_, _, s)| s.len()).sum::<usize>() - summary.sessions_processed;
             health::record(
@@ -269,39 +463,135 @@ pub fn run_v3(
             summary.sessions_pending = waiting;
             break;
         }
-        let sessions: Vec<Session> = group.iter().map(|(_, _, s)| s.clone()).collect();
-        let analyze_outcome = analysis_v3::analyze_sessions(&store, backend, &sessions, Some(slug));
-        // The backend call happened either way — count it against the daily
-        // budget on BOTH arms, or a persistently failing group becomes
-        // unbounded spend that max_ai_calls_per_day never sees.
-        {
+        let mut sessions: Vec<Session> = group.iter().map(|(_, _, s)| s.clone()).collect();
+        // `[analysis] max_new_nodes_per_run` spans every group of the run.
+        let new_node_allowance = match config.analysis.max_new_nodes_per_run {
+            0 => usize::MAX,
+            cap => cap.saturating_sub(summary.nodes_created),
+        };
+        let analyze_span = crate::profile::span("analyze");
+        let analyze_outcome = if heuristic {
+            analysis_heuristic::analyze_sessions(&store, &sessions, Some(slug))
+        } else {
+            // `[analysis] summarize_large_sessions`: condense what the prompt
+            // would truncate, leaving one call of today's budget for the
+            // analysis itself. Summary calls count against the budget too.
+            // Condensing is optional: when it fails the sessions go to the
+            // analysis as they are (truncated), and the calls made still count.
+            let mut condensed = summarize::SummaryCost::default();
+            if config.analysis.summarize_large_sessions {
+                let spare = state
+                    .budget_remaining(&today, config.runner.max_ai_calls_per_day)
+                    .saturating_sub(1);
+                if let Err(e) = summarize::condense(
+                    store_root,
+                    backend,
+                    &config.ai.summary_model,
+                    &mut sessions,
+                    spare,
+                    prompt_overrides.session_summary.as_deref(),
+                    &mut condensed,
+                ) {
+                    health::record(
+                        store_root,
+                        "summarize",
+                        false,
+                        &format!("{slug}: {e} — large sessions truncated instead"),
+                    )?;
+                }
+                if condensed.calls > 0 {
+                    let mut state = RunnerState::load(store_root)?;
+                    state.record_ai_calls(&today, condensed.calls);
+                    state.save(store_root)?;
+                    summary.ai_calls += condensed.calls;
+                }
+            }
+            let outcome = analysis_v3::analyze_sessions(
+                &store,
+                backend,
+                &sessions,
+                Some(slug),
+                config.analysis.max_existing_nodes_in_prompt,
+                new_node_allowance,
+                &analysis_v3::BatchContext {
+                    proposed_earlier: &proposed,
+                    output_language: &config.analysis.output_language,
+                    preamble: prompt_overrides.analysis_preamble.as_deref(),
+                },
+            )
+            .map(|mut r| {
+                r.sessions_summarized = condensed.summarized;
+                r.summary_input_tokens = condensed.input_tokens;
+                r.summary_output_tokens = condensed.output_tokens;
+                r
+            });
+            // The backend call happened either way — count it against the daily
+            // budget on BOTH arms, or a persistently failing group becomes
+            // unbounded spend that max_ai_calls_per_day never sees.
             let mut state = RunnerState::load(store_root)?;
             state.record_ai_calls(&today, 1);
             state.save(store_root)?;
-        }
-        summary.ai_calls += 1;
+            summary.ai_calls += 1;
+            outcome
+        };
+        drop(analyze_span);
         let result = match analyze_outcome {
             Ok(r) => r,
             Err(e) => {
-                health::record(store_root, "analyze", false, &e.to_string())?;
+                log::warn!(target: logging::RUNNER, "{slug}: analysis failed: {e}");
+                health::record(store_root, "analyze", false, &format!("{slug}: {e}"))?;
+                summary.projects.push(ProjectRunSummary {
+                    slug: slug.clone(),
+                    sessions: group.len(),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                });
                 // leave this group queued for a future run; keep going with others
                 continue;
             }
         };
+        summary.projects.push(ProjectRunSummary {
+            slug: slug.clone(),
+            sessions: result.sessions_analyzed,
+            nodes_created: result.nodes_created,
+            nodes_updated: result.nodes_updated + result.nodes_merged,
+            tokens: result.input_tokens
+                + result.output_tokens
+                + result.summary_input_tokens
+                + result.summary_output_tokens,
+            error: None,
+        });
         summary.sessions_processed += result.sessions_analyzed;
         summary.nodes_created += result.nodes_created;
         summary.nodes_updated += result.nodes_updated;
         summary.nodes_merged += result.nodes_merged;
         summary.nodes_invalidated += result.nodes_invalidated;
         summary.ops_skipped += result.ops_skipped;
+        summary.orphaned_updates += result.orphaned_updates;
+        summary.candidates_deferred += result.candidates_deferred;
+        summary.deferred.extend(result.deferred.iter().cloned());
+        proposed.extend(result.proposed.iter().cloned());
         learned.extend(result.learned.iter().map(|b| {
             let first_line = b.lines().next().unwrap_or(b);
             format!("Learned: {}", crate::util::truncate_str(first_line, 100))
         }));
+        let changed = result.nodes_created + result.nodes_updated + result.nodes_merged;
+        if changed > 0 && !result.reasoning.trim().is_empty() {
+            why.push(store_git::why_line(slug, &result.reasoning, &result.response_digest));
+        }
         let mut state = RunnerState::load(store_root)?;
-        for (session_id, mtime_unix, _) in group {
+        for (session_id, mtime_unix, session) in group {
+            if session_id.starts_with(history::PSEUDO_SESSION_PREFIX) {
+                history::mark_analyzed(&mut history_records, session);
+                history::save(store_root, &history_records)?;
+                continue;
+            }
             queue::remove(store_root, session_id)?;
-            state.record_processed(session_id, *mtime_unix);
+            state.record_processed(
+                session_id,
+                *mtime_unix,
+                fingerprints.get(session_id).map(String::as_str),
+            );
         }
         // Rejected/hostile ops surface as briefing notifications (≤3 per
         // group) — health ok=true records are invisible to warnings().
@@ -316,6 +606,30 @@ pub fn run_v3(
             "{}: +{} nodes, {} updated ({} ops skipped)",
             slug, result.nodes_created, result.nodes_updated, result.ops_skipped
         );
+        if result.sessions_summarized > 0 {
+            detail.push_str(&format!(
+                " [{} large session(s) summarized]",
+                result.sessions_summarized
+            ));
+        }
+        if result.candidates_deferred > 0 {
+            detail.push_str(&format!(
+                " [{} candidate(s) deferred (cap {})]",
+                result.candidates_deferred, config.analysis.max_new_nodes_per_run
+            ));
+        }
+        if result.orphaned_updates > 0 || result.update_ids_recovered > 0 {
+            detail.push_str(&format!(
+                " [update ids: {} recovered, {} orphaned]",
+                result.update_ids_recovered, result.orphaned_updates
+            ));
+        }
+        if result.context_included < result.context_total {
+            detail.push_str(&format!(
+                " [context {}/{} nodes]",
+                result.context_included, result.context_total
+            ));
+        }
         if !result.reasoning.is_empty() {
             detail.push_str(&format!(
                 " — {}",
@@ -325,13 +639,34 @@ pub fn run_v3(
         health::record(store_root, "analyze", true, &detail)?;
     }
 
+    // A later group's success must not overwrite an earlier group's failure
+    // in health (one record per stage): restate which projects still wait.
+    let failed: Vec<String> = summary
+        .projects
+        .iter()
+        .filter_map(|p| p.error.as_ref().map(|e| format!("{} ({e})", p.slug)))
+        .collect();
+    if !failed.is_empty() {
+        health::record(
+            store_root,
+            "analyze",
+            false,
+            &format!(
+                "{}/{} project(s) analyzed; failed, retried next run: {}",
+                summary.projects.len() - failed.len(),
+                summary.projects.len(),
+                crate::util::truncate_str(&failed.join("; "), 400)
+            ),
+        )?;
+    }
+
     // Stage: commit newly-learned knowledge — right after analysis, before
     // projection, so a projection failure never blocks these writes from
     // landing in history (analysis and projection are independent stages).
     let nodes_changed = summary.nodes_created + summary.nodes_updated + summary.nodes_merged;
     // With zero node changes, anything sitting uncommitted is a stray (crash
     // remnant, manual edit) — labeling it "learn 0 node(s)" would mislead.
-    let learn_message = if nodes_changed == 0 {
+    let learn_subject = if nodes_changed == 0 {
         "retro: maintenance".to_string()
     } else {
         format!(
@@ -340,6 +675,12 @@ pub fn run_v3(
             summary.nodes_updated + summary.nodes_merged
         )
     };
+    let learn_message = if why.is_empty() {
+        learn_subject.clone()
+    } else {
+        format!("{learn_subject}\n\n{}", why.join("\n"))
+    };
+    crate::store::meta::stamp(store_root)?;
     if store_git::commit_all(store_root, &learn_message)? {
         committed_any = true;
     }
@@ -349,23 +690,45 @@ pub fn run_v3(
     summary.sessions_pending = queue::list(store_root)?.len();
 
     // Stage: projection (global always — cheap and idempotent; locals for touched projects).
+    let project_span = crate::profile::span("project");
     let threshold = config.knowledge.confidence_threshold;
     let global_md = config.claude_dir().join("CLAUDE.md");
     let backups = store_root.join("backups");
-    match local_md::project_global_md(&store, &global_md, threshold, Some(&backups)) {
-        Ok(n) => {
-            summary.rules_projected_global = n;
-            health::record(store_root, "project", true, &format!("global: {n} rule(s)"))?;
+    let strict = config.knowledge.strict_lint;
+    let strict_block = |label: &str, preview| strict_blocked(store_root, strict, label, preview);
+    if !strict_block("global", local_md::preview_global_md(&store, &global_md, threshold))? {
+        match local_md::project_global_md(&store, &global_md, threshold, Some(&backups)) {
+            Ok(n) => {
+                summary.rules_projected_global = n;
+                health::record(store_root, "project", true, &format!("global: {n} rule(s)"))?;
+            }
+            Err(e) => health::record(store_root, "project", false, &e.to_string())?,
         }
-        Err(e) => health::record(store_root, "project", false, &e.to_string())?,
     }
     for (slug, project_path) in &touched {
-        if let Err(e) = local_md::project_local_md(&store, slug, Path::new(project_path), threshold)
-        {
+        let root = Path::new(project_path);
+        if !root.is_dir() {
+            // Never resurrect a moved/deleted repo's directory tree.
+            health::record(
+                store_root,
+                "project",
+                false,
+                &format!(
+                    "{slug}: {project_path} no longer exists — skipped (moved? `retro relocate {slug} <new-path>`)"
+                ),
+            )?;
+            continue;
+        }
+        if strict_block(slug, local_md::preview_local_md(&store, slug, root, threshold))? {
+            continue;
+        }
+        if let Err(e) = local_md::project_local_md(&store, slug, root, threshold) {
             health::record(store_root, "project", false, &format!("{slug}: {e}"))?;
         }
     }
 
+    drop(project_span);
+
     // Stage: notifications for the next briefing.
     if !learned.is_empty() {
         let mut st = RunnerState::load(store_root)?;
@@ -377,17 +740,21 @@ pub fn run_v3(
     // the knowledge commit above already landed the analysis writes),
     // straggler commit (anything touched since, e.g. by projection or a
     // concurrent manual edit), push.
+    let index_span = crate::profile::span("index");
     if let Err(e) = index::build(&store) {
         health::record(store_root, "index", false, &e.to_string())?;
     }
-    // learn_message already falls back to "retro: maintenance" when nothing
-    // changed, so stragglers get an honest label either way.
-    let committed = store_git::commit_all(store_root, &learn_message)?;
+    drop(index_span);
+    // learn_subject already falls back to "retro: maintenance" when nothing
+    // changed, so stragglers get an honest label either way (the reasoning
+    // stays with the commit that wrote the nodes).
+    let committed = store_git::commit_all(store_root, &learn_subject)?;
     committed_any = committed_any || committed;
     // Also push when an earlier commit (dashboard write, manual edit between
     // runs) is still sitting unpushed — this run made no commit of its own,
     // but the backup remote should not lag indefinitely.
     if committed_any || store_git::has_unpushed(store_root) {
+        let _push_span = crate::profile::span("push");
         match store_git::push_best_effort(store_root) {
             store_git::PushOutcome::Pushed => {
                 summary.pushed = true;
@@ -407,9 +774,77 @@ pub fn run_v3(
         true,
         &format!("{} session(s)", summary.sessions_processed),
     )?;
+    log::info!(
+        target: logging::RUNNER,
+        "run done: {} session(s), {} AI call(s), +{} node(s)",
+        summary.sessions_processed,
+        summary.ai_calls,
+        summary.nodes_created
+    );
     Ok(Some(summary))
 }
 
+/// `[knowledge] strict_lint`: whether to keep `label`'s current content
+/// instead of writing `preview`, its regenerated content. Lint findings
+/// block the write with a health warning; a preview that failed skips the
+/// file the same way, like a failed write. Never blocks when not `strict`.
+fn strict_blocked(
+    store_root: &Path,
+    strict: bool,
+    label: &str,
+    preview: Result<String, CoreError>,
+) -> Result<bool, CoreError> {
+    if !strict {
+        return Ok(false);
+    }
+    let preview = match preview {
+        Ok(preview) => preview,
+        Err(e) => {
+            health::record(store_root, "project", false, &format!("{label}: {e}"))?;
+            return Ok(true);
+        }
+    };
+    let findings = crate::projection::claude_md::lint(&preview);
+    if findings.is_empty() {
+        return Ok(false);
+    }
+    let details: Vec<String> =
+        findings.iter().map(|f| format!("[{}] {}", f.kind, f.detail)).collect();
+    health::record(
+        store_root,
+        "project",
+        false,
+        &format!(
+            "{label}: not projected (strict_lint): {}",
+            crate::util::truncate_str(&details.join("; "), 400)
+        ),
+    )?;
+    Ok(true)
+}
+
+/// Slug for the project at `cwd`, registering it on first sight.
+fn project_slug(store: &Store, cwd: &str, dry_run: bool) -> Result<String, CoreError> {
+    if dry_run {
+        // dry-run must not write project.toml; use a path-derived label
+        return Ok(crate::store::slugify(
+            Path::new(cwd)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("project"),
+        ));
+    }
+    let reg = projects::register(store, cwd)?;
+    if reg.newly_registered {
+        let mut state = RunnerState::load(store.root())?;
+        state.notifications.push(format!(
+            "retro is now watching `{}` — exclude via privacy.exclude_projects in ~/.retro/config.toml",
+            reg.slug
+        ));
+        state.save(store.root())?;
+    }
+    Ok(reg.slug)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,6 +898,257 @@ mod tests {
         assert_eq!(summary.ai_calls, 0);
     }
 
+    #[test]
+    fn config_changes_are_recorded_once_per_change() {
+        let (tmp, _claude, mut config) = setup();
+        let backend = MockBackend::with_responses(vec![]);
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        assert!(RunnerState::load(tmp.path()).unwrap().config_changes.is_empty());
+
+        config.runner.max_ai_calls_per_day = 3;
+        run_v3(tmp.path(), &config, &backend, true).unwrap();
+        assert!(
+            RunnerState::load(tmp.path()).unwrap().config_changes.is_empty(),
+            "dry run records nothing"
+        );
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(state.config_changes.len(), 1);
+        assert_eq!(
+            state.config_changes[0].changes,
+            vec!["runner.max_ai_calls_per_day: 10 → 3".to_string()]
+        );
+        let health = health::Health::load(tmp.path()).unwrap();
+        assert!(health.stages["config"].detail.contains("10 → 3"));
+    }
+
+    #[test]
+    fn run_overrides_are_not_recorded_as_config_changes() {
+        let (tmp, _claude, config) = setup();
+        let backend = MockBackend::with_responses(vec![]);
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+
+        let mut overridden = config.clone();
+        overridden.ai.backend = "heuristic".to_string();
+        overridden.analysis.max_new_nodes_per_run = 1;
+        run_v3_with_base(tmp.path(), &overridden, &config, &backend, false).unwrap();
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        assert!(RunnerState::load(tmp.path()).unwrap().config_changes.is_empty());
+    }
+
+    #[test]
+    fn newer_store_format_is_refused_before_any_write() {
+        let (tmp, _claude, config) = setup();
+        let newer = crate::store::meta::STORE_FORMAT + 1;
+        std::fs::write(
+            tmp.path().join("store.toml"),
+            format!("format = {newer}\nwritten_by = \"9.0.0\"\n"),
+        )
+        .unwrap();
+        let backend = MockBackend::with_responses(vec![]);
+        let err = run_v3(tmp.path(), &config, &backend, false).unwrap_err();
+        assert!(err.to_string().contains("retro v9.0.0"));
+        // the marker is untouched and the refusal is visible in health
+        let marker = std::fs::read_to_string(tmp.path().join("store.toml")).unwrap();
+        assert!(marker.contains("9.0.0"));
+        assert!(!health::Health::load(tmp.path()).unwrap().stages["run"].ok);
+    }
+
+    #[test]
+    fn heuristic_backend_learns_without_ai_calls_or_budget() {
+        let (tmp, _claude, mut config) = setup();
+        config.ai.backend = "heuristic".to_string();
+        config.runner.max_ai_calls_per_day = 0; // would block any AI call
+        let proj = TempDir::new().unwrap();
+        let cwd = proj.path().display().to_string();
+        let line = |n: u32, text: &str| {
+            format!(
+                r#"{{"type":"user","uuid":"h-{n}","sessionId":"h","cwd":"{cwd}","timestamp":"2026-07-06T10:00:0{n}Z","message":{{"role":"user","content":"{text}"}}}}"#
+            )
+        };
+        let transcript = tmp.path().join("h.jsonl");
+        std::fs::write(
+            &transcript,
+            format!(
+                "{}\n{}\n",
+                line(0, "always run cargo fmt before committing"),
+                line(1, "thanks")
+            ) + r#"{"type":"checkpoint","id":1}"#,
+        )
+        .unwrap();
+        queue::enqueue(
+            tmp.path(),
+            &queue::QueueEntry {
+                session_id: "h".to_string(),
+                transcript_path: transcript.display().to_string(),
+                cwd: Some(cwd.clone()),
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        let backend = MockBackend::with_responses(vec![]);
+        let summary = run_v3(tmp.path(), &config, &backend, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.ai_calls, 0);
+        assert_eq!(summary.nodes_created, 1);
+        assert!(backend.prompts_seen.lock().unwrap().is_empty());
+        assert!(queue::list(tmp.path()).unwrap().is_empty());
+        // an unknown entry type never fails the session, but is recorded
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(state.unknown_entry_types.get("checkpoint"), Some(&1));
+    }
+
+    #[test]
+    fn large_sessions_are_summarized_on_the_summary_model_first() {
+        let (tmp, _claude, mut config) = setup();
+        config.analysis.summarize_large_sessions = true;
+        let proj = TempDir::new().unwrap();
+        let transcript = tmp.path().join("big.jsonl");
+        let long = "please keep the build green ".repeat(1_300);
+        let lines: Vec<String> = (0..2)
+            .map(|n| {
+                format!(
+                    r#"{{"type":"user","uuid":"big-{n}","sessionId":"big","cwd":"{}","timestamp":"2026-07-06T10:00:0{n}Z","message":{{"role":"user","content":"{n}: {long}"}}}}"#,
+                    proj.path().display()
+                )
+            })
+            .collect();
+        std::fs::write(&transcript, lines.join("\n") + "\n").unwrap();
+        queue::enqueue(
+            tmp.path(),
+            &queue::QueueEntry {
+                session_id: "big".to_string(),
+                transcript_path: transcript.display().to_string(),
+                cwd: Some(proj.path().display().to_string()),
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Store prompt overrides reach both calls.
+        std::fs::create_dir_all(tmp.path().join("prompts")).unwrap();
+        std::fs::write(tmp.path().join("prompts/session_summary.md"), "{builtin}\nTeam: {project}")
+            .unwrap();
+        std::fs::write(tmp.path().join("prompts/analysis_preamble.md"), "Be terse. {builtin}")
+            .unwrap();
+
+        let digest = r#"{"instructions":["Keep the build green.","Run clippy first."],
+            "corrections":[],"errors":[]}"#;
+        let analysis = r#"{"reasoning":"r","operations":[]}"#;
+        let backend =
+            MockBackend::with_responses(vec![digest.to_string(), analysis.to_string()]);
+        let summary = run_v3(tmp.path(), &config, &backend, false)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(summary.ai_calls, 2, "one summary call + the analysis");
+        assert_eq!(backend.models_seen.lock().unwrap()[0], "haiku");
+        let prompts = backend.prompts_seen.lock().unwrap();
+        assert!(prompts[0].contains(&format!("Team: {}", proj.path().display())));
+        assert!(prompts[1].starts_with("Be terse. You are analyzing"));
+        assert!(prompts[1].contains("Run clippy first."));
+        assert!(!prompts[1].contains("please keep the build green"));
+        let today = chrono::Utc::now().date_naive().to_string();
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(state.budget_remaining(&today, 3), 1, "both calls budgeted");
+    }
+
+    /// Condensing is an optional pre-step: when it fails the run analyzes the
+    /// truncated sessions, and the summary call it made is still budgeted.
+    #[test]
+    fn failed_condensing_falls_back_to_truncation() {
+        let (tmp, _claude, mut config) = setup();
+        config.analysis.summarize_large_sessions = true;
+        let proj = TempDir::new().unwrap();
+        let transcript = tmp.path().join("big.jsonl");
+        let long = "please keep the build green ".repeat(1_300);
+        let lines: Vec<String> = (0..2)
+            .map(|n| {
+                format!(
+                    r#"{{"type":"user","uuid":"big-{n}","sessionId":"big","cwd":"{}","timestamp":"2026-07-06T10:00:0{n}Z","message":{{"role":"user","content":"{n}: {long}"}}}}"#,
+                    proj.path().display()
+                )
+            })
+            .collect();
+        std::fs::write(&transcript, lines.join("\n") + "\n").unwrap();
+        queue::enqueue(
+            tmp.path(),
+            &queue::QueueEntry {
+                session_id: "big".to_string(),
+                transcript_path: transcript.display().to_string(),
+                cwd: Some(proj.path().display().to_string()),
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        // The summary cache can't be written.
+        std::fs::create_dir_all(tmp.path().join("state/session_summaries.json")).unwrap();
+
+        let digest = r#"{"instructions":["Keep the build green."],"corrections":[],"errors":[]}"#;
+        let analysis = r#"{"reasoning":"r","operations":[]}"#;
+        let backend =
+            MockBackend::with_responses(vec![digest.to_string(), analysis.to_string()]);
+        let summary = run_v3(tmp.path(), &config, &backend, false)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(summary.ai_calls, 2, "the spent summary call + the analysis");
+        assert_eq!(summary.sessions_processed, 1);
+        let prompts = backend.prompts_seen.lock().unwrap();
+        assert!(prompts[1].contains("please keep the build green"), "truncated, not condensed");
+        let h = health::Health::load(tmp.path()).unwrap();
+        assert!(!h.stages["summarize"].ok);
+        assert!(h.stages["summarize"].detail.contains("truncated instead"));
+        let today = chrono::Utc::now().date_naive().to_string();
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(state.budget_remaining(&today, 3), 1, "both calls budgeted");
+    }
+
+    #[test]
+    fn new_node_cap_defers_the_rest_in_ranked_order() {
+        let (tmp, _claude, mut config) = setup();
+        config.analysis.max_new_nodes_per_run = 1;
+        let proj = TempDir::new().unwrap();
+        let transcript = write_fixture_session(tmp.path(), "sess-1", proj.path().to_str().unwrap());
+        queue::enqueue(
+            tmp.path(),
+            &queue::QueueEntry {
+                session_id: "sess-1".to_string(),
+                transcript_path: transcript.display().to_string(),
+                cwd: Some(proj.path().display().to_string()),
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        let create = |body: &str, conf: f64| {
+            format!(
+                r#"{{"action":"create_node","node_type":"rule","scope":"global","content":"{body}","confidence":{conf}}}"#
+            )
+        };
+        let response = format!(
+            r#"{{"reasoning":"r","operations":[{},{},{}]}}"#,
+            create("Prefer rg over grep.", 0.8),
+            create("Use uv for Python.", 0.95),
+            create("Squash fixups before merging.", 0.85)
+        );
+        let backend = MockBackend::with_responses(vec![response]);
+        let summary = run_v3(tmp.path(), &config, &backend, false)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(summary.nodes_created, 1);
+        assert_eq!(summary.deferred, ["Squash fixups before merging.", "Prefer rg over grep."]);
+        let store = Store::open(tmp.path());
+        let loaded = store.load_all().unwrap();
+        assert_eq!(loaded.nodes[0].1.body, "Use uv for Python.");
+        // the remainder waits in the deferred list, not the store
+        let waiting = crate::store::deferred::load(tmp.path());
+        assert_eq!(waiting.len(), 2);
+    }
+
     #[test]
     fn drains_queue_analyzes_and_projects() {
         let (tmp, _claude, config) = setup();
@@ -517,6 +1203,8 @@ mod tests {
             "got: {:?}",
             final_state.processed
         );
+        // with its content fingerprint, so an mtime-only touch isn't re-enqueued
+        assert!(final_state.fingerprints.contains_key("sess-1"));
         // the knowledge commit landed right after analysis (before projection)
         let log = std::process::Command::new("git")
             .arg("-C")
@@ -529,6 +1217,12 @@ mod tests {
             subjects.lines().any(|s| s.starts_with("retro: learn")),
             "got: {subjects}"
         );
+        // and its body says why, so the node file's history explains it
+        let (_, node) = &loaded.nodes[0];
+        let path = store.node_path(&node.scope, &node.id);
+        let history = store_git::file_history(tmp.path(), &path, 5).unwrap();
+        assert_eq!(history.len(), 1, "{history:?}");
+        assert!(history[0].why[0].contains("found one [response "), "{history:?}");
     }
 
     #[test]
@@ -589,6 +1283,272 @@ mod tests {
         assert!(!h.stages["analyze"].ok, "failure recorded");
     }
 
+    #[test]
+    fn failed_project_is_reported_and_retried_while_others_complete() {
+        let (tmp, _claude, config) = setup();
+        let projs: Vec<TempDir> = (0..3).map(|_| TempDir::new().unwrap()).collect();
+        for (i, proj) in projs.iter().enumerate() {
+            let id = format!("sess-{i}");
+            let transcript = write_fixture_session(tmp.path(), &id, proj.path().to_str().unwrap());
+            queue::enqueue(
+                tmp.path(),
+                &queue::QueueEntry {
+                    session_id: id,
+                    transcript_path: transcript.display().to_string(),
+                    cwd: Some(proj.path().display().to_string()),
+                    enqueued_at: format!("2026-07-06T10:00:0{i}Z"),
+                },
+            )
+            .unwrap();
+        }
+        let ok = |content: &str| {
+            format!(
+                r#"{{"reasoning":"r","operations":[{{"action":"create_node","node_type":"rule","scope":"project","content":"{content}","confidence":0.9}}]}}"#
+            )
+        };
+        // The middle project's response is unparseable -> its analysis fails.
+        let backend = MockBackend::with_responses(vec![
+            ok("First project rule."),
+            "not json".to_string(),
+            ok("Third project rule."),
+        ]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.projects.len(), 3);
+        let failed: Vec<&ProjectRunSummary> =
+            summary.projects.iter().filter(|p| p.error.is_some()).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(summary.projects[1].slug, failed[0].slug);
+        assert!(summary.projects[0].error.is_none() && summary.projects[2].error.is_none());
+        assert_eq!(summary.projects[0].nodes_created, 1);
+        assert_eq!(summary.projects[0].tokens, 150);
+        let queued = queue::list(tmp.path()).unwrap();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].session_id, "sess-1");
+        let h = health::Health::load(tmp.path()).unwrap();
+        assert!(!h.stages["analyze"].ok, "the later success must not mask the failure");
+        assert!(h.stages["analyze"].detail.contains("2/3 project(s) analyzed"));
+
+        // Next run: only the failed project is analyzed again.
+        let backend = MockBackend::with_responses(vec![ok("Second project rule.")]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.projects.len(), 1);
+        assert_eq!(summary.projects[0].slug, failed[0].slug);
+        assert!(summary.projects[0].error.is_none());
+        assert!(queue::list(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn ignored_sessions_are_dropped_unanalyzed_and_counted() {
+        let (tmp, _claude, mut config) = setup();
+        let proj = TempDir::new().unwrap();
+        let transcript = write_fixture_session(tmp.path(), "ci-sess", proj.path().to_str().unwrap());
+        let entry = queue::QueueEntry {
+            session_id: "ci-sess".to_string(),
+            transcript_path: transcript.display().to_string(),
+            cwd: Some(proj.path().display().to_string()),
+            enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+        };
+        queue::enqueue(tmp.path(), &entry).unwrap();
+        config.ingest.ignore_session_if = vec![crate::config::IgnoreRule {
+            first_message: Some("^first message$".to_string()),
+            ..Default::default()
+        }];
+        let backend = MockBackend::with_responses(vec![]);
+
+        let dry = run_v3(tmp.path(), &config, &backend, true).unwrap().unwrap();
+        assert_eq!(dry.sessions_ignored, 1);
+        assert_eq!(queue::list(tmp.path()).unwrap().len(), 1, "dry run keeps it queued");
+
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.sessions_ignored, 1);
+        assert_eq!(summary.ai_calls, 0);
+        assert!(queue::list(tmp.path()).unwrap().is_empty());
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert!(state.processed.contains_key("ci-sess"));
+        assert_eq!(state.ignored_sessions.get(&1), Some(&1));
+
+        // Override (`retro run --include-ignored` clears the rules): analyzed.
+        queue::enqueue(tmp.path(), &entry).unwrap();
+        config.ingest.ignore_session_if.clear();
+        let response = r#"{"reasoning":"r","operations":[]}"#.to_string();
+        let backend = MockBackend::with_responses(vec![response]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.sessions_ignored, 0);
+        assert_eq!(summary.sessions_processed, 1);
+    }
+
+    #[test]
+    fn sessions_in_an_opted_out_repo_are_never_analyzed() {
+        let (tmp, _claude, config) = setup();
+        let proj = TempDir::new().unwrap();
+        std::fs::write(proj.path().join(projects::OPT_OUT_FILE), "").unwrap();
+        let cwd = proj.path().to_str().unwrap();
+        let transcript = write_fixture_session(tmp.path(), "oss-sess", cwd);
+        queue::enqueue(
+            tmp.path(),
+            &queue::QueueEntry {
+                session_id: "oss-sess".to_string(),
+                transcript_path: transcript.display().to_string(),
+                cwd: Some(cwd.to_string()),
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        let backend = MockBackend::with_responses(vec![]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!((summary.sessions_skipped, summary.ai_calls), (1, 0));
+        assert!(queue::list(tmp.path()).unwrap().is_empty());
+        assert!(projects::PathMap::load(tmp.path()).unwrap().paths.is_empty(), "not registered");
+        assert!(!proj.path().join("CLAUDE.local.md").exists());
+    }
+
+    #[test]
+    fn history_only_prompts_are_analyzed_only_with_include_history() {
+        let (tmp, claude, mut config) = setup();
+        let proj = TempDir::new().unwrap();
+        let project = proj.path().display().to_string();
+        let history: String = ["always use uv, never pip", "use uv for the lockfile too"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let entry = serde_json::json!({
+                    "display": text, "timestamp": 1_750_000_000_000_i64 + i as i64,
+                    "project": project, "sessionId": format!("cleaned-{i}"),
+                });
+                format!("{entry}\n")
+            })
+            .collect();
+        std::fs::write(claude.path().join("history.jsonl"), history).unwrap();
+
+        let backend = MockBackend::with_responses(vec![]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.ai_calls, 0, "history is opt-in");
+        assert!(history::load(tmp.path()).is_empty());
+
+        config.ingest.include_history = true;
+        let dry = run_v3(tmp.path(), &config, &backend, true).unwrap().unwrap();
+        assert_eq!(dry.sessions_pending, 1, "one pseudo-session per project");
+        assert!(history::load(tmp.path()).is_empty(), "dry run writes nothing");
+
+        let response = r#"{"reasoning":"weak","operations":[
+            {"action":"create_node","node_type":"preference","scope":"project","content":"Use uv, not pip.","confidence":0.5}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!((summary.ai_calls, summary.nodes_created), (1, 1));
+        let prompt = backend.prompts_seen.lock().unwrap()[0].clone();
+        assert!(prompt.contains("use uv for the lockfile too"));
+        let records = history::load(tmp.path());
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.analyzed));
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert!(state.processed.is_empty(), "pseudo-sessions aren't transcripts");
+
+        let again = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(again.ai_calls, 0, "analyzed prompts are not re-analyzed");
+    }
+
+    #[test]
+    fn later_groups_see_global_rules_proposed_earlier_in_the_run() {
+        let (tmp, _claude, mut config) = setup();
+        // capped out of the existing-knowledge context entirely
+        config.analysis.max_existing_nodes_in_prompt = 0;
+        let (a, b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        for (id, proj) in [("sess-a", &a), ("sess-b", &b)] {
+            let cwd = proj.path().display().to_string();
+            let transcript = write_fixture_session(tmp.path(), id, &cwd);
+            queue::enqueue(
+                tmp.path(),
+                &queue::QueueEntry {
+                    session_id: id.to_string(),
+                    transcript_path: transcript.display().to_string(),
+                    cwd: Some(cwd),
+                    enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+                },
+            )
+            .unwrap();
+        }
+        let first = r#"{"reasoning":"r","operations":[
+            {"action":"create_node","node_type":"rule","scope":"global","content":"Prefer rg over grep.","confidence":0.8}
+        ]}"#;
+        let second = r#"{"reasoning":"nothing new","operations":[]}"#;
+        let backend = MockBackend::with_responses(vec![first.to_string(), second.to_string()]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.ai_calls, 2);
+        let prompts = backend.prompts_seen.lock().unwrap();
+        assert!(!prompts[0].contains("Proposed Earlier"));
+        assert!(prompts[1].contains("## Rules Proposed Earlier in This Run"), "{}", prompts[1]);
+        assert!(prompts[1].contains("- Prefer rg over grep."));
+    }
+
+    #[test]
+    fn anonymized_prompts_carry_pseudonyms_and_nodes_keep_real_paths() {
+        let (tmp, _claude, mut config) = setup();
+        config.privacy.anonymize_paths = true;
+        let proj = TempDir::new().unwrap();
+        let cwd = proj.path().display().to_string();
+        let transcript = write_fixture_session(tmp.path(), "sess-anon", &cwd);
+        queue::enqueue(
+            tmp.path(),
+            &queue::QueueEntry {
+                session_id: "sess-anon".to_string(),
+                transcript_path: transcript.display().to_string(),
+                cwd: Some(cwd.clone()),
+                enqueued_at: "2026-07-06T10:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        let mut st = RunnerState::load(tmp.path()).unwrap();
+        st.pseudonym_key = Some(42);
+        st.save(tmp.path()).unwrap();
+        let alias = crate::anonymize::Pseudonyms::new(42, [cwd.as_str()], None).anonymize(&cwd);
+        let response = format!(
+            r#"{{"reasoning":"seen in {alias}","operations":[
+            {{"action":"create_node","node_type":"rule","scope":"project","content":"Run {alias}/scripts/check.sh before committing.","confidence":0.8}}
+        ]}}"#
+        );
+        let backend = MockBackend::with_responses(vec![response]);
+        run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+
+        let prompt = backend.prompts_seen.lock().unwrap()[0].clone();
+        assert!(!prompt.contains(&cwd), "{prompt}");
+        assert!(prompt.contains(&format!("\"project\": \"{alias}\"")), "{prompt}");
+        let loaded = Store::open(tmp.path()).load_all().unwrap();
+        let bodies: Vec<&str> = loaded.nodes.iter().map(|(_, n)| n.body.as_str()).collect();
+        assert_eq!(bodies, vec![format!("Run {cwd}/scripts/check.sh before committing.")]);
+    }
+
+    #[test]
+    fn claude_config_dir_override_redirects_ingest_and_projection() {
+        let (tmp, claude, config) = setup();
+        let env_dir = TempDir::new().unwrap();
+        let mut config = config.with_env_claude_dir(Some(env_dir.path().display().to_string()));
+        config.ingest.include_history = true;
+        let proj = TempDir::new().unwrap();
+        let history: String = ["prefer rg over grep", "rg again, please"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let entry = serde_json::json!({
+                    "display": text, "timestamp": 1_750_000_000_000_i64 + i as i64,
+                    "project": proj.path().display().to_string(), "sessionId": format!("s{i}"),
+                });
+                format!("{entry}\n")
+            })
+            .collect();
+        std::fs::write(env_dir.path().join("history.jsonl"), history).unwrap();
+
+        let response = r#"{"reasoning":"r","operations":[
+            {"action":"create_node","node_type":"preference","scope":"global","content":"Prefer rg over grep.","confidence":0.9}
+        ]}"#;
+        let backend = MockBackend::with_responses(vec![response.to_string()]);
+        let summary = run_v3(tmp.path(), &config, &backend, false).unwrap().unwrap();
+        assert_eq!(summary.ai_calls, 1, "history read from $CLAUDE_CONFIG_DIR");
+        let md = std::fs::read_to_string(env_dir.path().join("CLAUDE.md")).unwrap();
+        assert!(md.contains("Prefer rg over grep."), "{md}");
+        assert!(!claude.path().join("CLAUDE.md").exists(), "config claude_dir untouched");
+    }
+
     #[test]
     fn budget_exhaustion_leaves_sessions_queued_with_health_warning() {
         let (tmp, _claude, mut config) = setup();
@@ -715,6 +1675,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn strict_lint_blocks_projection_of_a_file_with_findings() {
+        let (tmp, claude, mut config) = setup();
+        let md = claude.path().join("CLAUDE.md");
+        std::fs::write(&md, "# Mine\n\n- Always use tabs for indentation\n").unwrap();
+        let store = Store::open(tmp.path());
+        let today = chrono::Utc::now().date_naive();
+        store
+            .write_node(&crate::store::Node {
+                id: "no-tabs".to_string(),
+                scope: crate::store::Scope::Global,
+                node_type: crate::store::NodeType::Rule,
+                confidence: 0.9,
+                sources: vec![],
+                created: today,
+                updated: today,
+                invalidated_by: None,
+                dismissal_reason: None,
+                section: None,
+                body: "Never use tabs for indentation.".to_string(),
+            })
+            .unwrap();
+        let backend = MockBackend::with_responses(vec![]);
+
+        config.knowledge.strict_lint = true;
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        assert_eq!(std::fs::read_to_string(&md).unwrap(), "# Mine\n\n- Always use tabs for indentation\n");
+        let h = health::Health::load(tmp.path()).unwrap();
+        assert!(!h.stages["project"].ok);
+        assert!(h.stages["project"].detail.contains("contradiction"), "{}", h.stages["project"].detail);
+
+        // Default (advisory): the projection goes ahead.
+        config.knowledge.strict_lint = false;
+        run_v3(tmp.path(), &config, &backend, false).unwrap();
+        assert!(std::fs::read_to_string(&md).unwrap().contains("- Never use tabs for indentation."));
+    }
+
+    /// A preview that failed skips that file with a health failure instead of
+    /// erroring out of a run that already committed its learning.
+    #[test]
+    fn strict_lint_skips_a_file_that_cannot_be_previewed() {
+        let tmp = TempDir::new().unwrap();
+        let failed = || Err(CoreError::Io("unreadable".to_string()));
+        assert!(!strict_blocked(tmp.path(), false, "my-app", failed()).unwrap());
+        assert!(strict_blocked(tmp.path(), true, "my-app", failed()).unwrap());
+        let h = health::Health::load(tmp.path()).unwrap();
+        assert!(!h.stages["project"].ok);
+        assert_eq!(h.stages["project"].detail, "my-app: IO error: unreadable");
+        assert!(!strict_blocked(tmp.path(), true, "my-app", Ok("- fine\n".to_string())).unwrap());
+    }
+
     /// Fix 2 regression: the store root IS the real ~/.retro, which on
     /// existing installs contains v2 artifacts (SQLite DB, audit log, runner
     /// log, backups) and a plan-1-era .gitignore that doesn't cover them.
@@ -0,0 +1,373 @@
+//! Canned store states for development and tests: `retro dev seed` (built
+//! with the `dev-tools` feature) and the `retro status` integration tests
+//! (`test-support`) share these scenarios, so a dashboard or status bug can
+//! be reproduced without real AI analysis.
+//! Everything is deterministic — fixed ids, dates, and bodies.
+
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde_json::json;
+
+use crate::errors::CoreError;
+use crate::store::state::RunnerState;
+use crate::store::{Node, NodeType, Scope, Store, git as store_git, index, queue};
+
+/// Scenario names accepted by [`seed`], in display order.
+pub const SCENARIOS: &[&str] = &[
+    "fresh",
+    "populated",
+    "stale-candidates",
+    "pending-queue",
+    "failing-health",
+];
+
+/// Project slug used by every project-scoped seed node.
+pub const SEED_PROJECT: &str = "demo-app";
+
+/// Populate an EMPTY store root with `scenario`. Refuses a root that already
+/// holds a knowledge store — seeding must never touch real data.
+pub fn seed(store_root: &Path, scenario: &str) -> Result<(), CoreError> {
+    if !SCENARIOS.contains(&scenario) {
+        return Err(CoreError::Config(format!(
+            "unknown scenario `{scenario}` (expected one of: {})",
+            SCENARIOS.join(", ")
+        )));
+    }
+    if store_root.join("knowledge").exists() {
+        return Err(CoreError::Config(format!(
+            "{} already contains a knowledge store — seed an empty RETRO_HOME",
+            store_root.display()
+        )));
+    }
+    let store = Store::open(store_root);
+    store.ensure_layout()?; // before ensure_repo, same as `retro init`
+    crate::store::meta::stamp(store_root)?;
+    store_git::ensure_repo(store_root)?;
+
+    match scenario {
+        "fresh" => {}
+        "populated" => write_nodes(&store, &populated_nodes())?,
+        "stale-candidates" => {
+            write_nodes(&store, &populated_nodes())?;
+            write_nodes(&store, &stale_nodes())?;
+        }
+        "pending-queue" => seed_queue(store_root)?,
+        "failing-health" => {
+            write_nodes(&store, &populated_nodes())?;
+            seed_failures(store_root)?;
+        }
+        _ => unreachable!("validated above"),
+    }
+    index::build(&store)?;
+    store_git::commit_all(store_root, &format!("retro: seed {scenario}"))?;
+    Ok(())
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").expect("valid seed date")
+}
+
+fn node(
+    id: &str,
+    scope: Scope,
+    node_type: NodeType,
+    confidence: f64,
+    day: &str,
+    body: &str,
+) -> Node {
+    Node {
+        id: id.to_string(),
+        scope,
+        node_type,
+        confidence,
+        sources: vec![format!("session:seed-{id}")],
+        created: date(day),
+        updated: date(day),
+        invalidated_by: None,
+        dismissal_reason: None,
+        section: None,
+        body: body.to_string(),
+    }
+}
+
+/// Mixed store: projected global rules, project rules, a memory node,
+/// and one invalidated rule.
+pub fn populated_nodes() -> Vec<Node> {
+    let project = || Scope::Project(SEED_PROJECT.to_string());
+    let mut vetoed = node(
+        "use-tabs",
+        Scope::Global,
+        NodeType::Preference,
+        0.75,
+        "2026-01-05",
+        "Indent with tabs.",
+    );
+    vetoed.invalidated_by = Some("user".to_string());
+    vec![
+        node(
+            "prefer-uv-over-pip",
+            Scope::Global,
+            NodeType::Rule,
+            0.85,
+            "2026-01-02",
+            "Use uv instead of pip for Python dependencies.",
+        ),
+        node(
+            "run-clippy-before-commit",
+            Scope::Global,
+            NodeType::Rule,
+            0.8,
+            "2026-01-03",
+            "Run cargo clippy before committing Rust changes.",
+        ),
+        node(
+            "terse-commit-messages",
+            Scope::Global,
+            NodeType::Preference,
+            0.72,
+            "2026-01-04",
+            "Keep commit subjects under 60 characters.",
+        ),
+        vetoed,
+        node(
+            "migrations-need-tests",
+            project(),
+            NodeType::Rule,
+            0.82,
+            "2026-01-06",
+            "Every schema migration needs a round-trip test.",
+        ),
+        node(
+            "api-error-envelope",
+            project(),
+            NodeType::Pattern,
+            0.74,
+            "2026-01-07",
+            "API errors return {\"error\": string} with a 4xx/5xx status.",
+        ),
+        node(
+            "staging-deploys-fridays",
+            project(),
+            NodeType::Memory,
+            0.6,
+            "2026-01-08",
+            "Staging deploys happen on Fridays.",
+        ),
+    ]
+}
+
+/// Sub-threshold, long-untouched nodes plus a near-duplicate pair —
+/// everything `retro lint` reports.
+pub fn stale_nodes() -> Vec<Node> {
+    vec![
+        node(
+            "maybe-prefer-rg",
+            Scope::Global,
+            NodeType::Rule,
+            0.45,
+            "2025-10-01",
+            "Maybe prefer rg over grep.",
+        ),
+        node(
+            "check-node-version",
+            Scope::Project(SEED_PROJECT.to_string()),
+            NodeType::Rule,
+            0.5,
+            "2025-10-02",
+            "Check the node version before installing.",
+        ),
+        node(
+            "run-clippy-before-commits",
+            Scope::Global,
+            NodeType::Rule,
+            0.6,
+            "2025-10-03",
+            "Run cargo clippy before committing Rust change.",
+        ),
+    ]
+}
+
+fn write_nodes(store: &Store, nodes: &[Node]) -> Result<(), CoreError> {
+    for n in nodes {
+        store.write_node(n)?;
+    }
+    Ok(())
+}
+
+/// Three queued sessions with real transcripts under `seed-transcripts/`,
+/// the oldest enqueued long ago (trips the stale-queue nudge).
+fn seed_queue(store_root: &Path) -> Result<(), CoreError> {
+    let io = |e: std::io::Error| CoreError::Io(e.to_string());
+    let dir = store_root.join("queue").join("seed-transcripts");
+    std::fs::create_dir_all(&dir).map_err(io)?;
+    let cwd = format!("/tmp/{SEED_PROJECT}");
+    for (i, enqueued_at) in [
+        "2026-01-01T09:00:00Z",
+        "2026-01-10T09:00:00Z",
+        "2026-01-10T10:00:00Z",
+    ]
+    .iter()
+    .enumerate()
+    {
+        let id = format!("seed-session-{i}");
+        let line = |n: u32, text: &str| {
+            format!(
+                r#"{{"type":"user","uuid":"{id}-{n}","sessionId":"{id}","cwd":"{cwd}","timestamp":"2026-01-10T10:00:0{n}Z","message":{{"role":"user","content":"{text}"}}}}"#
+            )
+        };
+        let path = dir.join(format!("{id}.jsonl"));
+        std::fs::write(
+            &path,
+            format!(
+                "{}\n{}\n",
+                line(0, "always run the tests first"),
+                line(1, "thanks")
+            ),
+        )
+        .map_err(io)?;
+        queue::enqueue(
+            store_root,
+            &queue::QueueEntry {
+                session_id: id,
+                transcript_path: path.display().to_string(),
+                cwd: Some(cwd.clone()),
+                enqueued_at: enqueued_at.to_string(),
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Failed push/analyze stages, an exhausted budget, and queued notifications.
+fn seed_failures(store_root: &Path) -> Result<(), CoreError> {
+    let mut health = crate::health::Health::default();
+    for (stage, ok, detail) in [
+        (
+            "analyze",
+            false,
+            "claude CLI (execute) exited with exit status: 1: rate limited",
+        ),
+        (
+            "push",
+            false,
+            "fatal: unable to access remote: Could not resolve host",
+        ),
+        ("project", true, "global: 3 rule(s)"),
+        ("run", true, "0 session(s)"),
+    ] {
+        health.stages.insert(
+            stage.to_string(),
+            crate::health::StageHealth {
+                at: "2026-01-10T12:00:00+00:00".to_string(),
+                ok,
+                detail: detail.to_string(),
+            },
+        );
+    }
+    health.save(store_root)?;
+    let mut state = RunnerState {
+        ai_calls_date: "2026-01-10".to_string(),
+        ai_calls_today: 10,
+        ..Default::default()
+    };
+    state
+        .notifications
+        .push("Learned: Use uv instead of pip for Python dependencies.".to_string());
+    state.save(store_root)
+}
+
+/// Machine-readable snapshot of a store (nodes, queue, state, health) for
+/// golden comparisons. Nodes are ordered by path for stable output.
+pub fn dump(store_root: &Path) -> Result<serde_json::Value, CoreError> {
+    let store = Store::open(store_root);
+    let mut nodes = store.load_all()?.nodes;
+    nodes.sort_by(|a, b| a.0.cmp(&b.0));
+    let nodes: Vec<serde_json::Value> = nodes
+        .iter()
+        .map(|(_, n)| {
+            json!({
+                "id": n.id,
+                "scope": n.scope.to_string(),
+                "type": n.node_type.as_str(),
+                "confidence": n.confidence,
+                "sources": n.sources,
+                "created": n.created.to_string(),
+                "updated": n.updated.to_string(),
+                "invalidated_by": n.invalidated_by,
+                "body": n.body,
+            })
+        })
+        .collect();
+    let to_value =
+        |v: serde_json::Result<serde_json::Value>| v.map_err(|e| CoreError::Parse(e.to_string()));
+    Ok(json!({
+        "nodes": nodes,
+        "queue": to_value(serde_json::to_value(queue::list(store_root)?))?,
+        "state": to_value(serde_json::to_value(RunnerState::load(store_root)?))?,
+        "health": to_value(serde_json::to_value(crate::health::Health::load(store_root)?))?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn every_scenario_seeds_a_loadable_store() {
+        for scenario in SCENARIOS {
+            let tmp = TempDir::new().unwrap();
+            seed(tmp.path(), scenario).unwrap_or_else(|e| panic!("{scenario}: {e}"));
+            let store = Store::open(tmp.path());
+            let loaded = store.load_all().unwrap();
+            assert!(
+                loaded.warnings.is_empty(),
+                "{scenario}: {:?}",
+                loaded.warnings
+            );
+            assert!(
+                !store_git::has_changes(tmp.path()).unwrap(),
+                "{scenario} committed"
+            );
+            // status-equivalent reads all succeed
+            RunnerState::load(tmp.path()).unwrap();
+            crate::health::Health::load(tmp.path()).unwrap();
+            queue::list(tmp.path()).unwrap();
+            let dumped = dump(tmp.path()).unwrap();
+            assert_eq!(
+                dumped["nodes"].as_array().unwrap().len(),
+                loaded.nodes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn scenarios_produce_their_signature_state() {
+        let tmp = TempDir::new().unwrap();
+        seed(tmp.path(), "failing-health").unwrap();
+        let h = crate::health::Health::load(tmp.path()).unwrap();
+        assert_eq!(h.warnings().len(), 2);
+
+        let tmp = TempDir::new().unwrap();
+        seed(tmp.path(), "pending-queue").unwrap();
+        assert_eq!(queue::list(tmp.path()).unwrap().len(), 3);
+
+        let tmp = TempDir::new().unwrap();
+        seed(tmp.path(), "stale-candidates").unwrap();
+        let report =
+            crate::lint::run_lint(&Store::open(tmp.path()), &crate::config::Config::default())
+                .unwrap();
+        assert!(report.findings.iter().any(|f| f.kind == "near-duplicate"));
+        assert!(report.findings.iter().any(|f| f.kind == "stale-candidate"));
+    }
+
+    #[test]
+    fn refuses_existing_store_and_unknown_scenario() {
+        let tmp = TempDir::new().unwrap();
+        assert!(seed(tmp.path(), "nope").is_err());
+        seed(tmp.path(), "fresh").unwrap();
+        let err = seed(tmp.path(), "populated").unwrap_err().to_string();
+        assert!(err.contains("already contains"), "got: {err}");
+    }
+}
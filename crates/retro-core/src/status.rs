@@ -0,0 +1,174 @@
+//! "Recommended next steps" for `retro status`: a pure function from a
+//! [`StatusSnapshot`] (gathered by the CLI) to prioritized suggestions, so a
+//! user looking at counts also sees the command that acts on them.
+
+use crate::lock::LockState;
+
+/// Queue length at which `retro run` is suggested (smaller queues drain on
+/// their own at the next session end).
+pub const QUEUE_NUDGE: usize = 5;
+
+/// State of the `claude` CLI as far as status can tell without an AI call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClaudeCli {
+    #[default]
+    Ok,
+    Missing,
+    /// The last analysis failed with an authentication error.
+    Unauthenticated,
+    /// The heuristic backend is configured; the CLI isn't needed.
+    NotUsed,
+}
+
+/// Everything the recommendations look at.
+#[derive(Debug, Clone, Default)]
+pub struct StatusSnapshot {
+    pub queued: usize,
+    pub ai_budget_left: u32,
+    pub claude_cli: ClaudeCli,
+    pub lock: Option<LockState>,
+    /// Store lint findings (near-duplicates, stale candidates).
+    pub lint_findings: usize,
+    /// Projected CLAUDE.md files over the size limit.
+    pub oversize_projections: usize,
+    pub health_warnings: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recommendation {
+    /// Lower is more urgent.
+    pub priority: u8,
+    pub message: String,
+}
+
+/// Suggestions ordered by priority (most urgent first); empty when there is
+/// nothing to do.
+pub fn recommend(s: &StatusSnapshot) -> Vec<Recommendation> {
+    let mut out = Vec::new();
+    let mut push = |priority: u8, message: String| out.push(Recommendation { priority, message });
+
+    match s.claude_cli {
+        ClaudeCli::Missing => push(
+            0,
+            "claude CLI not found — install Claude Code (or set [ai] backend = \"heuristic\")"
+                .to_string(),
+        ),
+        ClaudeCli::Unauthenticated => push(
+            0,
+            "claude CLI is not logged in — run `claude /login`, then `retro run`".to_string(),
+        ),
+        ClaudeCli::Ok | ClaudeCli::NotUsed => {}
+    }
+    if let Some(LockState::Stale(pid)) = s.lock {
+        push(
+            1,
+            format!(
+                "stale run.lock (PID {pid} is gone) — the next run clears it, or delete ~/.retro/run.lock"
+            ),
+        );
+    }
+    if s.queued >= QUEUE_NUDGE {
+        if s.ai_budget_left == 0 && s.claude_cli != ClaudeCli::NotUsed {
+            push(
+                2,
+                format!(
+                    "{} sessions queued but today's AI budget is spent — `retro run --heuristic`, or raise [runner] max_ai_calls_per_day",
+                    s.queued
+                ),
+            );
+        } else {
+            push(2, format!("{} sessions queued — `retro run` to analyze them now", s.queued));
+        }
+    }
+    if s.health_warnings > 0 {
+        push(
+            3,
+            format!("{} health warning(s) — `retro doctor` for details", s.health_warnings),
+        );
+    }
+    if s.oversize_projections > 0 {
+        push(
+            4,
+            format!(
+                "{} CLAUDE.md file(s) over the size limit — `retro lint` shows what to trim",
+                s.oversize_projections
+            ),
+        );
+    }
+    if s.lint_findings > 0 {
+        push(
+            5,
+            format!(
+                "{} lint finding(s) — `retro lint`, then `retro merge` near-duplicates",
+                s.lint_findings
+            ),
+        );
+    }
+    out.sort_by_key(|r| r.priority);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(s: &StatusSnapshot) -> Vec<String> {
+        recommend(s).into_iter().map(|r| r.message).collect()
+    }
+
+    fn has(s: &StatusSnapshot, needle: &str) -> bool {
+        messages(s).iter().any(|m| m.contains(needle))
+    }
+
+    #[test]
+    fn all_caught_up_recommends_nothing() {
+        let s = StatusSnapshot {
+            queued: QUEUE_NUDGE - 1,
+            ai_budget_left: 10,
+            lock: Some(LockState::Held(42)),
+            ..Default::default()
+        };
+        assert!(recommend(&s).is_empty(), "{:?}", messages(&s));
+    }
+
+    #[test]
+    fn each_trigger_suggests_its_command() {
+        let base = StatusSnapshot {
+            ai_budget_left: 10,
+            ..Default::default()
+        };
+        let queued = StatusSnapshot { queued: 12, ..base.clone() };
+        assert!(has(&queued, "12 sessions queued — `retro run`"));
+        let spent = StatusSnapshot { ai_budget_left: 0, ..queued.clone() };
+        assert!(has(&spent, "--heuristic"));
+        let missing = StatusSnapshot { claude_cli: ClaudeCli::Missing, ..base.clone() };
+        assert!(has(&missing, "install Claude Code"));
+        let unauth = StatusSnapshot { claude_cli: ClaudeCli::Unauthenticated, ..base.clone() };
+        assert!(has(&unauth, "claude /login"));
+        let stale = StatusSnapshot { lock: Some(LockState::Stale(999)), ..base.clone() };
+        assert!(has(&stale, "PID 999"));
+        let health = StatusSnapshot { health_warnings: 2, ..base.clone() };
+        assert!(has(&health, "retro doctor"));
+        let oversize = StatusSnapshot { oversize_projections: 1, ..base.clone() };
+        assert!(has(&oversize, "size limit"));
+        let lint = StatusSnapshot { lint_findings: 3, ..base };
+        assert!(has(&lint, "retro merge"));
+    }
+
+    #[test]
+    fn ordered_by_priority() {
+        let s = StatusSnapshot {
+            queued: 20,
+            ai_budget_left: 5,
+            claude_cli: ClaudeCli::Unauthenticated,
+            lint_findings: 1,
+            health_warnings: 1,
+            ..Default::default()
+        };
+        let recs = recommend(&s);
+        assert_eq!(recs.len(), 4);
+        assert!(recs.windows(2).all(|w| w[0].priority <= w[1].priority));
+        assert!(recs[0].message.contains("login"));
+        assert!(recs[3].message.contains("lint"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Create candidates held back by `[analysis] max_new_nodes_per_run`: one
+//! over-enthusiastic batch must not flood the store, but its evidence isn't
+//! thrown away either. Deferred candidates live at `state/deferred.json`
+//! (machine-local, like the queue). A later create that re-observes one —
+//! same scope, near-duplicate body — absorbs its sources and confidence, and
+//! the candidate is dropped.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::Scope;
+use crate::errors::CoreError;
+use crate::util::normalized_similarity;
+
+/// Oldest candidates are dropped beyond this many.
+const MAX_DEFERRED: usize = 200;
+
+/// Body similarity above which a new create re-observes a candidate (same
+/// bar as near-duplicate lint).
+const REOBSERVED_SIMILARITY: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeferredCandidate {
+    /// `Scope` display form (`global`, `project/<slug>`).
+    pub scope: String,
+    pub node_type: String,
+    pub body: String,
+    pub confidence: f64,
+    pub sources: Vec<String>,
+    /// RFC3339.
+    pub deferred_at: String,
+}
+
+fn deferred_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("deferred.json")
+}
+
+/// Missing or corrupt file loads empty (deferral is best-effort memory).
+pub fn load(store_root: &Path) -> Vec<DeferredCandidate> {
+    std::fs::read_to_string(deferred_path(store_root))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Save, keeping the newest [`MAX_DEFERRED`] candidates.
+pub fn save(store_root: &Path, candidates: &[DeferredCandidate]) -> Result<(), CoreError> {
+    let keep = &candidates[candidates.len().saturating_sub(MAX_DEFERRED)..];
+    let path = deferred_path(store_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CoreError::Io(e.to_string()))?;
+    }
+    let json = serde_json::to_string_pretty(keep).map_err(|e| CoreError::Parse(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| CoreError::Io(e.to_string()))
+}
+
+/// Remove and return the candidate `body` in `scope` re-observes, if any.
+pub fn take_match(
+    candidates: &mut Vec<DeferredCandidate>,
+    scope: &Scope,
+    body: &str,
+) -> Option<DeferredCandidate> {
+    let scope = scope.to_string();
+    let i = candidates.iter().position(|c| {
+        c.scope == scope && normalized_similarity(&c.body, body) > REOBSERVED_SIMILARITY
+    })?;
+    Some(candidates.remove(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn candidate(scope: &str, body: &str) -> DeferredCandidate {
+        DeferredCandidate {
+            scope: scope.to_string(),
+            node_type: "rule".to_string(),
+            body: body.to_string(),
+            confidence: 0.6,
+            sources: vec!["session:a".to_string()],
+            deferred_at: "2026-07-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn roundtrip_cap_and_reobserved_match() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load(tmp.path()).is_empty());
+        let many: Vec<_> = (0..MAX_DEFERRED + 5)
+            .map(|i| candidate("global", &format!("rule {i}")))
+            .collect();
+        save(tmp.path(), &many).unwrap();
+        let loaded = load(tmp.path());
+        assert_eq!(loaded.len(), MAX_DEFERRED);
+        assert_eq!(loaded[0].body, "rule 5", "oldest dropped");
+
+        let mut list = vec![
+            candidate("project/app", "Use uv for Python installs."),
+            candidate("global", "Use uv for Python installs."),
+        ];
+        let hit = take_match(&mut list, &Scope::Global, "Use uv for python installs").unwrap();
+        assert_eq!(hit.scope, "global");
+        assert_eq!(list.len(), 1);
+        assert!(take_match(&mut list, &Scope::Global, "Use uv for Python installs.").is_none());
+    }
+}
@@ -2,15 +2,17 @@
 //! All commands run against an explicit root via `git -C <root>`.
 //! Commits are local-first; pushing is strictly best-effort.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::errors::CoreError;
+use crate::logging;
 
 /// Outcome of a best-effort push. Failures are data, not errors —
 /// callers record them in health, they never abort a pipeline.
 #[must_use]
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum PushOutcome {
     Pushed,
     NoRemote,
@@ -18,12 +20,23 @@ pub enum PushOutcome {
 }
 
 fn git(root: &Path, args: &[&str]) -> Result<std::process::Output, CoreError> {
-    Command::new("git")
+    log::trace!(target: logging::GIT, "git -C {} {}", root.display(), args.join(" "));
+    let out = Command::new("git")
         .arg("-C")
         .arg(root)
         .args(args)
         .output()
-        .map_err(|e| CoreError::Io(format!("failed to run git: {e}")))
+        .map_err(|e| CoreError::Io(format!("failed to run git: {e}")))?;
+    if !out.status.success() {
+        log::debug!(
+            target: logging::GIT,
+            "git {} failed ({}): {}",
+            args.join(" "),
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(out)
 }
 
 pub fn is_repo(root: &Path) -> bool {
@@ -36,6 +49,26 @@ pub fn head_exists(root: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Full hash of HEAD in the repository containing `dir`. None outside a
+/// repository and before its first commit.
+pub fn head_commit(dir: &Path) -> Option<String> {
+    stdout_of(dir, &["rev-parse", "--verify", "--quiet", "HEAD"])
+}
+
+/// Branch checked out in the repository containing `dir`. None when HEAD is
+/// detached or `dir` isn't in a repository.
+pub fn current_branch(dir: &Path) -> Option<String> {
+    stdout_of(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"])
+}
+
+fn stdout_of(dir: &Path, args: &[&str]) -> Option<String> {
+    let out = git(dir, args).ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
 /// Apply the store repo's local git config. Safe to call repeatedly.
 /// Must also be applied on the clone path (`retro init --from`), which
 /// bypasses `ensure_repo`'s create branch.
@@ -129,6 +162,210 @@ pub fn commit_all(root: &Path, message: &str) -> Result<bool, CoreError> {
     Ok(true)
 }
 
+/// Stage and commit only `paths` (files inside the store at `root`),
+/// leaving any other uncommitted change alone — the next run commits those
+/// as the user's own edits. Returns false when none of `paths` changed.
+pub fn commit_paths(root: &Path, paths: &[PathBuf], message: &str) -> Result<bool, CoreError> {
+    // A path that was never written and isn't tracked matches nothing, and
+    // git rejects a pathspec that matches nothing.
+    // Relative pathspecs: an absolute one through a symlinked store root
+    // would be "outside repository".
+    let mut spec: Vec<&str> = Vec::new();
+    for path in paths {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let Some(p) = rel.to_str() else {
+            return Err(CoreError::Io(format!("non-UTF-8 path: {}", path.display())));
+        };
+        if path.exists() || is_tracked(root, p) {
+            spec.push(p);
+        }
+    }
+    if spec.is_empty() {
+        return Ok(false);
+    }
+    let mut status = vec!["status", "--porcelain", "--"];
+    status.extend(&spec);
+    let out = git(root, &status)?;
+    if !out.status.success() || out.stdout.is_empty() {
+        return Ok(false);
+    }
+    let mut add = vec!["add", "-A", "--"];
+    add.extend(&spec);
+    run_checked(root, &add)?;
+    let mut commit = vec!["commit", "-m", message, "--"];
+    commit.extend(&spec);
+    run_checked(root, &commit)?;
+    Ok(true)
+}
+
+fn is_tracked(root: &Path, path: &str) -> bool {
+    git(root, &["ls-files", "--error-unmatch", "--", path])
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Prior contents of the files an operation is about to write, captured so
+/// a failed write or commit can put the store back instead of leaving the
+/// writes uncommitted (the next run would sweep them up as a "user: edit").
+#[derive(Debug, Default)]
+pub struct Rollback {
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl Rollback {
+    /// Snapshot `paths` (None for a file that doesn't exist yet).
+    pub fn capture(paths: &[PathBuf]) -> Self {
+        Self {
+            files: paths
+                .iter()
+                .map(|p| (p.clone(), std::fs::read(p).ok()))
+                .collect(),
+        }
+    }
+
+    /// Put every captured file back: rewrite its old bytes, or remove it if
+    /// it didn't exist.
+    pub fn restore(&self) -> Result<(), CoreError> {
+        for (path, before) in &self.files {
+            match before {
+                Some(bytes) => std::fs::write(path, bytes),
+                None if path.exists() => std::fs::remove_file(path),
+                None => Ok(()),
+            }
+            .map_err(|e| CoreError::Io(format!("rollback of {}: {e}", path.display())))?;
+        }
+        Ok(())
+    }
+
+    /// Restore the files after `err` and return it, noting the rollback (or
+    /// appending the rollback's own failure).
+    pub fn undo(&self, err: CoreError) -> CoreError {
+        match self.restore() {
+            Ok(()) => CoreError::Io(format!("{err} — changes rolled back")),
+            Err(r) => CoreError::Io(format!("{err}; {r}")),
+        }
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|(p, _)| p.clone()).collect()
+    }
+}
+
+/// [`commit_paths`] over `rollback`'s files, restoring them when the commit
+/// fails. The commit error is returned either way; a rollback failure is
+/// appended.
+pub fn commit_or_rollback(
+    root: &Path,
+    message: &str,
+    rollback: &Rollback,
+) -> Result<bool, CoreError> {
+    match commit_paths(root, &rollback.paths(), message) {
+        Ok(committed) => Ok(committed),
+        Err(e) => {
+            let e = rollback.undo(e);
+            // Unstage whatever `add` got to (best-effort: a stuck index lock
+            // fails this too, and the restored tree is what matters).
+            let _ = git(root, &["reset", "-q"]);
+            Err(e)
+        }
+    }
+}
+
+/// Prefix of the reasoning lines in a learn commit's body.
+pub const WHY_PREFIX: &str = "Why (";
+
+/// Longest reasoning excerpt a learn commit keeps.
+const WHY_MAX_CHARS: usize = 300;
+
+/// One body line of a learn commit: the batch's reasoning for `slug`, cut
+/// to an excerpt, and the digest of the response it came from (if any).
+pub fn why_line(slug: &str, reasoning: &str, response_digest: &str) -> String {
+    let reasoning = one_line(reasoning);
+    let excerpt = crate::util::truncate_str(&reasoning, WHY_MAX_CHARS);
+    if response_digest.is_empty() {
+        format!("{WHY_PREFIX}{slug}): {excerpt}")
+    } else {
+        format!("{WHY_PREFIX}{slug}): {excerpt} [response {response_digest}]")
+    }
+}
+
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A commit that touched a file.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FileCommit {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    pub subject: String,
+    /// The commit's [`why_line`]s.
+    pub why: Vec<String>,
+}
+
+/// The last `limit` commits that touched `path` (inside the store at
+/// `root`), newest first. A store without commits yields an empty list.
+pub fn file_history(root: &Path, path: &Path, limit: usize) -> Result<Vec<FileCommit>, CoreError> {
+    if !head_exists(root) {
+        return Ok(Vec::new());
+    }
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    let out = git(
+        root,
+        &[
+            "log",
+            "-n",
+            &limit.to_string(),
+            "--date=short",
+            "--format=%ad%x1f%s%x1f%b%x1e",
+            "--",
+            &rel.to_string_lossy(),
+        ],
+    )?;
+    if !out.status.success() {
+        return Err(CoreError::Io(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let date = fields.next().filter(|d| !d.is_empty())?.to_string();
+            let subject = fields.next().unwrap_or_default().to_string();
+            let why = fields
+                .next()
+                .unwrap_or_default()
+                .lines()
+                .filter(|l| l.starts_with(WHY_PREFIX))
+                .map(str::to_string)
+                .collect();
+            Some(FileCommit { date, subject, why })
+        })
+        .collect())
+}
+
+/// Commit subjects since `since` (any date `git log --since` accepts),
+/// newest first. A store without commits yields an empty list.
+pub fn subjects_since(root: &Path, since: &str) -> Result<Vec<String>, CoreError> {
+    if !head_exists(root) {
+        return Ok(Vec::new());
+    }
+    let since_arg = format!("--since={since}");
+    let out = git(root, &["log", &since_arg, "--format=%s"])?;
+    if !out.status.success() {
+        return Err(CoreError::Io(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
 /// True if HEAD has commits its upstream doesn't — or no upstream is set
 /// yet (the first `push -u` establishes it), which also warrants a push
 /// attempt. Lets the runner sweep up commits made between runs (dashboard
@@ -204,6 +441,114 @@ mod tests {
         assert!(commit_all(tmp.path(), "user: edit note").unwrap());
     }
 
+    #[test]
+    fn failed_commit_rolls_written_files_back() {
+        let tmp = TempDir::new().unwrap();
+        ensure_repo(tmp.path()).unwrap();
+        let kept = tmp.path().join("kept.md");
+        let added = tmp.path().join("added.md");
+        std::fs::write(&kept, "original").unwrap();
+        commit_all(tmp.path(), "seed").unwrap();
+
+        let rollback = Rollback::capture(&[kept.clone(), added.clone()]);
+        std::fs::write(&kept, "rewritten").unwrap();
+        std::fs::write(&added, "new").unwrap();
+        // A held index lock makes `git add`/`git commit` fail.
+        std::fs::write(tmp.path().join(".git/index.lock"), "").unwrap();
+        let err = commit_or_rollback(tmp.path(), "retro: merge", &rollback)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("rolled back"), "got: {err}");
+        assert_eq!(std::fs::read_to_string(&kept).unwrap(), "original");
+        assert!(!added.exists());
+
+        std::fs::remove_file(tmp.path().join(".git/index.lock")).unwrap();
+        assert!(!has_changes(tmp.path()).unwrap(), "store is back to its committed state");
+        std::fs::write(&kept, "rewritten").unwrap();
+        assert!(commit_or_rollback(tmp.path(), "retro: merge", &rollback).unwrap());
+    }
+
+    #[test]
+    fn commit_paths_leaves_other_edits_uncommitted() {
+        let tmp = TempDir::new().unwrap();
+        ensure_repo(tmp.path()).unwrap();
+        let ours = tmp.path().join("ours.md");
+        let hand = tmp.path().join("hand.md");
+        std::fs::write(&ours, "v1").unwrap();
+        std::fs::write(&hand, "v1").unwrap();
+        commit_all(tmp.path(), "seed").unwrap();
+
+        std::fs::write(&ours, "v2").unwrap();
+        std::fs::write(&hand, "edited by hand").unwrap();
+        let never_written = tmp.path().join("missing.md");
+        let paths = [ours.clone(), never_written];
+        assert!(commit_paths(tmp.path(), &paths, "user: merge").unwrap());
+        assert!(!commit_paths(tmp.path(), &paths, "user: merge").unwrap(), "nothing left");
+        let out = git(tmp.path(), &["status", "--porcelain"]).unwrap();
+        assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "M hand.md");
+    }
+
+    #[test]
+    fn undo_restores_files_after_a_failed_write() {
+        let tmp = TempDir::new().unwrap();
+        let kept = tmp.path().join("kept.md");
+        std::fs::write(&kept, "original").unwrap();
+        let rollback = Rollback::capture(std::slice::from_ref(&kept));
+        std::fs::write(&kept, "half-written").unwrap();
+        let err = rollback.undo(CoreError::Io("disk full".into())).to_string();
+        assert!(err.contains("disk full — changes rolled back"), "got: {err}");
+        assert_eq!(std::fs::read_to_string(&kept).unwrap(), "original");
+    }
+
+    #[test]
+    fn subjects_since_lists_recent_commits_newest_first() {
+        let tmp = TempDir::new().unwrap();
+        assert!(subjects_since(tmp.path(), "1 week ago").unwrap().is_empty());
+        ensure_repo(tmp.path()).unwrap();
+        std::fs::write(tmp.path().join("note.md"), "hello").unwrap();
+        commit_all(tmp.path(), "retro: learn note").unwrap();
+        let subjects = subjects_since(tmp.path(), "1 week ago").unwrap();
+        assert_eq!(subjects[0], "retro: learn note");
+        assert!(subjects_since(tmp.path(), "2099-01-01").unwrap().is_empty());
+    }
+
+    #[test]
+    fn file_history_keeps_the_why_lines_of_commits_touching_the_file() {
+        let tmp = TempDir::new().unwrap();
+        let note = tmp.path().join("note.md");
+        assert!(file_history(tmp.path(), &note, 5).unwrap().is_empty());
+        ensure_repo(tmp.path()).unwrap();
+        std::fs::write(&note, "hello").unwrap();
+        let why = why_line("app", "User  corrected\nthe runner twice.", "0123abcd");
+        assert_eq!(why, "Why (app): User corrected the runner twice. [response 0123abcd]");
+        commit_all(tmp.path(), &format!("retro: learn 1 node(s), update 0\n\nnote\n{why}"))
+            .unwrap();
+        std::fs::write(tmp.path().join("other.md"), "x").unwrap();
+        commit_all(tmp.path(), "user: edit other").unwrap();
+
+        let history = file_history(tmp.path(), &note, 5).unwrap();
+        assert_eq!(history.len(), 1, "{history:?}");
+        assert_eq!(history[0].subject, "retro: learn 1 node(s), update 0");
+        assert_eq!(history[0].why, vec![why]);
+        assert_eq!(history[0].date.len(), 10);
+    }
+
+    #[test]
+    fn head_commit_and_branch_read_the_enclosing_repo() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!((head_commit(tmp.path()), current_branch(tmp.path())), (None, None));
+        ensure_repo(tmp.path()).unwrap();
+        let sub = tmp.path().join("knowledge");
+        std::fs::create_dir_all(&sub).unwrap();
+        let head = head_commit(&sub).unwrap();
+        assert_eq!(head.len(), 40);
+        git(tmp.path(), &["checkout", "-q", "-b", "topic"]).unwrap();
+        assert_eq!(current_branch(&sub).as_deref(), Some("topic"));
+        git(tmp.path(), &["checkout", "-q", "--detach"]).unwrap();
+        assert_eq!(current_branch(&sub), None, "detached");
+        assert_eq!(head_commit(&sub), Some(head));
+    }
+
     #[test]
     fn has_remote_false_on_fresh_repo() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,286 @@
+//! Manual node merge (`retro merge`): the user-driven counterpart of the
+//! analysis `merge_nodes` operation. The survivor absorbs the losers'
+//! evidence; losers are invalidated with `invalidated_by = <survivor id>`,
+//! exactly as analysis records a merge, so projections pick up the result
+//! on the next reprojection and history stays in git.
+
+use super::{Node, Scope, Store};
+use crate::errors::CoreError;
+
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    /// Survivor reference; defaults to the first listed node.
+    pub into: Option<String>,
+    /// Append the losers' bodies to the survivor's instead of dropping them.
+    pub keep_both_bodies: bool,
+    /// Allow merging nodes from different scopes (the survivor's scope wins).
+    pub force: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    pub survivor: Node,
+    pub merged: Vec<Node>,
+}
+
+impl MergeOutcome {
+    /// Every scope whose projection the merge changed (survivor first).
+    pub fn affected_scopes(&self) -> Vec<Scope> {
+        let mut scopes = vec![self.survivor.scope.clone()];
+        for n in &self.merged {
+            if !scopes.contains(&n.scope) {
+                scopes.push(n.scope.clone());
+            }
+        }
+        scopes
+    }
+}
+
+/// Resolve a user-supplied node reference: `id`, a unique id prefix, or
+/// `<scope>/<id>` (`global/use-uv`, `project/my-app/use-uv`) to disambiguate
+/// an id present in several scopes. Only active nodes are candidates.
+pub fn resolve(store: &Store, reference: &str) -> Result<Node, CoreError> {
+    let (scope, id) = match reference.rsplit_once('/') {
+        Some((scope, id)) => (Some(Scope::parse(scope)?), id),
+        None => (None, reference),
+    };
+    let active: Vec<Node> = store
+        .load_all()?
+        .nodes
+        .into_iter()
+        .map(|(_, n)| n)
+        .filter(|n| n.is_active() && scope.as_ref().is_none_or(|s| *s == n.scope))
+        .collect();
+    let exact: Vec<&Node> = active.iter().filter(|n| n.id == id).collect();
+    let candidates = if exact.is_empty() {
+        active.iter().filter(|n| n.id.starts_with(id)).collect()
+    } else {
+        exact
+    };
+    match candidates.as_slice() {
+        [] => Err(CoreError::Config(format!("no active node matches `{reference}`"))),
+        [one] => Ok((*one).clone()),
+        many => Err(CoreError::Config(format!(
+            "`{reference}` is ambiguous: {}",
+            many.iter()
+                .map(|n| format!("{}/{}", n.scope, n.id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Merge the referenced nodes into one. Sources are unioned, confidence is
+/// the maximum, and every loser is invalidated in favour of the survivor.
+/// Refuses cross-scope merges unless `opts.force`.
+pub fn merge_nodes(
+    store: &Store,
+    references: &[String],
+    opts: &MergeOptions,
+) -> Result<MergeOutcome, CoreError> {
+    let mut nodes: Vec<Node> = Vec::new();
+    for r in references {
+        let node = resolve(store, r)?;
+        if nodes.iter().any(|n| n.scope == node.scope && n.id == node.id) {
+            return Err(CoreError::Config(format!(
+                "`{}/{}` is listed more than once",
+                node.scope, node.id
+            )));
+        }
+        nodes.push(node);
+    }
+    let mut survivor = match &opts.into {
+        Some(r) => {
+            let into = resolve(store, r)?;
+            nodes.retain(|n| !(n.scope == into.scope && n.id == into.id));
+            into
+        }
+        None if !nodes.is_empty() => nodes.remove(0),
+        None => return Err(CoreError::Config("nothing to merge".to_string())),
+    };
+    let losers = nodes;
+    if losers.is_empty() {
+        return Err(CoreError::Config(
+            "need at least two distinct nodes to merge".to_string(),
+        ));
+    }
+    let foreign = losers.iter().find(|n| n.scope != survivor.scope);
+    if let Some(other) = foreign.filter(|_| !opts.force) {
+        return Err(CoreError::Config(format!(
+            "`{}` is {} but `{}` is {} — pass --force to merge across scopes",
+            survivor.id, survivor.scope, other.id, other.scope
+        )));
+    }
+
+    for loser in &losers {
+        for s in &loser.sources {
+            if !survivor.sources.contains(s) {
+                survivor.sources.push(s.clone());
+            }
+        }
+        survivor.confidence = survivor.confidence.max(loser.confidence);
+        if opts.keep_both_bodies && loser.body.trim() != survivor.body.trim() {
+            survivor.body = format!("{}\n\n{}", survivor.body.trim_end(), loser.body.trim());
+        }
+    }
+    survivor.updated = chrono::Utc::now().date_naive();
+    // Survivor first: a loser never points (invalidated_by) at a survivor
+    // that isn't on disk yet, whatever fails in between.
+    store.write_node(&survivor)?;
+    for loser in &losers {
+        store.invalidate(&loser.scope, &loser.id, &survivor.id)?;
+    }
+    Ok(MergeOutcome {
+        survivor,
+        merged: losers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::NodeType;
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    fn node(id: &str, scope: Scope, confidence: f64, source: &str, body: &str) -> Node {
+        let date = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        Node {
+            id: id.to_string(),
+            scope,
+            node_type: NodeType::Rule,
+            confidence,
+            sources: vec![source.to_string()],
+            created: date,
+            updated: date,
+            invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
+            body: body.to_string(),
+        }
+    }
+
+    fn store_with(tmp: &TempDir, nodes: &[Node]) -> Store {
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        for n in nodes {
+            store.write_node(n).unwrap();
+        }
+        store
+    }
+
+    fn refs(r: &[&str]) -> Vec<String> {
+        r.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn merge_unions_sources_takes_max_confidence_and_invalidates_losers() {
+        let tmp = TempDir::new().unwrap();
+        let store = store_with(
+            &tmp,
+            &[
+                node("use-uv", Scope::Global, 0.6, "session:a", "Use uv."),
+                node("prefer-uv-over-pip", Scope::Global, 0.8, "session:b", "Prefer uv over pip."),
+            ],
+        );
+        let out = merge_nodes(&store, &refs(&["use-uv", "prefer-uv"]), &MergeOptions::default())
+            .unwrap();
+        assert_eq!(out.survivor.id, "use-uv");
+        assert_eq!(out.survivor.body, "Use uv.");
+        assert_eq!(out.survivor.sources, vec!["session:a", "session:b"]);
+        assert!((out.survivor.confidence - 0.8).abs() < 1e-9);
+        let loser = store.get(&Scope::Global, "prefer-uv-over-pip").unwrap().unwrap();
+        assert_eq!(loser.invalidated_by.as_deref(), Some("use-uv"));
+    }
+
+    #[test]
+    fn into_and_keep_both_bodies() {
+        let tmp = TempDir::new().unwrap();
+        let store = store_with(
+            &tmp,
+            &[
+                node("a-rule", Scope::Global, 0.7, "session:a", "Run clippy."),
+                node("b-rule", Scope::Global, 0.7, "session:b", "Run clippy with -D warnings."),
+            ],
+        );
+        let opts = MergeOptions {
+            into: Some("b-rule".to_string()),
+            keep_both_bodies: true,
+            force: false,
+        };
+        let out = merge_nodes(&store, &refs(&["a-rule", "b-rule"]), &opts).unwrap();
+        assert_eq!(out.survivor.id, "b-rule");
+        assert_eq!(out.survivor.body, "Run clippy with -D warnings.\n\nRun clippy.");
+        assert_eq!(out.merged.len(), 1);
+    }
+
+    #[test]
+    fn cross_scope_merge_needs_force() {
+        let tmp = TempDir::new().unwrap();
+        let project = Scope::Project("my-app".to_string());
+        let store = store_with(
+            &tmp,
+            &[
+                node("use-uv", Scope::Global, 0.8, "session:a", "Use uv."),
+                node("uv-here", project.clone(), 0.8, "session:b", "Use uv here."),
+            ],
+        );
+        let err = merge_nodes(&store, &refs(&["use-uv", "uv-here"]), &MergeOptions::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("--force"), "got: {err}");
+        assert!(store.get(&project, "uv-here").unwrap().unwrap().is_active());
+
+        let opts = MergeOptions {
+            force: true,
+            ..Default::default()
+        };
+        let out = merge_nodes(&store, &refs(&["use-uv", "uv-here"]), &opts).unwrap();
+        assert_eq!(out.affected_scopes(), vec![Scope::Global, project]);
+    }
+
+    #[test]
+    fn resolve_prefixes_scopes_and_ambiguity() {
+        let tmp = TempDir::new().unwrap();
+        let project = Scope::Project("my-app".to_string());
+        let store = store_with(
+            &tmp,
+            &[
+                node("use-uv", Scope::Global, 0.8, "session:a", "Use uv."),
+                node("use-uv", project.clone(), 0.8, "session:b", "Use uv."),
+                node("use-tabs", Scope::Global, 0.8, "session:c", "Tabs."),
+            ],
+        );
+        assert!(resolve(&store, "use-uv").unwrap_err().to_string().contains("ambiguous"));
+        assert_eq!(resolve(&store, "project/my-app/use-uv").unwrap().scope, project);
+        assert_eq!(resolve(&store, "use-t").unwrap().id, "use-tabs");
+        assert!(resolve(&store, "nope").is_err());
+        let dup = merge_nodes(
+            &store,
+            &refs(&["global/use-uv", "global/use-uv"]),
+            &MergeOptions::default(),
+        );
+        assert!(dup.unwrap_err().to_string().contains("more than once"));
+    }
+
+    #[test]
+    fn merged_loser_drops_out_of_the_projection() {
+        let tmp = TempDir::new().unwrap();
+        let store = store_with(
+            &tmp,
+            &[
+                node("use-uv", Scope::Global, 0.8, "session:a", "Use uv for Python."),
+                node("uv-not-pip", Scope::Global, 0.8, "session:b", "Never use pip directly."),
+            ],
+        );
+        let md = tmp.path().join("CLAUDE.md");
+        crate::projection::local_md::project_global_md(&store, &md, 0.7, None).unwrap();
+        assert!(std::fs::read_to_string(&md).unwrap().contains("Never use pip directly."));
+
+        merge_nodes(&store, &refs(&["use-uv", "uv-not-pip"]), &MergeOptions::default()).unwrap();
+        crate::projection::local_md::project_global_md(&store, &md, 0.7, None).unwrap();
+        let projected = std::fs::read_to_string(&md).unwrap();
+        assert!(projected.contains("Use uv for Python."));
+        assert!(!projected.contains("Never use pip directly."));
+    }
+}
@@ -0,0 +1,174 @@
+//! Store format marker at `<store>/store.toml`, committed with the knowledge
+//! so every machine sharing the repo sees it. Records the node file format
+//! and the retro version that last wrote the store; an older binary refuses
+//! to run against a newer format instead of rewriting nodes it can't parse.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::CoreError;
+
+/// Node file format this binary reads and writes. Bump when a change would
+/// make older binaries misparse or drop data (e.g. a new frontmatter key —
+/// strict parsing rejects unknown keys).
+///
+/// 2: optional `dismissal_reason` key.
+/// 3: optional `section` key.
+pub const STORE_FORMAT: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreMeta {
+    pub format: u32,
+    /// `CARGO_PKG_VERSION` of the newest binary that wrote the store: an
+    /// older binary stamping the store keeps it, so the marker tells every
+    /// machine which version the store has moved up to
+    /// ([`crate::version::store_skew`]).
+    pub written_by: String,
+    /// Build commit ([`crate::version::BUILD`]) of the binary that moved
+    /// the store to `written_by`, when it knew one. Not restamped by other
+    /// builds of the same version: the file is shared by every machine and
+    /// dev build, and a per-build key would change on each. Older binaries
+    /// ignore the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+impl StoreMeta {
+    pub fn current() -> Self {
+        StoreMeta {
+            format: STORE_FORMAT,
+            written_by: env!("CARGO_PKG_VERSION").to_string(),
+            commit: crate::version::BUILD.known_commit().map(String::from),
+        }
+    }
+
+    /// None when the marker doesn't exist yet (stores predating it are format 1).
+    pub fn load(store_root: &Path) -> Result<Option<Self>, CoreError> {
+        match std::fs::read_to_string(meta_path(store_root)) {
+            Ok(content) => toml::from_str(&content)
+                .map(Some)
+                .map_err(|e| CoreError::Parse(format!("store.toml: {e}"))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(CoreError::Io(e.to_string())),
+        }
+    }
+}
+
+fn meta_path(store_root: &Path) -> PathBuf {
+    store_root.join("store.toml")
+}
+
+/// Refuse a store written in a newer format than this binary understands.
+pub fn check_compatible(store_root: &Path) -> Result<(), CoreError> {
+    match StoreMeta::load(store_root)? {
+        Some(meta) if meta.format > STORE_FORMAT => Err(CoreError::Config(format!(
+            "knowledge store was written by retro v{} (format {}, this binary supports {}) — upgrade the binary or restore an older store from git history",
+            meta.written_by, meta.format, STORE_FORMAT
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Record this binary as the store's writer. No-op when the format and
+/// version are already current (whatever build commit is recorded), so
+/// repeated runs and rebuilds don't churn the file. Callers must have passed
+/// [`check_compatible`] — this never lowers the format, and never lowers
+/// `written_by` either (a newer writer keeps its commit too).
+pub fn stamp(store_root: &Path) -> Result<(), CoreError> {
+    stamp_as(store_root, StoreMeta::current())
+}
+
+fn stamp_as(store_root: &Path, mut current: StoreMeta) -> Result<(), CoreError> {
+    let existing = StoreMeta::load(store_root)?;
+    if let Some(newer) = existing
+        .as_ref()
+        .filter(|m| crate::version::is_newer(&m.written_by, &current.written_by))
+    {
+        current.written_by = newer.written_by.clone();
+        current.commit = newer.commit.clone();
+    }
+    let same_release = |m: &StoreMeta| {
+        (m.format, &m.written_by) == (current.format, &current.written_by)
+    };
+    if existing.as_ref().is_some_and(same_release) {
+        return Ok(());
+    }
+    let content = toml::to_string_pretty(&current).map_err(|e| CoreError::Parse(e.to_string()))?;
+    std::fs::write(meta_path(store_root), content).map_err(|e| CoreError::Io(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn stamp_writes_once_and_roundtrips() {
+        let tmp = TempDir::new().unwrap();
+        assert!(StoreMeta::load(tmp.path()).unwrap().is_none());
+        check_compatible(tmp.path()).unwrap(); // unmarked store is fine
+        stamp(tmp.path()).unwrap();
+        assert_eq!(
+            StoreMeta::load(tmp.path()).unwrap(),
+            Some(StoreMeta::current())
+        );
+        check_compatible(tmp.path()).unwrap();
+    }
+
+    #[test]
+    fn older_binary_keeps_the_newer_writer() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("store.toml"),
+            format!("format = {}\nwritten_by = \"999.0.0\"\n", STORE_FORMAT - 1),
+        )
+        .unwrap();
+        stamp(tmp.path()).unwrap();
+        let meta = StoreMeta::load(tmp.path()).unwrap().unwrap();
+        assert_eq!((meta.format, meta.written_by.as_str()), (STORE_FORMAT, "999.0.0"));
+        assert_eq!(meta.commit, None, "the newer writer's (absent) commit is kept");
+        assert!(crate::version::store_skew(tmp.path()).unwrap().contains("v999.0.0"));
+    }
+
+    #[test]
+    fn only_a_version_change_restamps_the_commit() {
+        let tmp = TempDir::new().unwrap();
+        let build = |commit: Option<&str>| StoreMeta {
+            commit: commit.map(String::from),
+            ..StoreMeta::current()
+        };
+        stamp_as(tmp.path(), build(Some("aaaaaaaaaaaa"))).unwrap();
+        let content = std::fs::read_to_string(tmp.path().join("store.toml")).unwrap();
+        assert!(content.contains("commit = \"aaaaaaaaaaaa\""), "{content}");
+        stamp_as(tmp.path(), build(Some("bbbbbbbbbbbb"))).unwrap();
+        stamp_as(tmp.path(), build(None)).unwrap();
+        let after = std::fs::read_to_string(tmp.path().join("store.toml")).unwrap();
+        assert_eq!(after, content, "other builds of the same version leave it alone");
+
+        let next = StoreMeta {
+            written_by: "999.0.0".to_string(),
+            ..build(Some("cccccccccccc"))
+        };
+        stamp_as(tmp.path(), next.clone()).unwrap();
+        assert_eq!(StoreMeta::load(tmp.path()).unwrap(), Some(next), "an upgrade restamps");
+
+        // a store.toml without the key (older binaries) still loads
+        std::fs::write(tmp.path().join("store.toml"), "format = 3\nwritten_by = \"3.0.0\"\n")
+            .unwrap();
+        assert_eq!(StoreMeta::load(tmp.path()).unwrap().unwrap().commit, None);
+    }
+
+    #[test]
+    fn newer_format_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("store.toml"),
+            format!("format = {}\nwritten_by = \"9.0.0\"\n", STORE_FORMAT + 1),
+        )
+        .unwrap();
+        let err = check_compatible(tmp.path()).unwrap_err().to_string();
+        assert!(err.contains("retro v9.0.0"), "got: {err}");
+        assert!(err.contains("upgrade the binary"));
+    }
+}
@@ -3,11 +3,16 @@
 //! Markdown files under `<root>/knowledge/` are the source of truth.
 //! SQLite (`index.db`) is a disposable, rebuildable index — files always win.
 
+pub mod deferred;
 pub mod git;
 pub mod index;
+pub mod merge;
+pub mod meta;
 pub mod queue;
+pub mod repair;
 pub mod state;
 pub mod projects;
+pub mod tools;
 mod node;
 mod slug;
 
@@ -37,6 +42,7 @@ pub(crate) const IGNORED_ENTRIES: &[&str] = &[
     "state/",
     "run.lock",
     "backups/",
+    "snapshots/",
     // v2 artifacts (SQLite DB, logs, audit trail) in the same ~/.retro root:
     "retro.db",
     "retro.db-wal",
@@ -47,6 +53,7 @@ pub(crate) const IGNORED_ENTRIES: &[&str] = &[
     "runner.log.1",
     "briefings/",
     "hook-stderr.log",
+    "hook-stderr.log.1",
     "warnings.log",
 ];
 
@@ -110,7 +117,22 @@ impl Store {
     /// Write a node to its canonical path (creates the project dir if needed).
     pub fn write_node(&self, node: &Node) -> Result<PathBuf, CoreError> {
         let io = |e: std::io::Error| CoreError::Io(e.to_string());
+        // Ids and project slugs become path components: anything but a slug
+        // (separators, dots) could write outside the store.
+        let slug_ok = match &node.scope {
+            Scope::Global => true,
+            Scope::Project(slug) => is_valid_slug(slug),
+        };
+        if !is_valid_slug(&node.id) || !slug_ok {
+            return Err(CoreError::Parse(format!(
+                "refusing to write node {:?} in scope {:?}: not a valid slug",
+                node.id,
+                node.scope.to_string()
+            )));
+        }
         let path = self.node_path(&node.scope, &node.id);
+        let relative = path.strip_prefix(self.knowledge_dir()).unwrap_or(&path);
+        let path = crate::util::contained_path(&self.knowledge_dir(), relative)?;
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).map_err(io)?;
         }
@@ -145,6 +167,40 @@ impl Store {
             nodes: Vec::new(),
             warnings: Vec::new(),
         };
+        for path in self.node_files()? {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    result.warnings.push(format!("{}: {}", path.display(), e));
+                    continue;
+                }
+            };
+            match Node::from_markdown(&content) {
+                Ok(node) => {
+                    let canonical = self.node_path(&node.scope, &node.id);
+                    if path != canonical {
+                        result.warnings.push(format!(
+                            "{}: declares {}/{} which belongs at {} — skipped",
+                            path.display(),
+                            node.scope,
+                            node.id,
+                            canonical.display()
+                        ));
+                        continue;
+                    }
+                    result.nodes.push((path, node));
+                }
+                Err(e) => {
+                    result.warnings.push(format!("{}: {}", path.display(), e));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Every `.md` file where nodes live (knowledge/global/ and
+    /// knowledge/projects/*/), parseable or not.
+    pub fn node_files(&self) -> Result<Vec<PathBuf>, CoreError> {
         let mut dirs = vec![self.knowledge_dir().join("global")];
         let projects_dir = self.knowledge_dir().join("projects");
         if projects_dir.is_dir() {
@@ -156,6 +212,7 @@ impl Store {
                 }
             }
         }
+        let mut files = Vec::new();
         for dir in dirs {
             if !dir.is_dir() {
                 continue;
@@ -163,38 +220,12 @@ impl Store {
             let entries = std::fs::read_dir(&dir).map_err(|e| CoreError::Io(e.to_string()))?;
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.extension().and_then(|e| e.to_str()) != Some("md") {
-                    continue;
-                }
-                let content = match std::fs::read_to_string(&path) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        result.warnings.push(format!("{}: {}", path.display(), e));
-                        continue;
-                    }
-                };
-                match Node::from_markdown(&content) {
-                    Ok(node) => {
-                        let canonical = self.node_path(&node.scope, &node.id);
-                        if path != canonical {
-                            result.warnings.push(format!(
-                                "{}: declares {}/{} which belongs at {} — skipped",
-                                path.display(),
-                                node.scope,
-                                node.id,
-                                canonical.display()
-                            ));
-                            continue;
-                        }
-                        result.nodes.push((path, node));
-                    }
-                    Err(e) => {
-                        result.warnings.push(format!("{}: {}", path.display(), e));
-                    }
+                if path.extension().and_then(|e| e.to_str()) == Some("md") {
+                    files.push(path);
                 }
             }
         }
-        Ok(result)
+        Ok(files)
     }
 
     /// Slug for a new node: slugified base, `-2`/`-3`... appended on collision.
@@ -225,6 +256,27 @@ impl Store {
         self.write_node(&node)?;
         Ok(true)
     }
+
+    /// User veto: invalidate as `"user"`, recording why when given.
+    /// Returns false if the node doesn't exist.
+    pub fn dismiss(
+        &self,
+        scope: &Scope,
+        id: &str,
+        reason: Option<&str>,
+    ) -> Result<bool, CoreError> {
+        let Some(mut node) = self.get(scope, id)? else {
+            return Ok(false);
+        };
+        node.invalidated_by = Some(crate::dismissals::USER.to_string());
+        node.dismissal_reason = reason
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(String::from);
+        node.updated = chrono::Utc::now().date_naive();
+        self.write_node(&node)?;
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -243,6 +295,8 @@ mod tests {
             created: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
             updated: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: "Test rule body.".to_string(),
         }
     }
@@ -295,6 +349,30 @@ mod tests {
         assert!(ids.contains(&"proj-rule"));
     }
 
+    #[test]
+    fn write_node_refuses_paths_outside_the_store() {
+        let tmp = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        for (id, scope) in [
+            ("../../.ssh/authorized_keys", Scope::Global),
+            ("/etc/passwd", Scope::Global),
+            ("ok", Scope::Project("../../escape".to_string())),
+            ("ok", Scope::Project("..".to_string())),
+        ] {
+            let err = store.write_node(&node(id, scope)).unwrap_err();
+            assert!(err.to_string().contains("not a valid slug"), "{id}: {err}");
+        }
+        std::os::unix::fs::symlink(outside.path(), tmp.path().join("knowledge/projects/evil"))
+            .unwrap();
+        let err = store
+            .write_node(&node("rule", Scope::Project("evil".to_string())))
+            .unwrap_err();
+        assert!(err.to_string().contains("resolves to"), "{err}");
+        assert_eq!(std::fs::read_dir(outside.path()).unwrap().count(), 0);
+    }
+
     #[test]
     fn load_all_skips_unparseable_with_warning() {
         let tmp = TempDir::new().unwrap();
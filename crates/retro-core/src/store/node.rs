@@ -22,6 +22,7 @@ pub fn is_valid_slug(s: &str) -> bool {
 /// Node type. v3 collapses v2's six types to four
 /// (`directive` → `rule`, `skill` → `pattern`, handled at migration).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum NodeType {
     Rule,
     Preference,
@@ -86,6 +87,7 @@ impl Scope {
 /// The body is stored WITHOUT a trailing newline; `to_markdown`
 /// appends exactly one (normalization keeps round-trips stable).
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct Node {
     pub id: String,
     pub scope: Scope,
@@ -95,10 +97,42 @@ pub struct Node {
     pub created: NaiveDate,
     pub updated: NaiveDate,
     pub invalidated_by: Option<String>,
+    /// Why the user dismissed the node (free text, one line). Written only
+    /// when set, as the optional `dismissal_reason` key.
+    pub dismissal_reason: Option<String>,
+    /// Heading of the project's CLAUDE.md this rule belongs under (e.g.
+    /// `Testing`), as suggested by the analyzer. Written only when set, as
+    /// the optional `section` key.
+    pub section: Option<String>,
     pub body: String,
 }
 
 impl Node {
+    /// A new, active node dated today, without sources. Set the other
+    /// fields on the result.
+    pub fn new(
+        id: impl Into<String>,
+        scope: Scope,
+        node_type: NodeType,
+        confidence: f64,
+        body: impl Into<String>,
+    ) -> Node {
+        let today = chrono::Utc::now().date_naive();
+        Node {
+            id: id.into(),
+            scope,
+            node_type,
+            confidence,
+            sources: Vec::new(),
+            created: today,
+            updated: today,
+            invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
+            body: body.into(),
+        }
+    }
+
     pub fn is_active(&self) -> bool {
         self.invalidated_by.is_none()
     }
@@ -107,8 +141,16 @@ impl Node {
         // NOTE: source IDs must not contain commas (comma-joined list format).
         let sources = self.sources.join(", ");
         let invalidated = self.invalidated_by.as_deref().unwrap_or("null");
+        let reason = match self.dismissal_reason.as_deref().map(one_line) {
+            Some(r) if !r.is_empty() => format!("dismissal_reason: {r}\n"),
+            _ => String::new(),
+        };
+        let section = match self.section.as_deref().map(one_line) {
+            Some(s) if !s.is_empty() => format!("section: {s}\n"),
+            _ => String::new(),
+        };
         format!(
-            "---\nid: {}\nscope: {}\ntype: {}\nconfidence: {:.2}\nsources: [{}]\ncreated: {}\nupdated: {}\ninvalidated_by: {}\n---\n{}\n",
+            "---\nid: {}\nscope: {}\ntype: {}\nconfidence: {:.2}\nsources: [{}]\ncreated: {}\nupdated: {}\ninvalidated_by: {}\n{reason}{section}---\n{}\n",
             self.id,
             self.scope,
             self.node_type.as_str(),
@@ -142,6 +184,8 @@ impl Node {
         let mut created: Option<NaiveDate> = None;
         let mut updated: Option<NaiveDate> = None;
         let mut invalidated_by: Option<String> = None;
+        let mut dismissal_reason: Option<String> = None;
+        let mut section: Option<String> = None;
         let mut seen_keys: Vec<String> = Vec::new();
 
         for line in front.lines() {
@@ -196,6 +240,10 @@ impl Node {
                         other => Some(other.to_string()),
                     }
                 }
+                "dismissal_reason" => {
+                    dismissal_reason = Some(value.to_string()).filter(|v| !v.is_empty())
+                }
+                "section" => section = Some(value.to_string()).filter(|v| !v.is_empty()),
                 other => {
                     return Err(CoreError::Parse(format!(
                         "unknown frontmatter key: {other:?}"
@@ -220,11 +268,18 @@ impl Node {
             created: created.ok_or_else(|| missing("created"))?,
             updated: updated.ok_or_else(|| missing("updated"))?,
             invalidated_by,
+            dismissal_reason,
+            section,
             body: body.trim_end_matches('\n').to_string(),
         })
     }
 }
 
+/// Frontmatter values are single lines: fold any line breaks into spaces.
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 fn parse_date(s: &str) -> Result<NaiveDate, CoreError> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d")
         .map_err(|_| CoreError::Parse(format!("invalid date: {s:?}")))
@@ -247,6 +302,8 @@ mod tests {
             created: NaiveDate::from_ymd_opt(2026, 5, 19).unwrap(),
             updated: NaiveDate::from_ymd_opt(2026, 6, 2).unwrap(),
             invalidated_by: None,
+            dismissal_reason: None,
+            section: None,
             body: "A/B comparisons must always use paired observations.\n\n**Why:** Unpaired comparisons mix traffic distributions.".to_string(),
         }
     }
@@ -322,6 +379,32 @@ A/B comparisons must always use paired observations.
         assert!(md.contains("invalidated_by: other-node\n"));
     }
 
+    #[test]
+    fn dismissal_reason_is_optional_and_roundtrips_as_one_line() {
+        let mut n = sample_node();
+        n.invalidated_by = Some("user".to_string());
+        n.dismissal_reason = Some("duplicate of\n  the smoke-test rule".to_string());
+        let md = n.to_markdown();
+        assert!(
+            md.contains("invalidated_by: user\ndismissal_reason: duplicate of the smoke-test rule\n---\n"),
+            "{md}"
+        );
+        let parsed = Node::from_markdown(&md).unwrap();
+        assert_eq!(parsed.dismissal_reason.as_deref(), Some("duplicate of the smoke-test rule"));
+        n.dismissal_reason = Some("  ".to_string());
+        assert!(!n.to_markdown().contains("dismissal_reason"), "blank reasons aren't written");
+    }
+
+    #[test]
+    fn section_is_optional_and_roundtrips() {
+        let mut n = sample_node();
+        assert!(!n.to_markdown().contains("section:"));
+        n.section = Some("Testing".to_string());
+        let md = n.to_markdown();
+        assert!(md.contains("invalidated_by: null\nsection: Testing\n---\n"), "{md}");
+        assert_eq!(Node::from_markdown(&md).unwrap(), n);
+    }
+
     #[test]
     fn to_markdown_empty_body_single_trailing_newline() {
         let mut n = sample_node();
@@ -1,9 +1,9 @@
 //! v3 project registry. Committed identity (project.toml per project dir),
 //! machine-local path map (state/projects.json), auto-registration from
-//! session cwd, and exclusion with cleanup.
+//! session cwd, exclusion with cleanup, and the per-repo opt-out marker.
 
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -201,6 +201,46 @@ pub fn is_excluded(path: &str, exclude_projects: &[String]) -> bool {
     })
 }
 
+/// Marker file at a repository root that opts the repo out of retro.
+pub const OPT_OUT_FILE: &str = ".retro-ignore";
+
+/// Per-repo settings file; `[retro] enabled = false` opts the repo out.
+pub const REPO_CONFIG_FILE: &str = ".retro.toml";
+
+/// The marker that opts `path` out of retro, if any: a [`OPT_OUT_FILE`], or
+/// a [`REPO_CONFIG_FILE`] with `[retro] enabled = false`, in `path` or a
+/// parent up to the repository root (the first directory holding `.git`).
+/// The repo's own decision, unlike `privacy.exclude_projects`: sessions there
+/// are never analyzed and nothing is projected into it. A few stats per call.
+pub fn opt_out_marker(path: &str) -> Option<PathBuf> {
+    if path.is_empty() {
+        return None;
+    }
+    for dir in Path::new(path).ancestors() {
+        let marker = dir.join(OPT_OUT_FILE);
+        if marker.exists() {
+            return Some(marker);
+        }
+        let repo_config = dir.join(REPO_CONFIG_FILE);
+        if repo_config.exists() && !repo_enabled(&repo_config) {
+            return Some(repo_config);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// `[retro] enabled` of a `.retro.toml`; true when absent or unreadable.
+fn repo_enabled(file: &Path) -> bool {
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .and_then(|t| t.get("retro")?.get("enabled")?.as_bool())
+        .unwrap_or(true)
+}
+
 /// Exclusion cleanup: delete the project's knowledge subtree (recoverable via
 /// store git history), drop it from the path map, and remove its
 /// CLAUDE.local.md (the whole file — it is retro-owned build output).
@@ -226,6 +266,72 @@ pub fn cleanup_excluded(
     Ok(())
 }
 
+/// What [`relocate`] changed (or would change, on a dry run).
+#[derive(Debug, Default, PartialEq)]
+pub struct Relocation {
+    /// `(slug, old path)` for each project re-pointed.
+    pub projects: Vec<(String, String)>,
+    /// Projection integrity records re-keyed (`state/projections.json`).
+    pub projection_records: usize,
+    /// History-only prompts re-pointed (`state/history.json`).
+    pub history_records: usize,
+}
+
+fn canonical(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .ok()
+        .and_then(|p| p.to_str().map(str::to_string))
+        .unwrap_or_else(|| path.trim_end_matches('/').to_string())
+}
+
+/// Point everything this machine recorded for a moved project at its new
+/// location. `old` is the registered path (compared canonicalized where it
+/// still resolves) or the project's slug; `new` must be an existing
+/// directory. Re-points the path map, projection records and history-only
+/// records; knowledge itself is keyed by slug and never changes. No
+/// project matching `old` yields an empty [`Relocation`].
+pub fn relocate(
+    store: &Store,
+    old: &str,
+    new: &str,
+    dry_run: bool,
+) -> Result<Relocation, CoreError> {
+    if !Path::new(new).is_dir() {
+        return Err(CoreError::Io(format!("{new} is not a directory")));
+    }
+    let new = canonical(new);
+    let old_canon = canonical(old);
+    let mut map = PathMap::load(store.root())?;
+    let mut out = Relocation::default();
+    for (slug, path) in map.paths.iter_mut() {
+        if slug == old || canonical(path) == old_canon {
+            out.projects.push((slug.clone(), path.clone()));
+            *path = new.clone();
+        }
+    }
+    for (_, old_path) in &out.projects {
+        out.projection_records +=
+            crate::projection::integrity::relocate(store.root(), old_path, &new, dry_run)?;
+        out.history_records +=
+            crate::ingest::history::relocate(store.root(), old_path, &new, dry_run)?;
+    }
+    if !dry_run && !out.projects.is_empty() {
+        map.save(store.root())?;
+    }
+    Ok(out)
+}
+
+/// `path` re-rooted from `old` to `new` when it is `old` or lies under it.
+pub(crate) fn rebase_path(path: &str, old: &str, new: &str) -> Option<String> {
+    let old = old.trim_end_matches('/');
+    if path == old {
+        return Some(new.to_string());
+    }
+    path.strip_prefix(old)
+        .filter(|rest| rest.starts_with('/'))
+        .map(|rest| format!("{}{rest}", new.trim_end_matches('/')))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,6 +450,33 @@ mod tests {
         assert!(!is_excluded("/Users/me/work/app", &excludes));
     }
 
+    #[test]
+    fn opt_out_marker_is_found_up_to_the_repo_root() {
+        let outer = TempDir::new().unwrap();
+        let repo = outer.path().join("repo");
+        let sub = repo.join("src/deep");
+        std::fs::create_dir_all(&sub).unwrap();
+        git_project(&repo, None);
+        let sub_str = sub.to_str().unwrap();
+        assert_eq!(opt_out_marker(sub_str), None);
+        assert_eq!(opt_out_marker(""), None);
+
+        // Above the repo root: not this repo's decision.
+        std::fs::write(outer.path().join(OPT_OUT_FILE), "").unwrap();
+        assert_eq!(opt_out_marker(sub_str), None);
+
+        std::fs::write(repo.join(REPO_CONFIG_FILE), "[retro]\nenabled = true\n").unwrap();
+        assert_eq!(opt_out_marker(sub_str), None);
+        std::fs::write(repo.join(REPO_CONFIG_FILE), "retro.enabled = false\n").unwrap();
+        assert_eq!(opt_out_marker(sub_str), Some(repo.join(REPO_CONFIG_FILE)));
+        std::fs::write(repo.join(REPO_CONFIG_FILE), "not toml [").unwrap();
+        assert_eq!(opt_out_marker(sub_str), None, "unreadable config opts nothing out");
+
+        std::fs::write(repo.join(OPT_OUT_FILE), "").unwrap();
+        assert_eq!(opt_out_marker(sub_str), Some(repo.join(OPT_OUT_FILE)));
+        assert_eq!(opt_out_marker(repo.to_str().unwrap()), Some(repo.join(OPT_OUT_FILE)));
+    }
+
     #[test]
     fn store_dir_and_children_are_self_excluded() {
         let tmp = TempDir::new().unwrap();
@@ -384,4 +517,49 @@ mod tests {
         let map = PathMap::load(store_tmp.path()).unwrap();
         assert!(!map.paths.contains_key(&reg.slug));
     }
+
+    #[test]
+    fn relocate_repoints_path_map_and_records_with_dry_run() {
+        let store_tmp = TempDir::new().unwrap();
+        let store = Store::open(store_tmp.path());
+        store.ensure_layout().unwrap();
+        let parent = TempDir::new().unwrap();
+        let old = parent.path().join("app");
+        std::fs::create_dir(&old).unwrap();
+        let reg = register(&store, old.to_str().unwrap()).unwrap();
+        let old_path = PathMap::load(store.root()).unwrap().paths[&reg.slug].clone();
+        let md = Path::new(&old_path).join("CLAUDE.local.md");
+        crate::projection::integrity::record(store.root(), &md, &["Use uv.".to_string()])
+            .unwrap();
+        let history = [format!("{old_path}/sub"), format!("{old_path}2")].map(|project| {
+            crate::ingest::history::HistoryRecord {
+                project,
+                display: "prompt".to_string(),
+                timestamp: None,
+                analyzed: false,
+            }
+        });
+        crate::ingest::history::save(store.root(), &history).unwrap();
+        let new = parent.path().join("renamed");
+        std::fs::rename(&old, &new).unwrap();
+        let new_str = new.to_str().unwrap();
+
+        let dry = relocate(&store, &old_path, new_str, true).unwrap();
+        assert_eq!(dry.projects, vec![(reg.slug.clone(), old_path.clone())]);
+        assert_eq!((dry.projection_records, dry.history_records), (1, 1), "app2 isn't under app");
+        assert_eq!(PathMap::load(store.root()).unwrap().paths[&reg.slug], old_path, "dry run");
+
+        let done = relocate(&store, &reg.slug, new_str, false).unwrap();
+        assert_eq!(done, dry, "a slug names the same project");
+        let canon_new = std::fs::canonicalize(&new).unwrap().display().to_string();
+        assert_eq!(PathMap::load(store.root()).unwrap().paths[&reg.slug], canon_new);
+        let records = crate::projection::integrity::load(store.root());
+        assert!(records.contains_key(&format!("{canon_new}/CLAUDE.local.md")), "{records:?}");
+        let history = crate::ingest::history::load(store.root());
+        assert_eq!(history[0].project, format!("{canon_new}/sub"));
+        assert_eq!(history[1].project, format!("{old_path}2"));
+
+        assert!(relocate(&store, "/nowhere/known", new_str, false).unwrap().projects.is_empty());
+        assert!(relocate(&store, &reg.slug, "/does/not/exist", false).is_err());
+    }
 }
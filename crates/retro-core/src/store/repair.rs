@@ -0,0 +1,191 @@
+//! `retro doctor --repair-sources`: node files whose `sources:` line was
+//! hand-edited out of shape. A list without brackets (or written as a YAML
+//! block) makes the whole file unparseable, so the node is skipped; quoted
+//! entries parse, but as `"session:…"`, which no evidence count recognizes
+//! and a merge never deduplicates against. The repair rewrites only that
+//! line; the rest of the file is left byte for byte.
+
+use std::path::PathBuf;
+
+use super::Store;
+use crate::errors::CoreError;
+
+/// A `sources:` line (or block) to rewrite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcesRepair {
+    pub path: PathBuf,
+    /// The text as found, line ending included.
+    pub before: String,
+    pub after: String,
+    /// What changed, one entry per rule applied.
+    pub notes: Vec<String>,
+}
+
+/// Read a `sources:` value leniently: brackets optional, quotes stripped,
+/// entries that still aren't a single token dropped, duplicates dropped.
+/// Returns the entries and what had to be done to get them.
+pub fn parse_sources(value: &str) -> (Vec<String>, Vec<String>) {
+    let mut notes = Vec::new();
+    let value = value.trim();
+    let inner = match value.strip_prefix('[') {
+        Some(rest) => rest.strip_suffix(']').unwrap_or_else(|| {
+            notes.push("closed an unterminated list".to_string());
+            rest
+        }),
+        None => {
+            notes.push("wrapped a bare value in [ ]".to_string());
+            value
+        }
+    };
+    let mut sources: Vec<String> = Vec::new();
+    for raw in inner.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let entry = unquote(raw);
+        if entry != raw {
+            notes.push(format!("unquoted {raw}"));
+        }
+        if entry.is_empty() || entry.chars().any(|c| c.is_whitespace() || "[]{}\"'".contains(c)) {
+            notes.push(format!("dropped {raw:?}"));
+        } else if sources.iter().any(|s| s == entry) {
+            notes.push(format!("dropped duplicate {entry}"));
+        } else {
+            sources.push(entry.to_string());
+        }
+    }
+    (sources, notes)
+}
+
+fn unquote(s: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|q| s.strip_prefix(*q).and_then(|rest| rest.strip_suffix(*q)))
+        .unwrap_or(s)
+}
+
+/// The repair `content` needs, if any.
+pub fn check_file(content: &str) -> Option<(String, String, Vec<String>)> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return None;
+    }
+    let front = lines[1..].iter().take_while(|l| l.trim_end() != "---").count();
+    let at = (1..=front).find(|&i| lines[i].trim_start().starts_with("sources:"))?;
+    let ending = if lines[at].ends_with("\r\n") { "\r\n" } else { "\n" };
+    let value = lines[at].trim()["sources:".len()..].trim();
+    let (mut end, mut notes) = (at + 1, Vec::new());
+    let (sources, mut found) = if value.is_empty() {
+        // YAML block list: `sources:` then `- entry` lines
+        while end <= front && lines[end].trim_start().starts_with('-') {
+            end += 1;
+        }
+        let items: Vec<&str> = lines[at + 1..end]
+            .iter()
+            .map(|l| l.trim().trim_start_matches('-').trim())
+            .collect();
+        if items.is_empty() {
+            notes.push("wrote an empty list".to_string());
+        } else {
+            notes.push("rewrote a block list inline".to_string());
+        }
+        parse_sources(&format!("[{}]", items.join(", ")))
+    } else {
+        parse_sources(value)
+    };
+    notes.append(&mut found);
+    if notes.is_empty() {
+        return None;
+    }
+    let before = lines[at..end].concat();
+    let after = format!("sources: [{}]{ending}", sources.join(", "));
+    Some((before, after, notes))
+}
+
+/// Every node file whose `sources:` needs a repair.
+pub fn scan(store: &Store) -> Result<Vec<SourcesRepair>, CoreError> {
+    let mut found = Vec::new();
+    for path in store.node_files()? {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        if let Some((before, after, notes)) = check_file(&content) {
+            found.push(SourcesRepair { path, before, after, notes });
+        }
+    }
+    Ok(found)
+}
+
+/// Apply `repairs`. A file that changed since the scan is left alone.
+/// Returns how many files were rewritten.
+pub fn apply(repairs: &[SourcesRepair]) -> Result<usize, CoreError> {
+    let io = |e: std::io::Error| CoreError::Io(e.to_string());
+    let mut written = 0;
+    for r in repairs {
+        let content = std::fs::read_to_string(&r.path).map_err(io)?;
+        if !content.contains(&r.before) {
+            continue;
+        }
+        std::fs::write(&r.path, content.replacen(&r.before, &r.after, 1)).map_err(io)?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Node;
+    use tempfile::TempDir;
+
+    const HEAD: &str = "---\nid: use-uv\nscope: global\ntype: rule\nconfidence: 0.80\n";
+    const TAIL: &str = "created: 2026-07-01\nupdated: 2026-07-01\ninvalidated_by: null\n---\nUse uv.\n";
+
+    fn file(sources: &str) -> String {
+        format!("{HEAD}{sources}{TAIL}")
+    }
+
+    #[test]
+    fn each_rule_repairs_its_shape() {
+        let cases = [
+            ("sources: session:a, session:b\n", "sources: [session:a, session:b]\n", "bare value"),
+            ("sources: [\"session:a\", 'session:b']\n", "sources: [session:a, session:b]\n", "unquoted"),
+            ("sources: [session:a, two words, {x}]\n", "sources: [session:a]\n", "dropped"),
+            ("sources: [session:a, session:a]\n", "sources: [session:a]\n", "duplicate"),
+            ("sources: [session:a\n", "sources: [session:a]\n", "unterminated"),
+            ("sources:\n  - session:a\n  - \"v2:7\"\n", "sources: [session:a, v2:7]\n", "block list"),
+        ];
+        for (broken, fixed, note) in cases {
+            let (before, after, notes) = check_file(&file(broken)).expect(note);
+            assert_eq!((before.as_str(), after.as_str()), (broken, fixed), "{note}");
+            let rule = note.split(' ').next().unwrap();
+            assert!(notes.iter().any(|n| n.contains(rule)), "{notes:?}");
+            let repaired = file(broken).replacen(&before, &after, 1);
+            assert_eq!(repaired, file(fixed), "rest of the file untouched");
+            Node::from_markdown(&repaired).expect("repaired file parses");
+        }
+        assert_eq!(check_file(&file("sources: [session:a, managed-import]\n")), None);
+        assert_eq!(check_file(&file("sources: []\n")), None);
+        assert_eq!(check_file(&file("")), None, "no sources line is fine");
+    }
+
+    #[test]
+    fn scan_and_apply_rewrite_only_broken_files() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let global = store.knowledge_dir().join("global");
+        std::fs::write(global.join("use-uv.md"), file("sources: session:a\n")).unwrap();
+        let good = file("sources: [session:b]\n").replace("use-uv", "run-tests");
+        std::fs::write(global.join("run-tests.md"), &good).unwrap();
+        assert_eq!(store.load_all().unwrap().nodes.len(), 1, "bare value: skipped");
+
+        let repairs = scan(&store).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert!(repairs[0].path.ends_with("use-uv.md"));
+        assert_eq!(apply(&repairs).unwrap(), 1);
+        let loaded = store.load_all().unwrap();
+        assert_eq!(loaded.nodes.len(), 2);
+        assert!(loaded.warnings.is_empty());
+        assert_eq!(std::fs::read_to_string(global.join("run-tests.md")).unwrap(), good);
+        assert!(scan(&store).unwrap().is_empty());
+        assert_eq!(apply(&repairs).unwrap(), 0, "already repaired: left alone");
+    }
+}
@@ -28,6 +28,51 @@ pub struct RunnerState {
     /// each drained session; read by `retro brief`'s catch-up dedup.
     #[serde(default)]
     pub processed: std::collections::BTreeMap<String, u64>,
+    /// session_id -> transcript content fingerprint
+    /// (`ingest::content_fingerprint`) at processing time. Lets catch-up
+    /// treat an mtime-only touch as unchanged. Entries written before this
+    /// field existed have none and fall back to the mtime comparison.
+    #[serde(default)]
+    pub fingerprints: std::collections::BTreeMap<String, String>,
+    /// Transcript entry types the parser didn't recognize in the last run
+    /// that parsed sessions -> line count. Non-empty means Claude Code's
+    /// format moved ahead of this binary; shown by `retro status`.
+    #[serde(default)]
+    pub unknown_entry_types: std::collections::BTreeMap<String, u64>,
+    /// `[[ingest.ignore_session_if]]` rule number (1-based) -> sessions it
+    /// has dropped unanalyzed, lifetime. Shown by `retro status`.
+    #[serde(default)]
+    pub ignored_sessions: std::collections::BTreeMap<usize, u64>,
+    /// `Config::snapshot` of the last real run; empty before the first.
+    #[serde(default)]
+    pub config_snapshot: std::collections::BTreeMap<String, String>,
+    /// Recorded config changes, oldest first (newest 20 kept).
+    #[serde(default)]
+    pub config_changes: Vec<ConfigChange>,
+    /// RFC3339 time a hook last spawned a background run
+    /// (`throttle::claim_spawn`'s coalescing marker).
+    #[serde(default)]
+    pub last_spawn_at: Option<String>,
+    /// RFC3339 time of the last GitHub release check (`[updates]
+    /// check_github`), successful or not.
+    #[serde(default)]
+    pub update_checked_at: Option<String>,
+    /// Newest release tag the last successful check saw.
+    #[serde(default)]
+    pub latest_release: Option<String>,
+    /// Key of the `[privacy] anonymize_paths` aliases (`anonymize`), created
+    /// on first use. Losing it only renames the aliases.
+    #[serde(default)]
+    pub pseudonym_key: Option<u64>,
+}
+
+/// One observed config change: when the runner first ran with it, and the
+/// field-level diff (`config::diff_snapshots`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConfigChange {
+    /// RFC3339.
+    pub at: String,
+    pub changes: Vec<String>,
 }
 
 fn state_path(store_root: &Path) -> PathBuf {
@@ -66,6 +111,11 @@ impl RunnerState {
         std::fs::rename(&tmp, &path).map_err(io)
     }
 
+    /// The alias key, created (unsaved) when there is none yet.
+    pub fn pseudonym_key(&mut self) -> u64 {
+        *self.pseudonym_key.get_or_insert_with(crate::anonymize::new_key)
+    }
+
     /// Remaining AI calls for `today` (YYYY-MM-DD) under `max_per_day`.
     /// A stored date != today means the counter is stale: full budget.
     pub fn budget_remaining(&self, today: &str, max_per_day: u32) -> u32 {
@@ -85,14 +135,52 @@ impl RunnerState {
         self.ai_calls_today += calls;
     }
 
+    /// Compare `snapshot` with the one stored by the previous run, record a
+    /// [`ConfigChange`] when they differ, and store `snapshot`. Returns the
+    /// diff; the first snapshot is a baseline, not a change.
+    pub fn note_config(
+        &mut self,
+        snapshot: std::collections::BTreeMap<String, String>,
+        at: &str,
+    ) -> Vec<String> {
+        const MAX_CONFIG_CHANGES: usize = 20;
+        let changes = if self.config_snapshot.is_empty() {
+            Vec::new()
+        } else {
+            crate::config::diff_snapshots(&self.config_snapshot, &snapshot)
+        };
+        if !changes.is_empty() {
+            self.config_changes.push(ConfigChange {
+                at: at.to_string(),
+                changes: changes.clone(),
+            });
+            if self.config_changes.len() > MAX_CONFIG_CHANGES {
+                let excess = self.config_changes.len() - MAX_CONFIG_CHANGES;
+                self.config_changes.drain(..excess);
+            }
+        }
+        self.config_snapshot = snapshot;
+        changes
+    }
+
     pub fn drain_notifications(&mut self) -> Vec<String> {
         std::mem::take(&mut self.notifications)
     }
 
-    /// Record a session as processed at the given transcript mtime, pruning
-    /// the map to the newest 1000 entries to bound growth.
-    pub fn record_processed(&mut self, session_id: &str, mtime_unix: u64) {
+    /// Record a session as processed at the given transcript mtime (and
+    /// content fingerprint, when known), pruning to the newest 1000 entries
+    /// to bound growth.
+    pub fn record_processed(
+        &mut self,
+        session_id: &str,
+        mtime_unix: u64,
+        fingerprint: Option<&str>,
+    ) {
         self.processed.insert(session_id.to_string(), mtime_unix);
+        match fingerprint {
+            Some(fp) => self.fingerprints.insert(session_id.to_string(), fp.to_string()),
+            None => self.fingerprints.remove(session_id),
+        };
         if self.processed.len() > 1000 {
             let mut by_mtime: Vec<(String, u64)> = self
                 .processed
@@ -101,7 +189,36 @@ impl RunnerState {
                 .collect();
             by_mtime.sort_by_key(|(_, m)| std::cmp::Reverse(*m));
             self.processed = by_mtime.into_iter().take(1000).collect();
+            let processed = &self.processed;
+            self.fingerprints.retain(|id, _| processed.contains_key(id));
+        }
+    }
+
+    /// True if the session was already processed in its current form: the
+    /// mtime is not newer than when processed, or it is newer but the content
+    /// fingerprint still matches (an mtime-only touch). `fingerprint` is only
+    /// computed when the mtime check fails. On a fingerprint match the stored
+    /// mtime is advanced so the next check takes the cheap path again.
+    pub fn is_processed_unchanged(
+        &mut self,
+        session_id: &str,
+        mtime_unix: u64,
+        fingerprint: impl FnOnce() -> Option<String>,
+    ) -> bool {
+        let Some(&processed_at) = self.processed.get(session_id) else {
+            return false;
+        };
+        if mtime_unix <= processed_at {
+            return true;
         }
+        let Some(stored) = self.fingerprints.get(session_id) else {
+            return false;
+        };
+        if fingerprint().as_deref() == Some(stored.as_str()) {
+            self.processed.insert(session_id.to_string(), mtime_unix);
+            return true;
+        }
+        false
     }
 }
 
@@ -110,6 +227,23 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn note_config_records_changes_but_not_the_baseline_or_no_ops() {
+        let mut s = RunnerState::default();
+        let base = crate::config::Config::default().snapshot();
+        assert!(s.note_config(base.clone(), "2026-10-01T00:00:00Z").is_empty());
+        assert!(s.note_config(base.clone(), "2026-10-02T00:00:00Z").is_empty());
+        assert!(s.config_changes.is_empty());
+
+        let mut changed = base;
+        changed.insert("ai.model".to_string(), "opus".to_string());
+        let diff = s.note_config(changed.clone(), "2026-10-03T00:00:00Z");
+        assert_eq!(diff, vec!["ai.model: sonnet → opus".to_string()]);
+        assert_eq!(s.config_changes.len(), 1);
+        assert_eq!(s.config_changes[0].at, "2026-10-03T00:00:00Z");
+        assert_eq!(s.config_snapshot, changed);
+    }
+
     #[test]
     fn state_roundtrips_and_defaults() {
         let tmp = TempDir::new().unwrap();
@@ -167,14 +301,38 @@ mod tests {
     fn record_processed_tracks_and_prunes() {
         let tmp = TempDir::new().unwrap();
         let mut s = RunnerState::load(tmp.path()).unwrap();
-        s.record_processed("a", 100);
+        s.record_processed("a", 100, Some("1:aa"));
         assert_eq!(s.processed["a"], 100);
         for i in 0..1100 {
-            s.record_processed(&format!("s{i}"), 1000 + i as u64);
+            s.record_processed(&format!("s{i}"), 1000 + i as u64, None);
         }
         assert!(s.processed.len() <= 1000);
         assert!(s.processed.contains_key("s1099"), "newest kept");
         assert!(!s.processed.contains_key("a"), "oldest pruned");
+        assert!(!s.fingerprints.contains_key("a"), "fingerprint pruned with it");
+    }
+
+    #[test]
+    fn mtime_only_touch_is_unchanged_when_fingerprint_matches() {
+        let mut s = RunnerState::default();
+        s.record_processed("a", 100, Some("10:abc"));
+        // same mtime: fingerprint never computed
+        assert!(s.is_processed_unchanged("a", 100, || panic!("not needed")));
+        // touched: fingerprint matches → unchanged, stored mtime advanced
+        assert!(s.is_processed_unchanged("a", 200, || Some("10:abc".to_string())));
+        assert_eq!(s.processed["a"], 200);
+    }
+
+    #[test]
+    fn genuine_change_or_missing_fingerprint_is_changed() {
+        let mut s = RunnerState::default();
+        s.record_processed("a", 100, Some("10:abc"));
+        assert!(!s.is_processed_unchanged("a", 200, || Some("12:def".to_string())));
+        assert_eq!(s.processed["a"], 100, "not advanced on a real change");
+        // legacy entry without a fingerprint: mtime logic only
+        s.record_processed("b", 100, None);
+        assert!(!s.is_processed_unchanged("b", 200, || Some("10:abc".to_string())));
+        assert!(!s.is_processed_unchanged("never-seen", 1, || None));
     }
 
     #[test]
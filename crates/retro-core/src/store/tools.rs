@@ -0,0 +1,216 @@
+//! Tool usage behind pattern nodes: for each pattern, which tools its source
+//! sessions used. A workflow that leans on Bash across several steps is the
+//! kind worth turning into a skill, so the histogram is shown with the node
+//! (dashboard) and handed to the analyzer next to it, letting reinforced
+//! patterns name the tools they rely on. Lives at `state/pattern_tools.json`
+//! (machine-local, like the transcripts it is counted from), keyed by
+//! [`key`]. Per-session tool lists are kept rather than bare counts so a
+//! session attached twice — or shared by two merged patterns — counts once.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::{Scope, Store};
+use crate::errors::CoreError;
+use crate::models::Session;
+
+/// Session id -> tools that session used.
+pub type SessionTools = BTreeMap<String, Vec<String>>;
+
+/// [`key`] -> the pattern's sessions.
+pub type ToolLog = BTreeMap<String, SessionTools>;
+
+/// Tools listed per pattern in the analysis prompt.
+pub const PROMPT_TOOLS: usize = 5;
+
+fn tools_path(store_root: &Path) -> PathBuf {
+    store_root.join("state").join("pattern_tools.json")
+}
+
+/// Log key for a node: `<scope>/<id>` (`global/x`, `project/app/x`).
+pub fn key(scope: &Scope, id: &str) -> String {
+    format!("{scope}/{id}")
+}
+
+/// Missing or corrupt file loads empty (the log is derived, best-effort).
+pub fn load(store_root: &Path) -> ToolLog {
+    std::fs::read_to_string(tools_path(store_root))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(store_root: &Path, log: &ToolLog) -> Result<(), CoreError> {
+    let path = tools_path(store_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CoreError::Io(e.to_string()))?;
+    }
+    let json = serde_json::to_string_pretty(log).map_err(|e| CoreError::Parse(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| CoreError::Io(e.to_string()))
+}
+
+/// Record `sessions` against one pattern. Sessions already recorded are left
+/// as they were. Returns whether anything was added.
+pub fn attach(entry: &mut SessionTools, sessions: &[&Session]) -> bool {
+    let mut changed = false;
+    for s in sessions {
+        if !entry.contains_key(&s.session_id) {
+            entry.insert(s.session_id.clone(), s.tools_used.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Fold a merged-away pattern's sessions into the keeper's.
+pub fn absorb(log: &mut ToolLog, keep: &str, removed: &str) -> bool {
+    let Some(gone) = log.remove(removed) else {
+        return false;
+    };
+    let entry = log.entry(keep.to_string()).or_default();
+    for (session, tools) in gone {
+        entry.entry(session).or_insert(tools);
+    }
+    true
+}
+
+/// Log keys whose node file is gone — deleted by hand, or its learn commit
+/// reverted. An invalidated node still has its file, so it keeps its entry.
+/// Left in place, an orphan's sessions would be attached to the next pattern
+/// that happens to get the same slug.
+pub fn orphans(log: &ToolLog, store: &Store) -> Vec<String> {
+    log.keys()
+        .filter(|k| {
+            let node = k.rsplit_once('/').and_then(|(scope, id)| {
+                let scope = Scope::parse(scope).ok()?;
+                super::is_valid_slug(id).then(|| store.node_path(&scope, id))
+            });
+            !node.is_some_and(|path| path.is_file())
+        })
+        .cloned()
+        .collect()
+}
+
+/// Drop [`orphans`] from the saved log. Returns the keys dropped.
+pub fn prune(store: &Store) -> Result<Vec<String>, CoreError> {
+    let mut log = load(store.root());
+    let gone = orphans(&log, store);
+    if !gone.is_empty() {
+        log.retain(|k, _| !gone.contains(k));
+        save(store.root(), &log)?;
+    }
+    Ok(gone)
+}
+
+/// Sessions per tool, most used first (ties by name).
+pub fn histogram(entry: &SessionTools) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for tools in entry.values() {
+        for tool in tools {
+            *counts.entry(tool.as_str()).or_default() += 1;
+        }
+    }
+    let mut hist: Vec<(String, usize)> =
+        counts.into_iter().map(|(t, n)| (t.to_string(), n)).collect();
+    hist.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    hist
+}
+
+/// `Bash×4, Edit×2 (of 5 sessions)`: the top `max` tools. Empty when no
+/// recorded session used a tool.
+pub fn compact(entry: &SessionTools, max: usize) -> String {
+    let hist = histogram(entry);
+    if hist.is_empty() {
+        return String::new();
+    }
+    let tools: Vec<String> = hist
+        .iter()
+        .take(max)
+        .map(|(t, n)| format!("{t}×{n}"))
+        .collect();
+    format!("{} (of {} sessions)", tools.join(", "), entry.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SessionMetadata;
+    use tempfile::TempDir;
+
+    fn session(id: &str, tools: &[&str]) -> Session {
+        Session {
+            session_id: id.to_string(),
+            project: "/p".to_string(),
+            session_path: String::new(),
+            user_messages: vec![],
+            assistant_messages: vec![],
+            summaries: vec![],
+            tools_used: tools.iter().map(|t| t.to_string()).collect(),
+            errors: vec![],
+            metadata: SessionMetadata {
+                cwd: None,
+                version: None,
+                git_branch: None,
+                model: None,
+                git_branches: vec![],
+            },
+            unknown_entry_types: Default::default(),
+        }
+    }
+
+    #[test]
+    fn attach_counts_each_session_once_and_absorb_unions() {
+        let (a, b, c) = (
+            session("a", &["Bash", "Edit"]),
+            session("b", &["Bash"]),
+            session("c", &["Read", "Bash"]),
+        );
+        let mut log = ToolLog::new();
+        let keep = key(&Scope::Global, "deploy-flow");
+        assert!(attach(log.entry(keep.clone()).or_default(), &[&a, &b]));
+        assert!(!attach(log.get_mut(&keep).unwrap(), &[&a]), "already recorded");
+        assert_eq!(
+            histogram(&log[&keep]),
+            vec![("Bash".to_string(), 2), ("Edit".to_string(), 1)]
+        );
+
+        let other = key(&Scope::Project("app".to_string()), "ship-it");
+        assert_eq!(other, "project/app/ship-it");
+        attach(log.entry(other.clone()).or_default(), &[&b, &c]);
+        assert!(absorb(&mut log, &keep, &other));
+        assert!(!log.contains_key(&other));
+        assert_eq!(compact(&log[&keep], 2), "Bash×3, Edit×1 (of 3 sessions)");
+        assert_eq!(compact(&SessionTools::new(), 5), "");
+    }
+
+    #[test]
+    fn orphans_are_entries_without_a_node_file() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::open(tmp.path());
+        store.ensure_layout().unwrap();
+        let kept = store.node_path(&Scope::Global, "deploy-flow");
+        std::fs::write(&kept, "not even a valid node: the file is what counts").unwrap();
+        let mut log = ToolLog::new();
+        for k in ["global/deploy-flow", "global/deleted-by-hand", "project/app/gone", "junk"] {
+            attach(log.entry(k.to_string()).or_default(), &[&session("s", &["Bash"])]);
+        }
+        save(tmp.path(), &log).unwrap();
+
+        let found = orphans(&log, &store);
+        assert_eq!(found, vec!["global/deleted-by-hand", "junk", "project/app/gone"]);
+        assert_eq!(prune(&store).unwrap(), found);
+        let left: Vec<String> = load(tmp.path()).into_keys().collect();
+        assert_eq!(left, vec!["global/deploy-flow"]);
+        assert!(prune(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load(tmp.path()).is_empty());
+        let mut log = ToolLog::new();
+        attach(log.entry("global/x".to_string()).or_default(), &[&session("s", &["Bash"])]);
+        save(tmp.path(), &log).unwrap();
+        assert_eq!(load(tmp.path()), log);
+    }
+}
@@ -0,0 +1,180 @@
+//! Coalescing for hook-spawned background runs. A burst of hook events — a
+//! script looping `claude -p`, a rebase replaying commits while sessions end
+//! — would otherwise spawn one `retro run --background` per event, all but
+//! one losing the lock race. The hook path asks [`claim_spawn`] first:
+//!
+//! - a spawn within [`COALESCE_WINDOW_SECS`] already covers this event (its
+//!   session stays queued and the next trigger drains it);
+//! - a rebase/merge in progress in the session's repo defers the run until
+//!   the operation finishes, so analysis never sees a half-applied history.
+//!
+//! The clock is a parameter so the window is testable.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::CoreError;
+use crate::store::state::RunnerState;
+
+/// Minimum gap between two hook-spawned runs.
+pub const COALESCE_WINDOW_SECS: i64 = 30;
+
+/// What the hook should do about spawning a background run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpawnDecision {
+    /// Spawn; the marker now records `now`.
+    Spawn,
+    /// Another spawn started this many seconds ago.
+    Coalesced(i64),
+    /// The repo at the given path is mid-operation (`rebase`, `merge`, ...).
+    RepoBusy(PathBuf, &'static str),
+}
+
+/// Decide whether a hook may spawn a background run for an event in `cwd`,
+/// recording the spawn marker in runner state when it may.
+pub fn claim_spawn(
+    store_root: &Path,
+    cwd: Option<&Path>,
+    now: DateTime<Utc>,
+) -> Result<SpawnDecision, CoreError> {
+    let decision = spawn_decision(store_root, cwd, now)?;
+    if decision == SpawnDecision::Spawn {
+        let mut state = RunnerState::load(store_root)?;
+        state.last_spawn_at = Some(now.to_rfc3339());
+        state.save(store_root)?;
+    }
+    Ok(decision)
+}
+
+/// [`claim_spawn`]'s decision without recording anything.
+pub fn spawn_decision(
+    store_root: &Path,
+    cwd: Option<&Path>,
+    now: DateTime<Utc>,
+) -> Result<SpawnDecision, CoreError> {
+    if let Some((repo, op)) = cwd.and_then(repo_operation_in_progress) {
+        return Ok(SpawnDecision::RepoBusy(repo, op));
+    }
+    let state = RunnerState::load(store_root)?;
+    let last = state
+        .last_spawn_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    // A marker from the future (clock change) never blocks.
+    let window = chrono::Duration::seconds(COALESCE_WINDOW_SECS);
+    if let Some(last) = last
+        .map(|t| t.with_timezone(&Utc))
+        .filter(|t| crate::util::within_window(*t, now, window))
+    {
+        return Ok(SpawnDecision::Coalesced(crate::util::elapsed(last, now).num_seconds()));
+    }
+    Ok(SpawnDecision::Spawn)
+}
+
+/// The git operation in progress in the repository containing `dir`, if any:
+/// `(working tree root, "rebase" | "merge" | "cherry-pick" | "revert")`.
+/// Handles worktrees and submodules, whose `.git` is a `gitdir:` file.
+pub fn repo_operation_in_progress(dir: &Path) -> Option<(PathBuf, &'static str)> {
+    let (root, git_dir) = find_git_dir(dir)?;
+    let markers: [(&str, &'static str); 5] = [
+        ("rebase-merge", "rebase"),
+        ("rebase-apply", "rebase"),
+        ("MERGE_HEAD", "merge"),
+        ("CHERRY_PICK_HEAD", "cherry-pick"),
+        ("REVERT_HEAD", "revert"),
+    ];
+    markers
+        .iter()
+        .find(|(marker, _)| git_dir.join(marker).exists())
+        .map(|(_, op)| (root, *op))
+}
+
+/// Nearest ancestor of `dir` with a `.git`, and its resolved git directory.
+fn find_git_dir(dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    for ancestor in dir.ancestors() {
+        let dot_git = ancestor.join(".git");
+        if dot_git.is_dir() {
+            return Some((ancestor.to_path_buf(), dot_git));
+        }
+        if dot_git.is_file() {
+            let content = std::fs::read_to_string(&dot_git).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            return Some((ancestor.to_path_buf(), ancestor.join(target)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_780_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn spawns_are_coalesced_within_the_window() {
+        let store = TempDir::new().unwrap();
+        assert_eq!(claim_spawn(store.path(), None, at(0)).unwrap(), SpawnDecision::Spawn);
+        assert_eq!(
+            claim_spawn(store.path(), None, at(10)).unwrap(),
+            SpawnDecision::Coalesced(10)
+        );
+        // Coalesced claims don't extend the window.
+        assert_eq!(
+            claim_spawn(store.path(), None, at(COALESCE_WINDOW_SECS)).unwrap(),
+            SpawnDecision::Spawn
+        );
+        // A marker in the future (clock moved back) doesn't block.
+        let back = at(COALESCE_WINDOW_SECS) - Duration::seconds(600);
+        assert_eq!(claim_spawn(store.path(), None, back).unwrap(), SpawnDecision::Spawn);
+    }
+
+    #[test]
+    fn rebase_and_merge_in_progress_defer_the_spawn() {
+        let store = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let sub = repo.path().join("src/deep");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::create_dir_all(repo.path().join(".git")).unwrap();
+        assert_eq!(repo_operation_in_progress(&sub), None);
+
+        std::fs::create_dir_all(repo.path().join(".git/rebase-merge")).unwrap();
+        assert_eq!(
+            claim_spawn(store.path(), Some(&sub), at(0)).unwrap(),
+            SpawnDecision::RepoBusy(repo.path().to_path_buf(), "rebase")
+        );
+        assert!(
+            RunnerState::load(store.path()).unwrap().last_spawn_at.is_none(),
+            "a deferred spawn doesn't claim the window"
+        );
+
+        std::fs::remove_dir(repo.path().join(".git/rebase-merge")).unwrap();
+        std::fs::write(repo.path().join(".git/MERGE_HEAD"), "abc\n").unwrap();
+        assert_eq!(
+            repo_operation_in_progress(&sub).map(|(_, op)| op),
+            Some("merge")
+        );
+    }
+
+    #[test]
+    fn worktree_gitdir_files_are_followed() {
+        let main = TempDir::new().unwrap();
+        let worktree = TempDir::new().unwrap();
+        let git_dir = main.path().join(".git/worktrees/wt");
+        std::fs::create_dir_all(git_dir.join("rebase-apply")).unwrap();
+        std::fs::write(
+            worktree.path().join(".git"),
+            format!("gitdir: {}\n", git_dir.display()),
+        )
+        .unwrap();
+        assert_eq!(
+            repo_operation_in_progress(worktree.path()),
+            Some((worktree.path().to_path_buf(), "rebase"))
+        );
+    }
+}
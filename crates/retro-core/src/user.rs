@@ -0,0 +1,203 @@
+//! Who retro runs as. On a shared machine `$HOME` can't be trusted to name
+//! the effective user's home (`sudo` keeps the caller's `$HOME` by default,
+//! `su` without `-` too), and two users' hooks can fire in one shared
+//! checkout. A `$HOME` owned by another user gives way to the passwd entry
+//! of the effective uid; checkout ownership is checked against that uid.
+
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+/// A passwd entry: the parts retro needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub uid: u32,
+    pub name: String,
+    pub home: PathBuf,
+}
+
+/// The uid file access is checked against (and files are created as).
+pub fn effective_uid() -> u32 {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() }
+}
+
+/// The passwd entry for `uid`, if the system has one.
+pub fn lookup(uid: u32) -> Option<Account> {
+    let mut buf = vec![0 as libc::c_char; 4096];
+    // SAFETY: an all-zero passwd is a valid out-parameter for getpwuid_r.
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    loop {
+        // SAFETY: every pointer is valid for the call; buf outlives the
+        // borrowed strings read below.
+        let rc = unsafe {
+            libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result)
+        };
+        if rc == libc::ERANGE && buf.len() < 1 << 20 {
+            buf.resize(buf.len() * 2, 0);
+            continue;
+        }
+        if rc != 0 || result.is_null() {
+            return None;
+        }
+        break;
+    }
+    // SAFETY: on success pw_name and pw_dir point into buf, NUL-terminated.
+    let (name, home) = unsafe {
+        (
+            std::ffi::CStr::from_ptr(pwd.pw_name).to_string_lossy().into_owned(),
+            std::ffi::CStr::from_ptr(pwd.pw_dir).to_string_lossy().into_owned(),
+        )
+    };
+    Some(Account {
+        uid,
+        name,
+        home: PathBuf::from(home),
+    })
+}
+
+/// The effective user's passwd entry.
+pub fn effective() -> Option<Account> {
+    lookup(effective_uid())
+}
+
+/// Display name for `uid`: the login name, or `uid N` without a passwd entry.
+pub fn name_of(uid: u32) -> String {
+    lookup(uid).map_or_else(|| format!("uid {uid}"), |a| a.name)
+}
+
+/// Where a home directory came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Home {
+    pub path: PathBuf,
+    /// Set when `$HOME` was ignored because another user owns it: a
+    /// one-line explanation for a warning.
+    pub mismatch: Option<String>,
+}
+
+/// Resolve the home directory. `$HOME` wins unless `env_home_owner` (the
+/// owner of that directory, `None` when it can't be read) is someone other
+/// than `account` — the `sudo` case — where the passwd entry takes over. A
+/// `$HOME` the user owns (a sandbox `mktemp -d`) is honored. Without
+/// `$HOME`: the passwd entry, else `.`. Pure over its inputs.
+pub fn resolve_home(
+    env_home: Option<&str>,
+    env_home_owner: Option<u32>,
+    account: Option<&Account>,
+) -> Home {
+    let env_home = env_home.filter(|h| !h.is_empty());
+    let honored = |h: &str| Home {
+        path: PathBuf::from(h),
+        mismatch: None,
+    };
+    let Some(account) = account else {
+        return honored(env_home.unwrap_or("."));
+    };
+    let Some(h) = env_home else {
+        return Home {
+            path: account.home.clone(),
+            mismatch: None,
+        };
+    };
+    match env_home_owner {
+        Some(owner) if owner != account.uid => Home {
+            path: account.home.clone(),
+            mismatch: Some(format!(
+                "$HOME ({h}) belongs to {}, not {} — using {}; point HOME at a directory {} \
+                 owns to override",
+                name_of(owner),
+                account.name,
+                account.home.display(),
+                account.name
+            )),
+        },
+        _ => honored(h),
+    }
+}
+
+/// [`resolve_home`] for this process.
+pub fn home() -> Home {
+    let env_home = std::env::var("HOME").ok();
+    let owner = env_home
+        .as_deref()
+        .filter(|h| !h.is_empty())
+        .and_then(|h| std::fs::metadata(h).ok())
+        .map(|m| m.uid());
+    resolve_home(env_home.as_deref(), owner, effective().as_ref())
+}
+
+/// The root of the checkout containing `path` (nearest ancestor with a
+/// `.git`), or `path` itself outside any checkout.
+pub fn checkout_root(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// The owner of the checkout containing `path` when it is not `uid`.
+/// `None` when `uid` owns it or it can't be inspected (never blocks on an
+/// unreadable path).
+pub fn foreign_owner(path: &Path, uid: u32) -> Option<u32> {
+    let owner = std::fs::metadata(checkout_root(path)).ok()?.uid();
+    (owner != uid).then_some(owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn alice() -> Account {
+        Account {
+            uid: 1001,
+            name: "alice".to_string(),
+            home: PathBuf::from("/home/alice"),
+        }
+    }
+
+    #[test]
+    fn env_home_wins_unless_another_user_owns_it() {
+        // a sandbox $HOME the user owns is honored
+        let sandbox = resolve_home(Some("/tmp/tmp.x1"), Some(1001), Some(&alice()));
+        assert_eq!((sandbox.path, sandbox.mismatch), (PathBuf::from("/tmp/tmp.x1"), None));
+        let unreadable = resolve_home(Some("/gone"), None, Some(&alice()));
+        assert_eq!(unreadable.path, PathBuf::from("/gone"));
+
+        // `sudo -u alice` keeping the caller's $HOME
+        let sudo = resolve_home(Some("/home/bob"), Some(u32::MAX - 7), Some(&alice()));
+        assert_eq!(sudo.path, PathBuf::from("/home/alice"));
+        let warning = sudo.mismatch.unwrap();
+        assert!(warning.contains("$HOME (/home/bob) belongs to uid"), "{warning}");
+        assert!(warning.contains("not alice"), "{warning}");
+
+        assert_eq!(resolve_home(None, None, Some(&alice())).path, PathBuf::from("/home/alice"));
+        let no_passwd = resolve_home(Some("/home/bob"), Some(7), None);
+        assert_eq!((no_passwd.path, no_passwd.mismatch), (PathBuf::from("/home/bob"), None));
+        assert_eq!(resolve_home(Some(""), None, None).path, PathBuf::from("."));
+    }
+
+    #[test]
+    fn checkout_owned_by_someone_else_is_foreign() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".git")).unwrap();
+        let nested = tmp.path().join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(checkout_root(&nested), tmp.path());
+
+        let me = std::fs::metadata(tmp.path()).unwrap().uid();
+        assert_eq!(foreign_owner(&nested, me), None);
+        assert_eq!(foreign_owner(&nested, me + 1), Some(me), "another user's view");
+        assert_eq!(foreign_owner(Path::new("/nonexistent/retro"), me + 1), None, "unreadable");
+    }
+
+    #[test]
+    fn effective_user_has_an_entry() {
+        let uid = effective_uid();
+        if let Some(account) = lookup(uid) {
+            assert_eq!(account.uid, uid);
+            assert_eq!(name_of(uid), account.name);
+        }
+        assert_eq!(name_of(u32::MAX - 7), format!("uid {}", u32::MAX - 7));
+    }
+}
@@ -1,8 +1,12 @@
 use crate::errors::CoreError;
-use chrono::Utc;
+use chrono::{DateTime, Local, NaiveDate, Offset, TimeZone, Utc};
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Process-wide display preference set once from the CLI's `--utc` flag.
+static DISPLAY_UTC: AtomicBool = AtomicBool::new(false);
 
 /// Backup a file to the backup directory.
 /// Uses a sanitized path to avoid collisions between files with the same name
@@ -51,11 +55,10 @@ pub fn truncate_str(s: &str, max: usize) -> &str {
 
 /// Shorten a path for display: replace home directory prefix with `~`.
 pub fn shorten_path(path: &str) -> String {
-    if let Some(home) = std::env::var_os("HOME") {
-        let home_str = home.to_string_lossy();
-        if path.starts_with(home_str.as_ref()) {
-            return format!("~{}", &path[home_str.len()..]);
-        }
+    let home = crate::user::home().path;
+    let home_str = home.to_string_lossy();
+    if home_str != "." && path.starts_with(home_str.as_ref()) {
+        return format!("~{}", &path[home_str.len()..]);
     }
     path.to_string()
 }
@@ -65,9 +68,96 @@ pub fn shorten_path_buf(path: &std::path::Path) -> String {
     shorten_path(&path.display().to_string())
 }
 
+/// Render user-facing timestamps in UTC instead of local time (`--utc`).
+/// Storage is always UTC RFC3339; this only affects display.
+pub fn set_display_utc(utc: bool) {
+    DISPLAY_UTC.store(utc, Ordering::Relaxed);
+}
+
+/// Format a timestamp for display: local time by default, UTC under `--utc`.
+/// `relative` renders recent times as "5m ago" (falls back to the absolute
+/// form after a week, where "23d ago" stops being useful).
+pub fn fmt_timestamp(dt: DateTime<Utc>, relative: bool) -> String {
+    if DISPLAY_UTC.load(Ordering::Relaxed) {
+        fmt_timestamp_at(dt, Utc::now(), &Utc, relative)
+    } else {
+        // Each timestamp gets the offset in force at that instant, so one
+        // from the other side of a DST change still shows its wall-clock hour.
+        fmt_timestamp_at(dt, Utc::now(), &Local, relative)
+    }
+}
+
+/// Like [`fmt_timestamp`] for a stored RFC3339 string. Unparseable input is
+/// returned verbatim rather than hidden.
+pub fn fmt_rfc3339(s: &str, relative: bool) -> String {
+    match DateTime::parse_from_rfc3339(s) {
+        Ok(dt) => fmt_timestamp(dt.with_timezone(&Utc), relative),
+        Err(_) => s.to_string(),
+    }
+}
+
+/// Pure core of [`fmt_timestamp`] with the clock and time zone injected.
+pub fn fmt_timestamp_at<Tz: TimeZone>(
+    dt: DateTime<Utc>,
+    now: DateTime<Utc>,
+    tz: &Tz,
+    relative: bool,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    if relative {
+        let age = now.signed_duration_since(dt);
+        // Small negative ages are clock skew between writer and reader.
+        if age < chrono::Duration::minutes(1) && age > chrono::Duration::minutes(-5) {
+            return "just now".to_string();
+        }
+        if age > chrono::Duration::zero() {
+            if age < chrono::Duration::hours(1) {
+                return format!("{}m ago", age.num_minutes());
+            }
+            if age < chrono::Duration::days(1) {
+                return format!("{}h ago", age.num_hours());
+            }
+            if age < chrono::Duration::days(7) {
+                return format!("{}d ago", age.num_days());
+            }
+        }
+    }
+    let local = dt.with_timezone(tz);
+    if local.offset().fix().local_minus_utc() == 0 {
+        format!("{} UTC", local.format("%Y-%m-%d %H:%M"))
+    } else {
+        local.format("%Y-%m-%d %H:%M %:z").to_string()
+    }
+}
+
+/// Time from `then` to `now`, clamped at zero. A `then` in the future (the
+/// clock moved back since it was recorded: DST, an NTP correction) has
+/// elapsed no time, never a negative amount.
+pub fn elapsed(then: DateTime<Utc>, now: DateTime<Utc>) -> chrono::Duration {
+    now.signed_duration_since(then).max(chrono::Duration::zero())
+}
+
+/// Whether `last` lies within `window` before `now` (inclusive of `now`
+/// itself). A `last` after `now` never does: a clock that went backwards
+/// must not hold a cooldown open for as long as it jumped.
+pub fn within_window(last: DateTime<Utc>, now: DateTime<Utc>, window: chrono::Duration) -> bool {
+    last <= now && now.signed_duration_since(last) < window
+}
+
+/// `date` minus `days`, saturating at the earliest representable date rather
+/// than panicking on a window wider than the calendar. Negative `days` count
+/// as zero.
+pub fn days_before(date: NaiveDate, days: i64) -> NaiveDate {
+    date.checked_sub_days(chrono::Days::new(days.max(0) as u64))
+        .unwrap_or(NaiveDate::MIN)
+}
+
 /// Log a parse warning to ~/.retro/warnings.log instead of stderr.
 /// Best-effort: silently drops the message if the file can't be opened.
 pub fn log_parse_warning(msg: &str) {
+    log::debug!(target: crate::logging::INGEST, "{msg}");
     let log_path = crate::config::retro_dir().join("warnings.log");
     if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&log_path) {
         let ts = Utc::now().format("%Y-%m-%dT%H:%M:%S");
@@ -75,6 +165,68 @@ pub fn log_parse_warning(msg: &str) {
     }
 }
 
+/// Check that `dir` can be written to, without creating it: probes `dir`
+/// itself if it exists, else its nearest existing ancestor (the directory
+/// `create_dir_all` would have to write into). The error names the path and
+/// the underlying permission problem, so callers can surface it verbatim.
+pub fn check_writable_dir(dir: &Path) -> Result<(), CoreError> {
+    let target = dir
+        .ancestors()
+        .find(|p| p.is_dir())
+        .ok_or_else(|| CoreError::Io(format!("{}: no existing parent directory", dir.display())))?;
+    let probe = target.join(format!(".retro-write-probe-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) => Err(CoreError::Io(format!(
+            "{} is not writable ({e}) — check its ownership and permissions",
+            target.display()
+        ))),
+    }
+}
+
+/// Join `relative` onto `base`, refusing any result outside `base`:
+/// absolute paths and `..` components are rejected outright, and the
+/// nearest existing ancestor of the result is canonicalized so a symlinked
+/// directory inside `base` can't redirect the write elsewhere. `base` itself
+/// may be a symlink (its resolved location is the allowed root).
+pub fn contained_path(base: &Path, relative: &Path) -> Result<PathBuf, CoreError> {
+    use std::path::Component;
+    let escape = |why: &str| {
+        CoreError::Parse(format!(
+            "refusing path {} under {}: {why}",
+            relative.display(),
+            base.display()
+        ))
+    };
+    if relative.as_os_str().is_empty() {
+        return Err(escape("empty path"));
+    }
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            Component::ParentDir => return Err(escape("contains `..`")),
+            Component::RootDir | Component::Prefix(_) => return Err(escape("absolute path")),
+        }
+    }
+    let joined = base.join(relative);
+    let Ok(root) = std::fs::canonicalize(base) else {
+        // Nothing exists yet, so nothing can be symlinked out of it.
+        return Ok(joined);
+    };
+    let existing = joined
+        .ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| std::fs::canonicalize(p).ok())
+        .unwrap_or_else(|| root.clone());
+    if !existing.starts_with(&root) {
+        return Err(escape(&format!("resolves to {}", existing.display())));
+    }
+    Ok(joined)
+}
+
 /// Compute normalized Levenshtein similarity between two strings.
 /// Returns a value in [0.0, 1.0] where 1.0 means identical.
 pub fn normalized_similarity(a: &str, b: &str) -> f64 {
@@ -159,6 +311,139 @@ pub fn strip_code_fences(content: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::FixedOffset;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    /// Central European time around the 2026 spring-forward (01:00 UTC on
+    /// March 29): +01:00 before, +02:00 after. Local 02:00-03:00 that day
+    /// doesn't exist.
+    #[derive(Debug, Clone, Copy)]
+    struct Berlin2026;
+
+    impl Berlin2026 {
+        const WINTER: i32 = 3600;
+        const SUMMER: i32 = 7200;
+
+        fn offset(secs: i32) -> FixedOffset {
+            FixedOffset::east_opt(secs).unwrap()
+        }
+
+        fn at(utc: &chrono::NaiveDateTime) -> FixedOffset {
+            let switch = ts("2026-03-29T01:00:00Z").naive_utc();
+            Self::offset(if *utc < switch { Self::WINTER } else { Self::SUMMER })
+        }
+    }
+
+    impl TimeZone for Berlin2026 {
+        type Offset = FixedOffset;
+        fn from_offset(_: &FixedOffset) -> Self {
+            Berlin2026
+        }
+        fn offset_from_local_date(&self, local: &NaiveDate) -> chrono::LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+        fn offset_from_local_datetime(
+            &self,
+            local: &chrono::NaiveDateTime,
+        ) -> chrono::LocalResult<FixedOffset> {
+            // The switch as wall-clock time on either side of the gap.
+            let gap_start = ts("2026-03-29T02:00:00Z").naive_utc();
+            let gap_end = ts("2026-03-29T03:00:00Z").naive_utc();
+            if *local < gap_start {
+                chrono::LocalResult::Single(Self::offset(Self::WINTER))
+            } else if *local >= gap_end {
+                chrono::LocalResult::Single(Self::offset(Self::SUMMER))
+            } else {
+                chrono::LocalResult::None
+            }
+        }
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Self::at(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+        fn offset_from_utc_datetime(&self, utc: &chrono::NaiveDateTime) -> FixedOffset {
+            Self::at(utc)
+        }
+    }
+
+    #[test]
+    fn test_fmt_timestamp_uses_the_offset_of_each_instant() {
+        // Formatted in summer: the winter timestamp must keep its +01:00.
+        let now = ts("2026-07-01T12:00:00Z");
+        let winter = fmt_timestamp_at(ts("2026-03-01T03:00:00Z"), now, &Berlin2026, false);
+        assert_eq!(winter, "2026-03-01 04:00 +01:00");
+        let summer = fmt_timestamp_at(ts("2026-03-29T03:00:00Z"), now, &Berlin2026, false);
+        assert_eq!(summer, "2026-03-29 05:00 +02:00");
+
+        // The zone agrees with itself in both directions.
+        let local = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let back = Berlin2026.from_local_datetime(&local("2026-03-29 05:00")).unwrap();
+        assert_eq!(back.with_timezone(&Utc), ts("2026-03-29T03:00:00Z"));
+        assert!(Berlin2026.from_local_datetime(&local("2026-03-29 02:30")).single().is_none());
+    }
+
+    #[test]
+    fn test_fmt_timestamp_absolute_applies_offset() {
+        let dt = ts("2026-03-01T03:00:00Z");
+        let now = ts("2026-03-20T00:00:00Z");
+        let cet = FixedOffset::east_opt(3600).unwrap();
+        let pst = FixedOffset::west_opt(8 * 3600).unwrap();
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert_eq!(fmt_timestamp_at(dt, now, &cet, false), "2026-03-01 04:00 +01:00");
+        assert_eq!(fmt_timestamp_at(dt, now, &pst, false), "2026-02-28 19:00 -08:00");
+        assert_eq!(fmt_timestamp_at(dt, now, &utc, false), "2026-03-01 03:00 UTC");
+    }
+
+    #[test]
+    fn test_fmt_timestamp_relative_buckets() {
+        let now = ts("2026-03-20T12:00:00Z");
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let fmt = |s: &str| fmt_timestamp_at(ts(s), now, &utc, true);
+        assert_eq!(fmt("2026-03-20T11:59:30Z"), "just now");
+        assert_eq!(fmt("2026-03-20T12:02:00Z"), "just now", "small skew");
+        assert_eq!(fmt("2026-03-20T11:15:00Z"), "45m ago");
+        assert_eq!(fmt("2026-03-20T07:00:00Z"), "5h ago");
+        assert_eq!(fmt("2026-03-17T12:00:00Z"), "3d ago");
+        // Older than a week, or well in the future: absolute.
+        assert_eq!(fmt("2026-03-01T12:00:00Z"), "2026-03-01 12:00 UTC");
+        assert_eq!(fmt("2026-03-21T12:00:00Z"), "2026-03-21 12:00 UTC");
+    }
+
+    #[test]
+    fn test_elapsed_and_window_treat_future_timestamps_as_fresh_clock() {
+        let now = ts("2026-03-29T03:00:00Z");
+        let ten_min = chrono::Duration::minutes(10);
+        // Recorded before a one-hour backwards jump: an hour "ahead" of now.
+        let future = ts("2026-03-29T04:00:00Z");
+        assert_eq!(elapsed(future, now), chrono::Duration::zero());
+        assert!(!within_window(future, now, ten_min), "clock went backwards");
+
+        assert_eq!(elapsed(now, now), chrono::Duration::zero());
+        assert!(within_window(now, now, ten_min), "equal timestamps are within");
+        assert!(!within_window(now, now, chrono::Duration::zero()), "empty window");
+
+        let recent = ts("2026-03-29T02:55:00Z");
+        assert_eq!(elapsed(recent, now), chrono::Duration::minutes(5));
+        assert!(within_window(recent, now, ten_min));
+        assert!(!within_window(ts("2026-03-29T02:50:00Z"), now, ten_min), "edge excluded");
+    }
+
+    #[test]
+    fn test_days_before_saturates_on_extreme_windows() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        assert_eq!(days_before(today, 7), NaiveDate::from_ymd_opt(2026, 3, 13).unwrap());
+        assert_eq!(days_before(today, 0), today);
+        assert_eq!(days_before(today, -3), today, "negative window is empty");
+        assert_eq!(days_before(today, i64::MAX), NaiveDate::MIN);
+        assert_eq!(days_before(today, u32::MAX as i64), NaiveDate::MIN);
+    }
+
+    #[test]
+    fn test_fmt_rfc3339_passes_through_garbage() {
+        assert_eq!(fmt_rfc3339("not a time", false), "not a time");
+    }
 
     #[test]
     fn test_identical_strings() {
@@ -245,7 +530,7 @@ mod tests {
 
     #[test]
     fn test_shorten_path_replaces_home() {
-        let home = std::env::var("HOME").unwrap();
+        let home = crate::user::home().path.display().to_string();
         let input = format!("{home}/projects/foo");
         assert_eq!(shorten_path(&input), "~/projects/foo");
     }
@@ -257,8 +542,62 @@ mod tests {
 
     #[test]
     fn test_shorten_path_buf_works() {
-        let home = std::env::var("HOME").unwrap();
-        let p = std::path::PathBuf::from(format!("{home}/.retro/retro.db"));
+        let home = crate::user::home().path;
+        let p = home.join(".retro/retro.db");
         assert_eq!(shorten_path_buf(&p), "~/.retro/retro.db");
     }
+
+    #[test]
+    fn test_check_writable_dir_probes_nearest_existing_ancestor() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert!(check_writable_dir(tmp.path()).is_ok());
+        assert!(check_writable_dir(&tmp.path().join("not/yet/created")).is_ok());
+        assert!(!tmp.path().join("not").exists(), "check must not create dirs");
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0, "probe left behind");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_writable_dir_reports_read_only_path() {
+        use std::os::unix::fs::PermissionsExt;
+        // root ignores permission bits — nothing to observe there.
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ro = tmp.path().join("ro");
+        std::fs::create_dir(&ro).unwrap();
+        std::fs::set_permissions(&ro, std::fs::Permissions::from_mode(0o555)).unwrap();
+        let err = check_writable_dir(&ro.join(".retro")).unwrap_err().to_string();
+        std::fs::set_permissions(&ro, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(err.contains(&ro.display().to_string()), "got: {err}");
+        assert!(err.contains("not writable"), "got: {err}");
+    }
+
+    #[test]
+    fn test_contained_path_refuses_traversal_absolute_and_symlinked_parents() {
+        let base = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let ok = contained_path(base.path(), Path::new("projects/app/rule.md")).unwrap();
+        assert_eq!(ok, base.path().join("projects/app/rule.md"));
+
+        for bad in ["../../.ssh/authorized_keys", "global/../../x.md", "/etc/passwd", ""] {
+            let err = contained_path(base.path(), Path::new(bad)).unwrap_err();
+            assert!(err.to_string().contains("refusing path"), "{bad}: {err}");
+        }
+
+        std::fs::create_dir_all(base.path().join("projects")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), base.path().join("projects/evil")).unwrap();
+        let err = contained_path(base.path(), Path::new("projects/evil/rule.md")).unwrap_err();
+        assert!(err.to_string().contains("resolves to"), "{err}");
+        std::os::unix::fs::symlink(outside.path().join("keys"), base.path().join("link.md"))
+            .unwrap();
+        std::fs::write(outside.path().join("keys"), "").unwrap();
+        assert!(contained_path(base.path(), Path::new("link.md")).is_err());
+
+        // A symlinked base is fine: its target is the allowed root.
+        let linked = outside.path().join("store-link");
+        std::os::unix::fs::symlink(base.path(), &linked).unwrap();
+        assert!(contained_path(&linked, Path::new("projects/app/rule.md")).is_ok());
+    }
 }
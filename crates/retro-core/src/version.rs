@@ -0,0 +1,264 @@
+//! Version skew. The store's `store.toml` (committed, shared by every
+//! machine syncing the repo) records the newest retro that wrote it; a
+//! binary older than that is out of date and says so in `retro status` and
+//! the session briefing. Opt-in (`[updates] check_github`), the newest
+//! GitHub release is looked up at most once a day and cached in runner
+//! state. The network side never fails loudly: no answer means no notice.
+//!
+//! [`BUILD`] is what `build.rs` recorded about this binary (commit, date,
+//! target, rustc) for `retro version` and the store marker.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::Config;
+use crate::errors::CoreError;
+use crate::store::state::RunnerState;
+
+/// This binary's version.
+pub const CURRENT: &str = env!("CARGO_PKG_VERSION");
+
+/// How this binary was built. Fields `build.rs` couldn't determine read
+/// `unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// Short hash of the commit built from.
+    pub commit: &'static str,
+    /// `YYYY-MM-DD` (UTC).
+    pub build_date: &'static str,
+    /// Target triple.
+    pub target: &'static str,
+    /// `rustc --version` of the compiler.
+    pub rustc: &'static str,
+}
+
+pub const BUILD: BuildInfo = BuildInfo {
+    version: CURRENT,
+    commit: env!("RETRO_BUILD_COMMIT"),
+    build_date: env!("RETRO_BUILD_DATE"),
+    target: env!("RETRO_BUILD_TARGET"),
+    rustc: env!("RETRO_BUILD_RUSTC"),
+};
+
+/// `retro --version`: the version, then the build on the same line.
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("RETRO_BUILD_COMMIT"),
+    " ",
+    env!("RETRO_BUILD_DATE"),
+    ", ",
+    env!("RETRO_BUILD_TARGET"),
+    ", ",
+    env!("RETRO_BUILD_RUSTC"),
+    ")"
+);
+
+impl BuildInfo {
+    /// The commit, when `build.rs` found one.
+    pub fn known_commit(&self) -> Option<&'static str> {
+        Some(self.commit).filter(|c| *c != "unknown")
+    }
+}
+
+/// GitHub API endpoint for the newest release.
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/ImanHashemi/retro/releases/latest";
+
+/// Minimum gap between two release checks.
+pub const CHECK_INTERVAL_HOURS: i64 = 24;
+
+/// `1.2.3` / `v1.2.3` / `1.2.3-beta.1` -> `[1, 2, 3]`. Pre-release and build
+/// suffixes are ignored; `None` for anything that isn't dotted numbers.
+pub fn parse(version: &str) -> Option<Vec<u64>> {
+    let v = version.trim();
+    let v = v.strip_prefix('v').unwrap_or(v);
+    let core = v.split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Whether `candidate` is a strictly newer version than `than`. Unparseable
+/// versions are never newer.
+pub fn is_newer(candidate: &str, than: &str) -> bool {
+    match (parse(candidate), parse(than)) {
+        (Some(mut a), Some(mut b)) => {
+            let len = a.len().max(b.len());
+            a.resize(len, 0);
+            b.resize(len, 0);
+            a > b
+        }
+        _ => false,
+    }
+}
+
+/// Warning when the store was last written by a newer retro than `binary`.
+/// The writer's commit, when the store recorded one, names the exact build.
+pub fn skew_warning(
+    binary: &str,
+    store_written_by: &str,
+    writer_commit: Option<&str>,
+) -> Option<String> {
+    is_newer(store_written_by, binary).then(|| {
+        format!(
+            "this retro is v{binary} but the store was written by v{}{} — upgrade retro",
+            store_written_by.trim_start_matches('v'),
+            writer_commit.map(|c| format!(" ({c})")).unwrap_or_default()
+        )
+    })
+}
+
+/// [`skew_warning`] for this binary against the store at `store_root`.
+pub fn store_skew(store_root: &Path) -> Option<String> {
+    let meta = crate::store::meta::StoreMeta::load(store_root).ok()??;
+    skew_warning(CURRENT, &meta.written_by, meta.commit.as_deref())
+}
+
+/// Whether a release check is due: never checked, checked over
+/// [`CHECK_INTERVAL_HOURS`] ago, or "checked" in the future (clock change).
+pub fn check_due(last_checked: Option<&str>, now: DateTime<Utc>) -> bool {
+    let window = chrono::Duration::hours(CHECK_INTERVAL_HOURS);
+    !last_checked
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| crate::util::within_window(t.with_timezone(&Utc), now, window))
+}
+
+/// "retro vX.Y available" when the cached latest release is newer than this
+/// binary. Never touches the network.
+pub fn cached_notice(state: &RunnerState) -> Option<String> {
+    let latest = state.latest_release.as_deref()?;
+    is_newer(latest, CURRENT)
+        .then(|| format!("retro v{} available", latest.trim_start_matches('v')))
+}
+
+/// Refresh the cached latest release when `[updates] check_github` is on
+/// and a check is due, then return [`cached_notice`]. `fetch` performs the
+/// lookup; a failed lookup still counts as a check, so an offline machine
+/// asks once a day, not on every call.
+pub fn check_for_update(
+    store_root: &Path,
+    config: &Config,
+    now: DateTime<Utc>,
+    fetch: impl FnOnce() -> Option<String>,
+) -> Result<Option<String>, CoreError> {
+    if !config.updates.check_github {
+        return Ok(None);
+    }
+    let mut state = RunnerState::load(store_root)?;
+    if check_due(state.update_checked_at.as_deref(), now) {
+        let latest = fetch();
+        // Re-load: the lookup can take seconds, and a hook may have written
+        // state meanwhile.
+        state = RunnerState::load(store_root)?;
+        state.update_checked_at = Some(now.to_rfc3339());
+        if latest.is_some() {
+            state.latest_release = latest;
+        }
+        state.save(store_root)?;
+    }
+    Ok(cached_notice(&state))
+}
+
+/// Ask GitHub for the newest release tag: `curl` with a short timeout.
+/// `None` on any failure (offline, rate-limited, no curl).
+pub fn fetch_latest_release() -> Option<String> {
+    let out = std::process::Command::new("curl")
+        .args(["-fsSL", "--max-time", "3", "-H", "Accept: application/vnd.github+json"])
+        .arg(LATEST_RELEASE_URL)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let body: serde_json::Value = serde_json::from_slice(&out.stdout).ok()?;
+    let tag = body.get("tag_name")?.as_str()?;
+    parse(tag).map(|_| tag.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(parse("v1.10.0-rc.1"), Some(vec![1, 10, 0]));
+        assert_eq!(parse("banana"), None);
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(is_newer("v2.0", "1.99.99"));
+        assert!(!is_newer("1.2.0", "1.2"), "trailing zeros are equal");
+        assert!(!is_newer("1.2.3-beta", "1.2.3"));
+        assert!(!is_newer("garbage", "0.1.0"));
+        assert!(!is_newer("0.1.0", "garbage"));
+    }
+
+    #[test]
+    fn skew_warns_only_when_the_store_is_newer() {
+        let warning = skew_warning("0.3.0", "0.4.1", None).unwrap();
+        assert!(warning.contains("this retro is v0.3.0"), "{warning}");
+        assert!(warning.contains("written by v0.4.1 —"), "{warning}");
+        let warning = skew_warning("0.3.0", "0.4.1", Some("0123abcd4567")).unwrap();
+        assert!(warning.contains("written by v0.4.1 (0123abcd4567)"), "{warning}");
+        assert_eq!(skew_warning("0.4.1", "0.4.1", None), None);
+        assert_eq!(skew_warning("0.5.0", "0.4.1", None), None, "an older writer is fine");
+    }
+
+    #[test]
+    fn build_info_serializes_every_field() {
+        let json = serde_json::to_value(BUILD).unwrap();
+        for key in ["version", "commit", "build_date", "target", "rustc"] {
+            let value = json[key].as_str().unwrap_or_default();
+            assert!(!value.is_empty(), "{key} missing: {json}");
+        }
+        assert_eq!(json["version"], CURRENT);
+        assert_eq!(BUILD.build_date.len(), 10, "YYYY-MM-DD: {}", BUILD.build_date);
+        assert!(LONG_VERSION.starts_with(&format!("{CURRENT} (")), "{LONG_VERSION}");
+    }
+
+    #[test]
+    fn checks_are_throttled_to_once_a_day() {
+        let now = at("2026-07-06T12:00:00Z");
+        assert!(check_due(None, now));
+        assert!(check_due(Some("not a time"), now));
+        assert!(!check_due(Some("2026-07-06T01:00:00Z"), now));
+        assert!(check_due(Some("2026-07-05T11:59:00Z"), now));
+        assert!(check_due(Some("2026-07-07T12:00:00Z"), now), "clock moved back");
+    }
+
+    #[test]
+    fn check_for_update_caches_the_answer_and_is_opt_in() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        let calls = Cell::new(0);
+        let fetch = || {
+            calls.set(calls.get() + 1);
+            Some("v999.0.0".to_string())
+        };
+        let now = at("2026-07-06T12:00:00Z");
+        assert_eq!(check_for_update(tmp.path(), &config, now, fetch).unwrap(), None);
+        assert_eq!(calls.get(), 0, "off by default: no lookup");
+
+        config.updates.check_github = true;
+        let notice = check_for_update(tmp.path(), &config, now, fetch).unwrap();
+        assert_eq!(notice.as_deref(), Some("retro v999.0.0 available"));
+        let later = at("2026-07-06T20:00:00Z");
+        let again = check_for_update(tmp.path(), &config, later, fetch).unwrap();
+        assert_eq!(again, notice, "served from the cache");
+        assert_eq!(calls.get(), 1);
+
+        // A failed lookup next day keeps the last answer and still throttles.
+        let next_day = at("2026-07-07T13:00:00Z");
+        let failed = check_for_update(tmp.path(), &config, next_day, || None).unwrap();
+        assert_eq!(failed, notice);
+        let state = RunnerState::load(tmp.path()).unwrap();
+        assert_eq!(state.update_checked_at, Some(next_day.to_rfc3339()));
+        assert_eq!(cached_notice(&RunnerState::default()), None);
+    }
+}